@@ -35,6 +35,16 @@ pub enum Error {
     #[error("Service error {0}")]
     ServiceError(String),
 
+    /// A SPARQL endpoint responded with an HTTP status code other than 2xx,
+    /// the body of the response is truncated if it was long
+    #[error("SPARQL endpoint {endpoint} responded with HTTP status {status}: {body}\n{statement}")]
+    SPARQLEndpointError {
+        status:    u16,
+        endpoint:  String,
+        body:      String,
+        statement: String,
+    },
+
     #[error("No event")]
     NoEvent,
 
@@ -156,10 +166,118 @@ pub enum Error {
     #[error("Encountered SPARQL error \"{source:}\" in\n{statement:}")]
     SPARQLStatementError {
         #[source]
-        source:    spargebra::ParseError,
+        source:    spargebra::SparqlSyntaxError,
+        statement: String,
+        /// The line of the error in the statement (including its prefixes),
+        /// if known
+        line:      Option<usize>,
+        /// The column of the error in the statement, if known
+        column:    Option<usize>,
+    },
+
+    /// A SPARQL statement of an unexpected type was passed in, for instance an
+    /// update-statement where a SELECT query was expected
+    #[error("Expected a SPARQL {expected} statement but got:\n{statement:}")]
+    UnexpectedSPARQLStatementType { expected: String, statement: String },
+
+    /// A SPARQL statement uses features that are not part of the SPARQL
+    /// flavor that it is declared with
+    #[error("SPARQL statement is declared as {flavor} but uses {features}:\n{statement:}")]
+    UnsupportedSPARQLFeatures {
+        flavor:    String,
+        features:  String,
         statement: String,
     },
 
+    /// A SPARQL statement uses a feature that the store it is evaluated
+    /// against does not support
+    #[error("{store} does not support {feature}:\n{statement:}")]
+    UnsupportedByStore {
+        store:     String,
+        feature:   String,
+        statement: String,
+    },
+
+    /// A SPARQL `SERVICE` clause refers to an endpoint that is not
+    /// registered with the federation that evaluates the statement
+    #[error("Unknown SPARQL endpoint <{endpoint}> in SERVICE clause of:\n{statement:}")]
+    UnknownSPARQLEndpoint { endpoint: String, statement: String },
+
+    /// An `# ekg:` directive in the comments of a SPARQL statement is invalid
+    #[error("Invalid directive on line {line} of SPARQL statement: {message}")]
+    InvalidStatementDirective { line: usize, message: String },
+
+    /// A prefixed name in a SPARQL statement uses a prefix that is neither
+    /// declared nor well-known
+    #[error(
+        "Undeclared prefix {prefix} on line {line}, column {column} of SPARQL \
+         statement:\n{statement:}"
+    )]
+    UndeclaredPrefix {
+        prefix:    String,
+        line:      usize,
+        column:    usize,
+        statement: String,
+    },
+
+    /// A value was bound to a variable that does not occur in the SPARQL
+    /// statement
+    #[error("Variable ?{variable} does not occur in SPARQL statement:\n{statement:}")]
+    UnknownSPARQLBinding { variable: String, statement: String },
+
+    /// No value was bound to a variable that is declared with an
+    /// `# ekg:param` directive
+    #[error("No value bound to parameter ?{variable} of SPARQL statement:\n{statement:}")]
+    MissingSPARQLBinding { variable: String, statement: String },
+
+    /// The value bound to a variable cannot be used in a SPARQL statement or
+    /// does not have the datatype of its `# ekg:param` directive
+    #[error("Invalid value for variable ?{variable}: {message}")]
+    InvalidSPARQLBinding { variable: String, message: String },
+
+    /// The response of a SPARQL endpoint could not be decoded
+    #[error("Invalid SPARQL results: {0}")]
+    InvalidSPARQLResults(String),
+
+    /// RDF content (such as the response to a SPARQL CONSTRUCT query) could
+    /// not be parsed
+    #[error("Invalid RDF: {0}")]
+    InvalidRDF(String),
+
+    /// The authentication method configured for a SPARQL endpoint is not
+    /// supported
+    #[error("Unknown SPARQL endpoint authentication method {0}")]
+    UnknownAuthenticationMethod(String),
+
+    /// A prefixed name such as `story:Story` could not be expanded because
+    /// its prefix is not declared
+    #[error("The prefix of {0} is not declared")]
+    UnknownPrefix(String),
+
+    /// A database or SPARQL parameter with an unknown key
+    #[error("Unknown parameter {0}")]
+    UnknownParameter(String),
+
+    /// A known parameter that the engine version in use does not accept
+    #[error("Parameter {key} is not supported by {version}")]
+    UnsupportedParameter { key: String, version: String },
+
+    /// A value outside the domain of a parameter
+    #[error("Invalid value \"{value}\" for parameter {key}, expected {expected}")]
+    InvalidParameterValue {
+        key:      String,
+        value:    String,
+        expected: String,
+    },
+
+    /// A file or document with prefix declarations could not be read
+    #[error("Invalid prefixes: {0}")]
+    InvalidPrefixes(String),
+
+    /// The RDF format of a file could not be derived from its extension
+    #[error("Unknown RDF format of file {0}")]
+    UnknownRDFFormat(String),
+
     #[error(transparent)]
     FormatError(#[from] core::fmt::Error),
 
@@ -169,6 +287,29 @@ pub enum Error {
     #[error(transparent)]
     WalkError(#[from] ignore::Error),
 
+    /// One or more files of a directory of SPARQL statements could not be
+    /// loaded, with the path of each file and its error
+    #[cfg(feature = "fs")]
+    #[error(
+        "Could not load {} SPARQL statement file(s) from {root}:\n{}",
+        errors.len(),
+        errors.iter().map(|(path, err)| format!("{path}: {err}")).collect::<Vec<_>>().join("\n")
+    )]
+    InvalidStatementLibrary {
+        root:   String,
+        errors: Vec<(String, Error)>,
+    },
+
+    /// A name declared with an `# ekg:name:` directive is already used by
+    /// another statement in the same directory
+    #[error("SPARQL statement name {name} is already declared in {path}")]
+    DuplicateStatementName { name: String, path: String },
+
+    /// There is no statement with the given name or path in a directory of
+    /// SPARQL statements
+    #[error("Unknown SPARQL statement {name} in {root}")]
+    UnknownStatement { name: String, root: String },
+
     #[cfg(feature = "iref")]
     #[error(transparent)]
     IriErrorString(#[from] iref::IriError<String>),
@@ -310,6 +451,22 @@ pub enum Error {
     #[error(transparent)]
     R2D2Error(#[from] r2d2::Error),
 
+    #[cfg(all(feature = "oxigraph-support", not(target_arch = "wasm32")))]
+    #[error(transparent)]
+    OxigraphStorageError(#[from] oxigraph::store::StorageError),
+
+    #[cfg(all(feature = "oxigraph-support", not(target_arch = "wasm32")))]
+    #[error(transparent)]
+    OxigraphLoaderError(#[from] oxigraph::store::LoaderError),
+
+    #[cfg(all(feature = "oxigraph-support", not(target_arch = "wasm32")))]
+    #[error(transparent)]
+    OxigraphQueryError(#[from] oxigraph::sparql::QueryEvaluationError),
+
+    #[cfg(all(feature = "oxigraph-support", not(target_arch = "wasm32")))]
+    #[error(transparent)]
+    OxigraphUpdateError(#[from] oxigraph::sparql::UpdateEvaluationError),
+
     #[error("While {action}: {message}")]
     Exception { action: String, message: String },
     #[error(
@@ -325,6 +482,8 @@ pub enum Error {
     CannotGetAnyArgumentIndexes { query: String },
     #[error("Maximum number of rows ({maxrow}) has been exceeded for query:\n{query}")]
     ExceededMaximumNumberOfRows { maxrow: usize, query: String },
+    #[error("Maximum response size ({max_bytes} bytes) has been exceeded for query:\n{query}")]
+    ExceededMaximumResponseSize { max_bytes: usize, query: String },
     #[error("Could not find a license key")]
     RDFoxLicenseFileNotFound,
     #[error("Unknown resource")]
@@ -357,6 +516,27 @@ pub enum Error {
 
 unsafe impl Send for Error {}
 
+impl Error {
+    /// The HTTP status code that a SPARQL endpoint responded with, if this is
+    /// a [`Error::SPARQLEndpointError`].
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            Error::SPARQLEndpointError { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Whether the SPARQL endpoint rejected the statement itself (HTTP 400),
+    /// typically because of a syntax error.
+    pub fn is_bad_request(&self) -> bool { self.http_status() == Some(400) }
+
+    /// Whether the SPARQL endpoint rejected the credentials (HTTP 401 or 403).
+    pub fn is_unauthorized(&self) -> bool { matches!(self.http_status(), Some(401 | 403)) }
+
+    /// Whether the SPARQL endpoint failed (HTTP 5xx).
+    pub fn is_server_error(&self) -> bool { matches!(self.http_status(), Some(500..=599)) }
+}
+
 #[cfg(all(feature = "salvo", not(target_arch = "wasm32")))]
 #[salvo::async_trait]
 impl salvo::Writer for Error {
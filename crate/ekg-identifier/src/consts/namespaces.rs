@@ -30,3 +30,38 @@ lazy_static! {
     pub static ref NS_USE_CASE: Namespace = Namespace::declare(NS_PREFIX_USE_CASE, NS_IRI_USE_CASE.as_str().try_into().unwrap()).unwrap();
     pub static ref NS_STORY_IMPL_SPARQL: Namespace = Namespace::declare(NS_PREFIX_STORY_IMPL_SPARQL, NS_IRI_STORY_IMPL_SPARQL.as_str().try_into().unwrap()).unwrap();
 }
+
+/// The well-known namespace with the given prefix (including its colon, as
+/// in `"rdf:"`), if any.
+pub fn well_known_namespace(prefix: &str) -> Option<&'static Namespace> {
+    let namespace: &Namespace = match prefix {
+        NS_PREFIX_BN => &NS_BN,
+        NS_PREFIX_SD => &NS_SD,
+        NS_PREFIX_WF => &NS_WF,
+        NS_PREFIX_UI => &NS_UI,
+        NS_PREFIX_SBE => &NS_SBE,
+        NS_PREFIX_API => &NS_API,
+        NS_PREFIX_DCT => &NS_DCT,
+        NS_PREFIX_OWL => &NS_OWL,
+        NS_PREFIX_RAW => &NS_RAW,
+        NS_PREFIX_RDF => &NS_RDF,
+        NS_PREFIX_XSD => &NS_XSD,
+        NS_PREFIX_DCAT => &NS_DCAT,
+        NS_PREFIX_JIRA => &NS_JIRA,
+        NS_PREFIX_PROV => &NS_PROV,
+        NS_PREFIX_RDFS => &NS_RDFS,
+        NS_PREFIX_SDLC => &NS_SDLC,
+        NS_PREFIX_SKOS => &NS_SKOS,
+        NS_PREFIX_EKGMM => &NS_EKGMM,
+        NS_PREFIX_RDFOX => &NS_RDFOX,
+        NS_PREFIX_STORY => &NS_STORY,
+        NS_PREFIX_DATAOPS => &NS_DATAOPS,
+        NS_PREFIX_CONCEPT => &NS_CONCEPT,
+        NS_PREFIX_DATASET => &NS_DATASET,
+        NS_PREFIX_PERSONA => &NS_PERSONA,
+        NS_PREFIX_USE_CASE => &NS_USE_CASE,
+        NS_PREFIX_STORY_IMPL_SPARQL => &NS_STORY_IMPL_SPARQL,
+        _ => return None,
+    };
+    Some(namespace)
+}
@@ -36,28 +36,31 @@ impl EkgIdentifierContexts {
 
     // #[cfg(test)]
     pub fn default_test() {
-        std::env::set_var("EKG_BASE_INTERNAL", crate::PLACEHOLDER_BASE_IRI);
-        std::env::set_var(
-            "EKG_ID_BASE_INTERNAL",
-            crate::PLACEHOLDER_ID_BASE_IRI,
-        );
-        std::env::set_var(
-            "EKG_GRAPH_BASE_INTERNAL",
-            crate::PLACEHOLDER_GRAPH_BASE_IRI,
-        );
-        std::env::set_var(
-            "EKG_ONTOLOGY_BASE_INTERNAL",
-            crate::PLACEHOLDER_ONTOLOGY_BASE_IRI,
-        );
-        std::env::set_var("EKG_BASE_EXTERNAL", "http://localhost:3000");
-        std::env::set_var("EKG_ID_BASE_EXTERNAL", "http://localhost:3000/id");
-        std::env::set_var(
-            "EKG_GRAPH_BASE_EXTERNAL",
-            "http://localhost:3000/graph",
-        );
-        std::env::set_var(
-            "EKG_ONTOLOGY_BASE_EXTERNAL",
-            "http://localhost:3000/ontology",
-        );
+        // SAFETY: only used to set up test fixtures before any threads are spawned
+        unsafe {
+            std::env::set_var("EKG_BASE_INTERNAL", crate::PLACEHOLDER_BASE_IRI);
+            std::env::set_var(
+                "EKG_ID_BASE_INTERNAL",
+                crate::PLACEHOLDER_ID_BASE_IRI,
+            );
+            std::env::set_var(
+                "EKG_GRAPH_BASE_INTERNAL",
+                crate::PLACEHOLDER_GRAPH_BASE_IRI,
+            );
+            std::env::set_var(
+                "EKG_ONTOLOGY_BASE_INTERNAL",
+                crate::PLACEHOLDER_ONTOLOGY_BASE_IRI,
+            );
+            std::env::set_var("EKG_BASE_EXTERNAL", "http://localhost:3000");
+            std::env::set_var("EKG_ID_BASE_EXTERNAL", "http://localhost:3000/id");
+            std::env::set_var(
+                "EKG_GRAPH_BASE_EXTERNAL",
+                "http://localhost:3000/graph",
+            );
+            std::env::set_var(
+                "EKG_ONTOLOGY_BASE_EXTERNAL",
+                "http://localhost:3000/ontology",
+            );
+        }
     }
 }
@@ -3,6 +3,8 @@ pub trait NamespaceIRI {
     fn as_str(&self) -> &str;
     /// Returns the length in characters of the namespace IRI
     fn len(&self) -> usize { self.as_str().len() }
+    /// Returns true if the namespace IRI is empty
+    fn is_empty(&self) -> bool { self.as_str().is_empty() }
     /// Returns true if the given IRI is in the namespace
     fn is_in_namespace(&self, iri: &str) -> bool { iri.starts_with(self.as_str()) }
 
@@ -44,9 +44,9 @@ impl Display for Namespace {
 impl Namespace {
     pub fn declare(name: &str, iri: crate::TBoxNamespaceIRI) -> Result<Self, ekg_error::Error> {
         match iri.to_string().chars().last() {
-            Some('/') | Some('#') => Ok(Self { name: name.to_string(), iri }),
+            Some('/') | Some('#') | Some(':') => Ok(Self { name: name.to_string(), iri }),
             _ => {
-                tracing::error!("{} does not end with either / or #", iri);
+                tracing::error!("{} does not end with either /, # or :", iri);
                 Err(ekg_error::Error::IncorrectBaseIRI { iri: iri.to_string() })
             },
         }
@@ -57,9 +57,11 @@ impl Namespace {
     /// everything we need.
     pub fn declare_iref_iri(name: &str, iri: &iref::Iri) -> Result<Self, ekg_error::Error> {
         match iri.to_string().chars().last() {
-            Some('/') | Some('#') => Ok(Self { name: name.to_string(), iri: iri.try_into()? }),
+            Some('/') | Some('#') | Some(':') => {
+                Ok(Self { name: name.to_string(), iri: iri.try_into()? })
+            },
             _ => {
-                tracing::error!("{} does not end with either / or #", iri);
+                tracing::error!("{} does not end with either /, # or :", iri);
                 Err(ekg_error::Error::IncorrectBaseIRI { iri: iri.to_string() })
             },
         }
@@ -77,9 +79,9 @@ impl Namespace {
         name: &str,
     ) -> Result<iri_string::types::IriReferenceString, ekg_error::Error> {
         let iri_str = match self.iri.to_string().chars().last().unwrap() {
-            '/' | '#' => format!("{}{name}", self.iri),
+            '/' | '#' | ':' => format!("{}{name}", self.iri),
             _ => {
-                panic!("{} does not end with either / or #", self.iri)
+                panic!("{} does not end with either /, # or :", self.iri)
             },
         };
 
@@ -117,8 +119,7 @@ mod tests {
             "test:",
             iri_string::types::IriReferenceString::try_from("http://whatever.kom/test#")
                 .unwrap()
-                .try_into()
-                .unwrap(),
+                .into(),
         )
         .unwrap();
         let x = namespace.with_local_name("abc")?;
@@ -136,8 +137,7 @@ mod tests {
             "test:",
             iri_string::types::IriReferenceString::try_from("http://whatever.kom/test/")
                 .unwrap()
-                .try_into()
-                .unwrap(),
+                .into(),
         )
         .unwrap();
         let x = namespace.with_local_name("abc")?;
@@ -158,4 +158,14 @@ mod tests {
         assert_eq!(x.as_str(), "http://whatever.kom/test/abc/");
         Ok(())
     }
+
+    #[test_log::test]
+    fn test_urn_prefix() -> Result<(), ekg_error::Error> {
+        let namespace = crate::Namespace::declare_from_str("test:", "urn:example:")?;
+        let x = namespace.with_local_name("abc")?;
+
+        assert_eq!(x.to_string().as_str(), "urn:example:abc");
+        assert!(crate::Namespace::declare_from_str("test:", "urn:example").is_err());
+        Ok(())
+    }
 }
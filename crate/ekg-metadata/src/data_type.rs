@@ -27,6 +27,7 @@ static DATA_TYPE_MAP: phf::Map<&'static str, DataType> = phf_map! {
     "http://www.w3.org/2001/XMLSchema#negativeInteger" => DataType::NegativeInteger,
     "http://www.w3.org/2001/XMLSchema#nonNegativeInteger" => DataType::NonNegativeInteger,
     "http://www.w3.org/2001/XMLSchema#nonPositiveInteger" => DataType::NonPositiveInteger,
+    "http://www.w3.org/2001/XMLSchema#positiveInteger" => DataType::PositiveInteger,
     "http://www.w3.org/2001/XMLSchema#short" => DataType::Short,
     "http://www.w3.org/2001/XMLSchema#string" => DataType::String,
     "http://www.w3.org/2001/XMLSchema#time" => DataType::Time,
@@ -141,16 +142,20 @@ impl DataType {
     }
 
     pub fn as_xsd_iri_str(&self) -> &'static str {
-        DATA_TYPE_MAP
-            .entries()
-            .find_map(
-                |(key, val)| {
-                    if val == self { Some(key) } else { None }
-                },
-            )
+        self.try_as_xsd_iri_str()
             .unwrap_or_else(|| panic!("You've managed to create an unknown DataType instance"))
     }
 
+    /// The IRI of this datatype, or `None` for the datatypes that do not have
+    /// one such as [`DataType::PlainLiteral`].
+    pub fn try_as_xsd_iri_str(&self) -> Option<&'static str> {
+        DATA_TYPE_MAP.entries().find_map(
+            |(key, val)| {
+                if val == self { Some(*key) } else { None }
+            },
+        )
+    }
+
     #[inline]
     pub fn is_string(&self) -> bool { matches!(self, DataType::String | DataType::PlainLiteral) }
 
@@ -175,6 +180,27 @@ impl DataType {
     #[inline]
     pub fn is_duration(&self) -> bool { matches!(self, DataType::Duration) }
 
+    /// Is this one of the datatypes whose values [`Literal`](crate::Literal)
+    /// keeps as their lexical form, without interpreting them?
+    #[inline]
+    pub fn is_lexical(&self) -> bool {
+        matches!(
+            self,
+            DataType::Literal |
+                DataType::DateTimeStamp |
+                DataType::Time |
+                DataType::YearMonth |
+                DataType::Year |
+                DataType::MonthDay |
+                DataType::Day |
+                DataType::Month |
+                DataType::YearMonthDuration |
+                DataType::DayTimeDuration |
+                DataType::Double |
+                DataType::Float
+        )
+    }
+
     #[inline]
     pub fn is_signed_integer(&self) -> bool {
         // IRI_TYPES
@@ -185,7 +211,8 @@ impl DataType {
                 DataType::NegativeInteger |
                 DataType::NonPositiveInteger |
                 DataType::Long |
-                DataType::Short
+                DataType::Short |
+                DataType::Byte
         )
     }
 
@@ -0,0 +1,10 @@
+/// Turns full IRIs into prefixed names (CURIEs) such as `rdf:type`, for
+/// instance `ekg_sparql::Prefixes`. Used by
+/// [`crate::Literal::display_turtle_with`]
+/// and [`crate::Term::display_turtle_with`] to show readable IRIs.
+pub trait IriCompactor {
+    /// The given IRI as prefixed name, or `None` if it is not in any of the
+    /// known namespaces or if its local name cannot be written as part of a
+    /// prefixed name.
+    fn compact_iri(&self, iri: &str) -> Option<String>;
+}
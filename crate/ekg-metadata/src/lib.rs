@@ -7,6 +7,7 @@ pub use {
     data_type::DataType,
     ekg_identifier::Namespace,
     graph::{Graph, GraphDisplayIRI},
+    iri_compactor::IriCompactor,
     literal::{Literal, LiteralIdUrlDisplay, LiteralUrlDisplay, LiteralValue},
     predicate::Predicate,
     term::Term,
@@ -17,6 +18,9 @@ mod class;
 pub mod consts;
 mod data_type;
 mod graph;
+mod iri_compactor;
 mod literal;
+#[cfg(feature = "oxigraph-support")]
+pub mod oxigraph;
 mod predicate;
 mod term;
@@ -1,5 +1,3 @@
-#![cfg(test)]
-
 #[cfg(feature = "serde")]
 use serde_json::json;
 use {crate::literal::this::Literal, ekg_identifier::ABoxNamespaceIRI, std::str::FromStr};
@@ -1,6 +1,6 @@
 use {
-    crate::{DataType, LiteralIdUrlDisplay, LiteralUrlDisplay, LiteralValue, Term},
-    ekg_identifier::{iri::NamespaceIRI, ABoxNamespaceIRI},
+    crate::{DataType, IriCompactor, LiteralIdUrlDisplay, LiteralUrlDisplay, LiteralValue, Term},
+    ekg_identifier::{ABoxNamespaceIRI, iri::NamespaceIRI},
     ekg_util::log::LOG_TARGET_DATABASE,
     std::{
         fmt::{Debug, Display, Formatter},
@@ -29,18 +29,20 @@ use {
 pub struct Literal {
     pub data_type: DataType,
     literal_value: LiteralValue,
+    /// The language tag of a [`DataType::PlainLiteral`], if any
+    language:      Option<String>,
 }
 
 impl PartialEq for Literal {
     fn eq(&self, other: &Self) -> bool {
         let data_type = self.data_type;
-        if data_type != other.data_type {
+        if data_type != other.data_type || self.language != other.language {
             return false;
         }
         unsafe {
             if data_type.is_iri() {
                 self.literal_value.iri.as_str() == other.literal_value.iri.as_str()
-            } else if data_type.is_string() {
+            } else if data_type.is_string() || data_type.is_lexical() {
                 self.literal_value.string == other.literal_value.string
             } else if data_type.is_boolean() {
                 self.literal_value.boolean == other.literal_value.boolean
@@ -50,7 +52,7 @@ impl PartialEq for Literal {
                 self.literal_value.unsigned_integer == other.literal_value.unsigned_integer
             } else if data_type.is_blank_node() {
                 self.literal_value.blank_node == other.literal_value.blank_node
-            } else if data_type.is_decimal() {
+            } else if data_type.is_decimal() || data_type.is_duration() {
                 self.literal_value.string == other.literal_value.string
             } else if data_type.is_date() {
                 self.literal_value.date == other.literal_value.date
@@ -70,11 +72,12 @@ impl std::hash::Hash for Literal {
     where H: std::hash::Hasher {
         let data_type = self.data_type;
         data_type.hash(state);
+        self.language.hash(state);
         unsafe {
             #[allow(clippy::if_same_then_else)]
             if data_type.is_iri() {
                 self.literal_value.iri.as_str().hash(state)
-            } else if data_type.is_string() {
+            } else if data_type.is_string() || data_type.is_lexical() {
                 self.literal_value.string.hash(state)
             } else if data_type.is_blank_node() {
                 self.literal_value.blank_node.hash(state)
@@ -108,7 +111,12 @@ impl Debug for Literal {
             if data_type.is_iri() {
                 write!(f, "{}", self)?
             } else if data_type.is_string() {
-                write!(f, "\"{}\"", self.literal_value.string.as_str())?
+                write!(f, "\"{}\"", self.literal_value.string.as_str())?;
+                if let Some(language) = &self.language {
+                    write!(f, "@{language}")?
+                }
+            } else if data_type.is_lexical() {
+                write!(f, "{}", self.literal_value.string.as_str())?
             } else if data_type.is_blank_node() {
                 write!(f, "_:{}", self.literal_value.blank_node.as_str())?
             } else if data_type.is_boolean() {
@@ -119,7 +127,7 @@ impl Debug for Literal {
                 write!(f, "{}", self.literal_value.unsigned_integer)?
             } else if data_type.is_decimal() {
                 write!(f, "{}", self.literal_value.string.as_str())?
-            } else if data_type.is_duration() || data_type.is_date_time_stamp() {
+            } else if data_type.is_duration() {
                 write!(f, "{}", self.literal_value.string.as_str())?
             } else if data_type.is_date() {
                 write!(
@@ -145,7 +153,11 @@ impl Display for Literal {
             write!(f, "_:{}", self.as_string().unwrap().as_str())
         } else if self.data_type.is_string() {
             if let Some(str) = self.as_string() {
-                write!(f, "\"{}\"", str.as_str())
+                write!(f, "\"{}\"", str.as_str())?;
+                match &self.language {
+                    Some(language) => write!(f, "@{language}"),
+                    None => Ok(()),
+                }
             } else {
                 write!(f, "ERROR, could not convert to String")
             }
@@ -175,6 +187,7 @@ impl Clone for Literal {
                 Literal {
                     data_type:     self.data_type,
                     literal_value: LiteralValue::new_iri(iri),
+                    language:      None,
                 }
             } else {
                 todo!("the situation where the iri in a lexical value is empty")
@@ -187,10 +200,21 @@ impl Clone for Literal {
             }
         } else if self.data_type.is_string() {
             if let Some(str) = self.as_str() {
-                Literal::new_string_with_datatype(str, self.data_type).unwrap()
+                Literal {
+                    language: self.language.clone(),
+                    ..Literal::new_string_with_datatype(str, self.data_type).unwrap()
+                }
             } else {
                 todo!("the situation where the string in a lexical value is empty")
             }
+        } else if self.data_type.is_lexical() {
+            Literal {
+                data_type:     self.data_type,
+                literal_value: LiteralValue::new_string(unsafe {
+                    self.literal_value.string.as_str()
+                }),
+                language:      None,
+            }
         } else if self.data_type.is_boolean() {
             if let Some(boolean) = self.as_boolean() {
                 Literal::new_boolean_with_datatype(boolean, self.data_type).unwrap()
@@ -262,7 +286,7 @@ impl serde::Serialize for Literal {
                 serializer.serialize_u64(self.literal_value.unsigned_integer)
             } else if data_type.is_decimal() {
                 serializer.serialize_str(self.literal_value.string.as_str())
-            } else if data_type.is_duration() || data_type.is_date_time_stamp() {
+            } else if data_type.is_duration() || data_type.is_lexical() {
                 serializer.serialize_str(self.literal_value.string.as_str())
             } else if data_type.is_date() {
                 serializer.serialize_str(self.literal_value.date.to_string().as_str())
@@ -288,11 +312,14 @@ impl FromStr for Literal {
     fn from_str(str: &str) -> Result<Self, Self::Err> { Self::new_plain_literal_string(str) }
 }
 
-#[cfg(feature = "oxigraph")]
+/// **Deprecated**: use `Literal::try_from(literal.as_ref())` instead, which
+/// returns an error rather than panicking if the literal has a datatype that
+/// has no [`DataType`], such as `geo:wktLiteral`. (Rust does not support
+/// `#[deprecated]` on trait impls.)
+#[cfg(feature = "oxigraph-support")]
 impl From<oxrdf::Literal> for Literal {
     fn from(value: oxrdf::Literal) -> Self {
-        // TODO: Temporary simplistic implementation
-        Self::from_str(value.value()).unwrap()
+        Self::try_from(value.as_ref()).expect("a literal with a supported datatype")
     }
 }
 
@@ -386,10 +413,11 @@ impl Literal {
             }
         } else if self.data_type.is_decimal() {
             unsafe { Some(self.literal_value.string.as_str()) }
-        } else if self.data_type.is_duration() {
-            unsafe { Some(self.literal_value.string.as_str()) }
-        } else if self.data_type.is_date_time() {
+        } else if self.data_type.is_duration() || self.data_type.is_lexical() {
             unsafe { Some(self.literal_value.string.as_str()) }
+        } else if self.data_type.is_date() || self.data_type.is_date_time() {
+            // Dates are not kept as strings, see `as_date` and `as_date_time`
+            None
         } else {
             panic!("Data type {:?} not yet supported", self.data_type);
         }
@@ -449,6 +477,9 @@ impl Literal {
         }
     }
 
+    /// The language tag of a language-tagged string such as `"chat"@fr`
+    pub fn language(&self) -> Option<&str> { self.language.as_deref() }
+
     pub fn from_type_and_c_buffer(
         data_type: DataType,
         buffer: &[u8],
@@ -506,7 +537,7 @@ impl Literal {
                         id_base_iri,
                     )?))
                 } else {
-                    return match iri_string::types::IriReferenceString::try_from(buffer) {
+                    match iri_string::types::IriReferenceString::try_from(buffer) {
                         Ok(iri) => {
                             tracing::error!(
                                 target: LOG_TARGET_DATABASE,
@@ -525,7 +556,7 @@ impl Literal {
                             );
                             Err(ekg_error::Error::from(error))
                         },
-                    };
+                    }
                 }
             },
             DataType::BlankNode => {
@@ -555,7 +586,8 @@ impl Literal {
             DataType::NegativeInteger |
             DataType::NonPositiveInteger |
             DataType::Long |
-            DataType::Short => {
+            DataType::Short |
+            DataType::Byte => {
                 let signed_integer: i64 = buffer.parse()?; // TODO: Remove unwrap
                 Ok(Some(Literal::new_signed_integer_with_datatype(
                     signed_integer,
@@ -568,7 +600,7 @@ impl Literal {
             DataType::UnsignedInt |
             DataType::UnsignedShort |
             DataType::UnsignedLong => {
-                let unsigned_integer: u64 = buffer.parse().unwrap(); // TODO: Remove unwrap
+                let unsigned_integer: u64 = buffer.parse()?;
                 Ok(Some(Literal::new_unsigned_integer_with_datatype(
                     unsigned_integer,
                     data_type,
@@ -584,14 +616,23 @@ impl Literal {
                     buffer, data_type,
                 )?))
             },
-            DataType::UnboundValue => Ok(None),
-            _ => {
-                tracing::warn!(
-                    target: LOG_TARGET_DATABASE,
-                    "Unsupported datatype: {data_type:?} value={buffer}"
-                );
-                Err(ekg_error::Error::Unknown)
+            DataType::Literal |
+            DataType::DateTimeStamp |
+            DataType::Time |
+            DataType::YearMonth |
+            DataType::Year |
+            DataType::MonthDay |
+            DataType::Day |
+            DataType::Month |
+            DataType::YearMonthDuration |
+            DataType::DayTimeDuration |
+            DataType::Double |
+            DataType::Float => {
+                Ok(Some(Literal::new_lexical_with_datatype(
+                    buffer, data_type,
+                )?))
             },
+            DataType::UnboundValue => Ok(None),
         }
     }
 
@@ -669,6 +710,7 @@ impl Literal {
         Ok(Literal {
             data_type:     DataType::IriReference,
             literal_value: LiteralValue { iri: ManuallyDrop::new(iri.to_owned()) },
+            language:      None,
         })
     }
 
@@ -680,6 +722,7 @@ impl Literal {
                     iri.as_str(),
                 )?),
             },
+            language:      None,
         })
     }
 
@@ -687,6 +730,26 @@ impl Literal {
         Self::new_string_with_datatype(str, DataType::PlainLiteral)
     }
 
+    /// A string with a language tag such as `"chat"@fr`, the language tag is
+    /// stored in lower case.
+    pub fn new_language_tagged_string(str: &str, language: &str) -> Result<Self, ekg_error::Error> {
+        if language.is_empty() ||
+            !language.split('-').all(|subtag| {
+                !subtag.is_empty() && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+            })
+        {
+            return Err(ekg_error::Error::UnknownValueForDataType {
+                data_type_xsd_iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString"
+                    .to_string(),
+                value:             language.to_string(),
+            });
+        }
+        Ok(Literal {
+            language: Some(language.to_ascii_lowercase()),
+            ..Self::new_plain_literal_string(str)?
+        })
+    }
+
     pub fn new_plain_literal_boolean(boolean: bool) -> Result<Self, ekg_error::Error> {
         Self::new_string_with_datatype(
             boolean.to_string().as_str(),
@@ -705,6 +768,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_string(str),
+            language: None,
         })
     }
 
@@ -721,6 +785,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_date(date),
+            language: None,
         })
     }
 
@@ -735,6 +800,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_date_time(date_time),
+            language: None,
         })
     }
 
@@ -749,6 +815,24 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_string(str),
+            language: None,
+        })
+    }
+
+    /// A value of one of the datatypes that are kept as their lexical form,
+    /// such as `xsd:double` or `xsd:gYear` (see [`DataType::is_lexical`]).
+    /// The lexical form is not validated.
+    pub fn new_lexical_with_datatype(
+        str: &str,
+        data_type: DataType,
+    ) -> Result<Self, ekg_error::Error> {
+        if !data_type.is_lexical() {
+            return Err(ekg_error::Error::UnknownDataType { data_type_id: data_type as u8 });
+        }
+        Ok(Literal {
+            data_type,
+            literal_value: LiteralValue::new_string(str),
+            language: None,
         })
     }
 
@@ -763,6 +847,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_string(str),
+            language: None,
         })
     }
 
@@ -804,6 +889,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_iref_iri(iri)?,
+            language: None,
         })
     }
 
@@ -818,6 +904,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_iri(iri),
+            language: None,
         })
     }
 
@@ -832,6 +919,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_blank_node(id),
+            language: None,
         })
     }
 
@@ -870,6 +958,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_boolean(boolean),
+            language: None,
         })
     }
 
@@ -892,6 +981,7 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_signed_integer(signed_integer),
+            language: None,
         })
     }
 
@@ -910,20 +1000,49 @@ impl Literal {
         Ok(Literal {
             data_type,
             literal_value: LiteralValue::new_unsigned_integer(unsigned_integer),
+            language: None,
         })
     }
 
     pub fn display_turtle<'a, 'b>(&'a self) -> impl Display + 'a + 'b
     where 'a: 'b {
-        struct TurtleLexVal<'b>(&'b Literal);
+        self.turtle(None)
+    }
+
+    /// Display the literal in Turtle format like [`Literal::display_turtle`]
+    /// but with IRIs as prefixed names (such as `rdf:type`) where the given
+    /// prefixes allow it.
+    pub fn display_turtle_with<'a>(&'a self, prefixes: &'a dyn IriCompactor) -> impl Display + 'a {
+        self.turtle(Some(prefixes))
+    }
+
+    pub(crate) fn turtle<'a>(
+        &'a self,
+        prefixes: Option<&'a dyn IriCompactor>,
+    ) -> impl Display + 'a {
+        struct TurtleLexVal<'b>(&'b Literal, Option<&'b dyn IriCompactor>);
         impl<'b> Display for TurtleLexVal<'b> {
             fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
                 let data_type = self.0.data_type;
                 unsafe {
                     if data_type.is_iri() {
-                        write!(f, "<{}>", self.0.literal_value.iri.as_str())?
+                        let iri = self.0.literal_value.iri.as_str();
+                        match self.1.and_then(|prefixes| prefixes.compact_iri(iri)) {
+                            Some(prefixed_name) => write!(f, "{prefixed_name}")?,
+                            None => write!(f, "<{iri}>")?,
+                        }
                     } else if data_type.is_string() {
-                        write!(f, "\"{}\"", self.0.literal_value.string.as_str())?
+                        write!(f, "\"{}\"", self.0.literal_value.string.as_str())?;
+                        if let Some(language) = &self.0.language {
+                            write!(f, "@{language}")?
+                        }
+                    } else if data_type.is_lexical() {
+                        write!(
+                            f,
+                            "\"{}\"^^<{}>",
+                            self.0.literal_value.string.as_str(),
+                            data_type.as_xsd_iri_str()
+                        )?
                     } else if data_type.is_blank_node() {
                         write!(
                             f,
@@ -957,7 +1076,7 @@ impl Literal {
                 Ok(())
             }
         }
-        TurtleLexVal(self)
+        TurtleLexVal(self, prefixes)
     }
 
     pub fn display_json<'a, 'b>(&'a self) -> impl Display + 'a + 'b
@@ -991,7 +1110,7 @@ impl Literal {
                         write!(f, "\"{}\"", self.0.literal_value.string.as_str())?
                     } else if data_type.is_decimal() {
                         write!(f, "{}", self.0.literal_value.string.as_str())?
-                    } else if data_type.is_duration() {
+                    } else if data_type.is_duration() || data_type.is_lexical() {
                         write!(f, "\"{}\"", self.0.literal_value.string.as_str())?
                     } else {
                         panic!("Cannot format for JSON, unimplemented datatype {data_type:?}")
@@ -1003,7 +1122,7 @@ impl Literal {
         JsonLexVal(self)
     }
 
-    pub fn as_url_display(&self) -> LiteralUrlDisplay { LiteralUrlDisplay { literal: self } }
+    pub fn as_url_display(&self) -> LiteralUrlDisplay<'_> { LiteralUrlDisplay { literal: self } }
 
     pub fn as_id_url_display<'a, T: NamespaceIRI>(
         &'a self,
@@ -1016,7 +1135,7 @@ impl Literal {
     pub fn is_id_iri(&self, id_base_iri: &ABoxNamespaceIRI) -> bool {
         match self.data_type {
             DataType::AnyUri | DataType::IriReference => unsafe {
-                id_base_iri.is_in_namespace(&self.literal_value.iri.as_str())
+                id_base_iri.is_in_namespace(self.literal_value.iri.as_str())
             },
             _ => false,
         }
@@ -1025,7 +1144,7 @@ impl Literal {
     pub fn is_in_namespace<T: NamespaceIRI>(&self, namespace: &T) -> bool {
         match self.data_type {
             DataType::AnyUri | DataType::IriReference => unsafe {
-                namespace.is_in_namespace(&self.literal_value.iri.as_str())
+                namespace.is_in_namespace(self.literal_value.iri.as_str())
             },
             _ => false,
         }
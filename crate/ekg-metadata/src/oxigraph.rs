@@ -0,0 +1,484 @@
+//! Conversions between our RDF types and those of the `oxrdf` crate that is
+//! used by `spargebra`, `oxttl` and Oxigraph.
+//!
+//! Literals keep their [`DataType`], language-tagged strings are converted to
+//! [`DataType::PlainLiteral`] literals with a [`Literal::language`]. Since
+//! RDF 1.1 simple literals are `xsd:string`s, so a plain literal without a
+//! language tag comes back as a [`DataType::String`].
+//! IRIs and blank nodes are represented by a [`Term`] (a [`Literal`] of type
+//! [`DataType::IriReference`] or [`DataType::BlankNode`]), converting those
+//! into an `oxrdf::Literal` fails with [`ekg_error::Error::UnknownDataType`],
+//! as does converting an `oxrdf::Literal` whose datatype has no [`DataType`]
+//! such as `geo:wktLiteral`. An owned `oxrdf::Literal` is converted through
+//! its `as_ref()`, the `From<oxrdf::Literal>` impl is only kept for backward
+//! compatibility and panics on such datatypes.
+use {
+    crate::{DataType, Graph, Literal, Namespace, Term},
+    ekg_error::Error,
+    ekg_util::log::LOG_TARGET_DATABASE,
+};
+
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+fn unknown_data_type(data_type: DataType) -> Error {
+    Error::UnknownDataType { data_type_id: data_type as u8 }
+}
+
+fn named_node(iri: &str) -> Result<oxrdf::NamedNode, Error> {
+    oxrdf::NamedNode::new(iri).map_err(|_| Error::InvalidIri(iri.to_string()))
+}
+
+fn iri_literal(iri: &str) -> Result<Literal, Error> {
+    Literal::from_iri(&iri_string::types::IriReferenceString::try_from(
+        iri,
+    )?)
+}
+
+/// The lexical form of the given literal in the canonical representation
+/// of its datatype where we have to generate it ourselves.
+fn lexical_form(literal: &Literal) -> Result<String, Error> {
+    let data_type = literal.data_type;
+    let lexical_form = if data_type.is_signed_integer() {
+        literal.as_signed_long().map(|value| value.to_string())
+    } else if data_type.is_unsigned_integer() {
+        literal.as_unsigned_long().map(|value| value.to_string())
+    } else if data_type.is_date() {
+        literal
+            .as_date()
+            .map(|date| date.format("%Y-%m-%d").to_string())
+    } else if data_type.is_date_time() {
+        literal
+            .as_date_time()
+            .map(|date_time| date_time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    } else if data_type == DataType::UnboundValue {
+        None
+    } else {
+        literal.as_string()
+    };
+    lexical_form.ok_or_else(|| unknown_data_type(data_type))
+}
+
+impl TryFrom<&Literal> for oxrdf::Literal {
+    type Error = Error;
+
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        let data_type = literal.data_type;
+        if data_type.is_blank_node() || data_type == DataType::IriReference {
+            return Err(unknown_data_type(data_type));
+        }
+        let value = lexical_form(literal)?;
+        if data_type == DataType::PlainLiteral {
+            return Ok(match literal.language() {
+                Some(language) => {
+                    oxrdf::Literal::new_language_tagged_literal(value, language).map_err(|_| {
+                        Error::UnknownValueForDataType {
+                            data_type_xsd_iri: RDF_LANG_STRING.to_string(),
+                            value:             language.to_string(),
+                        }
+                    })?
+                },
+                None => oxrdf::Literal::new_simple_literal(value),
+            });
+        }
+        let data_type_iri = data_type
+            .try_as_xsd_iri_str()
+            .ok_or_else(|| unknown_data_type(data_type))?;
+        Ok(oxrdf::Literal::new_typed_literal(
+            value,
+            named_node(data_type_iri)?,
+        ))
+    }
+}
+
+impl TryFrom<Literal> for oxrdf::Literal {
+    type Error = Error;
+
+    fn try_from(literal: Literal) -> Result<Self, Self::Error> { Self::try_from(&literal) }
+}
+
+impl TryFrom<oxrdf::LiteralRef<'_>> for Literal {
+    type Error = Error;
+
+    fn try_from(literal: oxrdf::LiteralRef<'_>) -> Result<Self, Self::Error> {
+        if let Some(language) = literal.language() {
+            return Literal::new_language_tagged_string(literal.value(), language);
+        }
+        let data_type_iri = literal.datatype().as_str();
+        let Ok(data_type) = DataType::from_xsd_iri(data_type_iri) else {
+            tracing::warn!(
+                target: LOG_TARGET_DATABASE,
+                "Unsupported datatype <{data_type_iri}> of literal \"{}\"",
+                literal.value()
+            );
+            return Err(unknown_data_type(DataType::UnboundValue));
+        };
+        Literal::from_type_and_buffer(data_type, literal.value(), None)?
+            .ok_or_else(|| unknown_data_type(data_type))
+    }
+}
+
+impl TryFrom<&Literal> for oxrdf::NamedNode {
+    type Error = Error;
+
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        let iri = literal
+            .as_iri()
+            .ok_or_else(|| unknown_data_type(literal.data_type))?;
+        named_node(iri.as_str())
+    }
+}
+
+impl TryFrom<&Literal> for oxrdf::BlankNode {
+    type Error = Error;
+
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        if !literal.data_type.is_blank_node() {
+            return Err(unknown_data_type(literal.data_type));
+        }
+        let id = literal.as_str().unwrap_or_default();
+        oxrdf::BlankNode::new(id).map_err(|_| {
+            Error::UnknownValueForDataType {
+                data_type_xsd_iri: "Blank Node".to_string(),
+                value:             id.to_string(),
+            }
+        })
+    }
+}
+
+impl TryFrom<&Term> for oxrdf::Term {
+    type Error = Error;
+
+    fn try_from(term: &Term) -> Result<Self, Self::Error> {
+        Ok(match term {
+            Term::Iri(literal) => oxrdf::NamedNode::try_from(literal)?.into(),
+            Term::BlankNode(literal) => oxrdf::BlankNode::try_from(literal)?.into(),
+            Term::Literal(literal) if literal.data_type.is_blank_node() => {
+                oxrdf::BlankNode::try_from(literal)?.into()
+            },
+            // A literal of type `xsd:anyURI` stays a literal, other IRIs are named nodes
+            Term::Literal(literal) if literal.data_type == DataType::IriReference => {
+                oxrdf::NamedNode::try_from(literal)?.into()
+            },
+            Term::Literal(literal) => oxrdf::Literal::try_from(literal)?.into(),
+        })
+    }
+}
+
+impl TryFrom<Term> for oxrdf::Term {
+    type Error = Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> { Self::try_from(&term) }
+}
+
+impl TryFrom<oxrdf::TermRef<'_>> for Term {
+    type Error = Error;
+
+    fn try_from(term: oxrdf::TermRef<'_>) -> Result<Self, Self::Error> {
+        match term {
+            oxrdf::TermRef::NamedNode(node) => Term::try_from(node),
+            oxrdf::TermRef::BlankNode(node) => Term::try_from(node),
+            oxrdf::TermRef::Literal(literal) => Ok(Term::Literal(Literal::try_from(literal)?)),
+        }
+    }
+}
+
+impl TryFrom<oxrdf::Term> for Term {
+    type Error = Error;
+
+    fn try_from(term: oxrdf::Term) -> Result<Self, Self::Error> { Self::try_from(term.as_ref()) }
+}
+
+impl TryFrom<oxrdf::NamedNodeRef<'_>> for Term {
+    type Error = Error;
+
+    fn try_from(node: oxrdf::NamedNodeRef<'_>) -> Result<Self, Self::Error> {
+        Ok(Term::Iri(iri_literal(node.as_str())?))
+    }
+}
+
+impl TryFrom<oxrdf::NamedNode> for Term {
+    type Error = Error;
+
+    fn try_from(node: oxrdf::NamedNode) -> Result<Self, Self::Error> {
+        Self::try_from(node.as_ref())
+    }
+}
+
+impl TryFrom<oxrdf::BlankNodeRef<'_>> for Term {
+    type Error = Error;
+
+    fn try_from(node: oxrdf::BlankNodeRef<'_>) -> Result<Self, Self::Error> {
+        Ok(Term::BlankNode(
+            Literal::new_blank_node_with_datatype(node.as_str(), DataType::BlankNode)?,
+        ))
+    }
+}
+
+impl TryFrom<oxrdf::BlankNode> for Term {
+    type Error = Error;
+
+    fn try_from(node: oxrdf::BlankNode) -> Result<Self, Self::Error> {
+        Self::try_from(node.as_ref())
+    }
+}
+
+impl TryFrom<&Graph> for oxrdf::NamedNode {
+    type Error = Error;
+
+    fn try_from(graph: &Graph) -> Result<Self, Self::Error> { named_node(graph.as_iri()?.as_str()) }
+}
+
+impl TryFrom<&Graph> for oxrdf::GraphName {
+    type Error = Error;
+
+    fn try_from(graph: &Graph) -> Result<Self, Self::Error> {
+        Ok(oxrdf::NamedNode::try_from(graph)?.into())
+    }
+}
+
+/// The graph is split into an unnamed [`Namespace`] that ends at the last
+/// `/` or `#` of the IRI, or at the last `:` for IRIs such as
+/// `urn:example:g1`, and a local name.
+impl TryFrom<oxrdf::NamedNodeRef<'_>> for Graph {
+    type Error = Error;
+
+    fn try_from(node: oxrdf::NamedNodeRef<'_>) -> Result<Self, Self::Error> {
+        let iri = node.as_str();
+        let Some(index) = iri.rfind(['/', '#']).or_else(|| iri.rfind(':')) else {
+            return Err(Error::InvalidIri(iri.to_string()));
+        };
+        Ok(Graph::declare(
+            Namespace::declare_from_str("", &iri[..=index])?,
+            &iri[index + 1..],
+        ))
+    }
+}
+
+impl TryFrom<oxrdf::NamedNode> for Graph {
+    type Error = Error;
+
+    fn try_from(node: oxrdf::NamedNode) -> Result<Self, Self::Error> {
+        Self::try_from(node.as_ref())
+    }
+}
+
+/// Convert the given graph name into a [`Graph`], or `None` for the default
+/// graph. Blank node graph names are not supported.
+pub fn graph_from_oxrdf(graph_name: oxrdf::GraphNameRef<'_>) -> Result<Option<Graph>, Error> {
+    match graph_name {
+        oxrdf::GraphNameRef::NamedNode(node) => Ok(Some(Graph::try_from(node)?)),
+        oxrdf::GraphNameRef::DefaultGraph => Ok(None),
+        oxrdf::GraphNameRef::BlankNode(node) => Err(Error::InvalidIri(node.to_string())),
+    }
+}
+
+/// Convert the given quad into its subject, predicate, object and graph,
+/// the graph is `None` for the default graph.
+pub fn quad_from_oxrdf(
+    quad: oxrdf::QuadRef<'_>,
+) -> Result<(Term, Term, Term, Option<Graph>), Error> {
+    let subject = match quad.subject {
+        oxrdf::NamedOrBlankNodeRef::NamedNode(node) => Term::try_from(node)?,
+        oxrdf::NamedOrBlankNodeRef::BlankNode(node) => Term::try_from(node)?,
+    };
+    Ok((
+        subject,
+        Term::try_from(quad.predicate)?,
+        Term::try_from(quad.object)?,
+        graph_from_oxrdf(quad.graph_name)?,
+    ))
+}
+
+/// Convert the given subject, predicate, object and graph (`None` for the
+/// default graph) into a quad. The subject has to be an IRI or blank node
+/// and the predicate an IRI.
+pub fn quad_to_oxrdf(
+    subject: &Term,
+    predicate: &Term,
+    object: &Term,
+    graph: Option<&Graph>,
+) -> Result<oxrdf::Quad, Error> {
+    let subject = match oxrdf::Term::try_from(subject)? {
+        oxrdf::Term::NamedNode(node) => oxrdf::NamedOrBlankNode::from(node),
+        oxrdf::Term::BlankNode(node) => oxrdf::NamedOrBlankNode::from(node),
+        oxrdf::Term::Literal(_) => return Err(unknown_data_type(data_type_of(subject))),
+    };
+    let oxrdf::Term::NamedNode(predicate_node) = oxrdf::Term::try_from(predicate)? else {
+        return Err(unknown_data_type(data_type_of(predicate)));
+    };
+    let graph_name = match graph {
+        Some(graph) => oxrdf::GraphName::try_from(graph)?,
+        None => oxrdf::GraphName::DefaultGraph,
+    };
+    Ok(oxrdf::Quad::new(
+        subject,
+        predicate_node,
+        oxrdf::Term::try_from(object)?,
+        graph_name,
+    ))
+}
+
+fn data_type_of(term: &Term) -> DataType {
+    match term {
+        Term::Iri(literal) | Term::Literal(literal) | Term::BlankNode(literal) => literal.data_type,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{DataType, Graph, Literal, Namespace, Term},
+        std::str::FromStr,
+    };
+
+    const SAMPLES: [(DataType, &str); 33] = [
+        (DataType::AnyUri, "https://example.com/a"),
+        (DataType::String, "abc"),
+        (DataType::Boolean, "true"),
+        (DataType::DateTime, "2024-03-01T12:30:00Z"),
+        (
+            DataType::DateTimeStamp,
+            "2024-03-01T12:30:00+01:00",
+        ),
+        (DataType::Time, "12:30:00"),
+        (DataType::Date, "2024-03-01"),
+        (DataType::YearMonth, "2024-03"),
+        (DataType::Year, "2024"),
+        (DataType::MonthDay, "--03-01"),
+        (DataType::Day, "---01"),
+        (DataType::Month, "--03"),
+        (DataType::Duration, "P1Y2M"),
+        (DataType::YearMonthDuration, "P1Y2M"),
+        (DataType::DayTimeDuration, "PT1H"),
+        (DataType::Double, "1.5E0"),
+        (DataType::Float, "1.5"),
+        (DataType::Decimal, "1.50"),
+        (DataType::Integer, "-42"),
+        (DataType::NonNegativeInteger, "42"),
+        (DataType::NonPositiveInteger, "-42"),
+        (DataType::NegativeInteger, "-42"),
+        (DataType::PositiveInteger, "42"),
+        (DataType::Long, "-42"),
+        (DataType::Int, "-42"),
+        (DataType::Short, "-42"),
+        (DataType::Byte, "-42"),
+        (DataType::UnsignedLong, "42"),
+        (DataType::UnsignedInt, "42"),
+        (DataType::UnsignedShort, "42"),
+        (DataType::UnsignedByte, "42"),
+        (DataType::Literal, "abc"),
+        (DataType::PlainLiteral, "abc"),
+    ];
+
+    #[test_log::test]
+    fn test_literal_round_trip() -> Result<(), ekg_error::Error> {
+        for (data_type, value) in SAMPLES {
+            let literal = Literal::from_type_and_buffer(data_type, value, None)?.unwrap();
+            let converted = oxrdf::Literal::try_from(&literal)?;
+            assert_eq!(converted.value(), value, "{data_type:?}");
+            let back = Literal::try_from(converted.as_ref())?;
+            match data_type {
+                // A simple literal is an xsd:string in RDF 1.1
+                DataType::PlainLiteral => assert_eq!(back.data_type, DataType::String),
+                _ => assert_eq!(back, literal, "{data_type:?}"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_language_tagged_string() -> Result<(), ekg_error::Error> {
+        let literal = Literal::new_language_tagged_string("chat", "FR")?;
+        assert_eq!(literal.language(), Some("fr"));
+        assert_eq!(
+            literal.display_turtle().to_string(),
+            "\"chat\"@fr"
+        );
+        let converted = oxrdf::Literal::try_from(&literal)?;
+        assert_eq!(converted.to_string(), "\"chat\"@fr");
+        assert_eq!(Literal::try_from(converted.as_ref())?, literal);
+        assert_ne!(
+            literal,
+            Literal::new_plain_literal_string("chat")?
+        );
+        assert!(Literal::new_language_tagged_string("chat", "f r").is_err());
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_unsupported_conversions() -> Result<(), ekg_error::Error> {
+        let iri = Literal::new_iri_reference_from_str("https://example.com/a")?;
+        assert!(matches!(
+            oxrdf::Literal::try_from(&iri),
+            Err(ekg_error::Error::UnknownDataType { .. })
+        ));
+        assert!(matches!(
+            oxrdf::NamedNode::try_from(&Literal::from_str("abc")?),
+            Err(ekg_error::Error::UnknownDataType { .. })
+        ));
+        let unknown = oxrdf::Literal::new_typed_literal(
+            "POINT(1 2)",
+            oxrdf::NamedNode::new_unchecked("http://www.opengis.net/ont/geosparql#wktLiteral"),
+        );
+        assert!(matches!(
+            Literal::try_from(unknown.as_ref()),
+            Err(ekg_error::Error::UnknownDataType { .. })
+        ));
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_from_owned_literal() -> Result<(), ekg_error::Error> {
+        let literal = Literal::from(oxrdf::Literal::new_typed_literal(
+            "2024-02-29",
+            oxrdf::NamedNode::new_unchecked("http://www.w3.org/2001/XMLSchema#date"),
+        ));
+        assert_eq!(literal.data_type, DataType::Date);
+        assert_eq!(
+            literal.as_date().map(|date| date.to_string()),
+            Some("2024-02-29".to_string())
+        );
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_quad_round_trip() -> Result<(), ekg_error::Error> {
+        let graph = Graph::declare(
+            Namespace::declare_from_str("ex:", "https://example.com/graph/")?,
+            "people",
+        );
+        let subject = Term::new_blank_node("b0")?;
+        let predicate = Term::new_iri_from_str("https://example.com/name")?;
+        let object = Term::Literal(Literal::new_language_tagged_string(
+            "Alice", "en",
+        )?);
+        let quad = super::quad_to_oxrdf(&subject, &predicate, &object, Some(&graph))?;
+        assert_eq!(
+            quad.to_string(),
+            "_:b0 <https://example.com/name> \"Alice\"@en <https://example.com/graph/people>"
+        );
+        let (s, p, o, g) = super::quad_from_oxrdf(quad.as_ref())?;
+        assert_eq!(
+            s.display_turtle().to_string(),
+            subject.display_turtle().to_string()
+        );
+        assert_eq!(
+            p.display_turtle().to_string(),
+            predicate.display_turtle().to_string()
+        );
+        assert_eq!(o.display_turtle().to_string(), "\"Alice\"@en");
+        let g = g.unwrap();
+        assert_eq!(g.as_iri()?, graph.as_iri()?);
+        assert_eq!(g.local_name, "people");
+
+        let urn = Graph::try_from(oxrdf::NamedNode::new_unchecked("urn:x:y"))?;
+        assert_eq!(urn.as_iri()?.as_str(), "urn:x:y");
+        assert_eq!(urn.local_name, "y");
+
+        assert!(matches!(
+            super::quad_to_oxrdf(&object, &predicate, &object, None),
+            Err(ekg_error::Error::UnknownDataType { .. })
+        ));
+        Ok(())
+    }
+}
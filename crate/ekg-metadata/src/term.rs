@@ -1,5 +1,5 @@
 use {
-    crate::{DataType, Literal},
+    crate::{DataType, IriCompactor, Literal},
     std::str::FromStr,
 };
 
@@ -62,18 +62,22 @@ impl Term {
     /// ```
     pub fn display_turtle<'a, 'b>(&'a self) -> impl std::fmt::Display + 'a + 'b
     where 'a: 'b {
-        struct TurtleTerm<'b>(&'b Term);
-        impl<'b> std::fmt::Display for TurtleTerm<'b> {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                let value = match self.0 {
-                    Term::Iri(value) => value,
-                    Term::Literal(value) => value,
-                    Term::BlankNode(value) => value,
-                };
-                value.display_turtle().fmt(f)
-            }
+        self.as_literal().turtle(None)
+    }
+
+    /// Display a [`Term`] in Turtle format with IRIs as prefixed names where
+    /// the given prefixes allow it, see [`Literal::display_turtle_with`].
+    pub fn display_turtle_with<'a>(
+        &'a self,
+        prefixes: &'a dyn IriCompactor,
+    ) -> impl std::fmt::Display + 'a {
+        self.as_literal().turtle(Some(prefixes))
+    }
+
+    fn as_literal(&self) -> &Literal {
+        match self {
+            Term::Iri(value) | Term::Literal(value) | Term::BlankNode(value) => value,
         }
-        TurtleTerm(self)
     }
 }
 
@@ -1,14 +1,7 @@
-#![cfg(feature = "_rdfox")]
-
 use {
-    crate::{
-        prefixes::Prefixes,
-        rdfox::{GraphConnection, Transaction},
-        statement::Statement,
-    },
-    ekg_metadata::{consts::DEFAULT_GRAPH_RDFOX, Class},
+    crate::{prefixes::Prefixes, statement::Statement, store::TripleStore},
+    ekg_metadata::{Class, Graph},
     indoc::formatdoc,
-    std::{ops::Deref, sync::Arc},
 };
 
 /// Some simple queries about a [`Class`](Class)
@@ -21,8 +14,15 @@ impl<'a> std::fmt::Display for ClassReport<'a> {
 }
 
 impl<'a> ClassReport<'a> {
-    pub fn number_of_individuals(&self, tx: &Arc<Transaction>) -> Result<usize, ekg_error::Error> {
-        let default_graph = DEFAULT_GRAPH_RDFOX.deref().as_display_iri();
+    /// The number of distinct individuals of the class in the default graph
+    /// or any named graph of the given store.
+    ///
+    /// With RDFox, pass the `Arc<DataStoreConnection>`, the query is then
+    /// evaluated in a read-only transaction of its own.
+    pub async fn number_of_individuals(
+        &self,
+        store: &dyn TripleStore,
+    ) -> Result<usize, ekg_error::Error> {
         let prefixes = Prefixes::builder().declare(&self.0.namespace).build()?;
         let sparql = formatdoc! {r##"
             SELECT DISTINCT ?thing
@@ -32,26 +32,23 @@ impl<'a> ClassReport<'a> {
                         ?thing a {self}
                     }}
                 }} UNION {{
-                        ?thing a {self}
-                    BIND({default_graph} AS ?graph)
+                    ?thing a {self}
                 }}
             }}
             "##
         };
         tracing::debug!(target: "sparql", "\n{sparql}");
-        let count_result = Statement::new(prefixes, sparql.into())?
-            .cursor_with_default_parameters(&tx.connection)?
-            .count(tx);
-        #[allow(clippy::let_and_return)]
-        count_result
+        store.count(&Statement::new(prefixes, sparql.into())?).await
     }
 
-    pub fn number_of_individuals_in_graph(
+    /// The number of distinct individuals of the class in the given graph of
+    /// the given store.
+    pub async fn number_of_individuals_in_graph(
         &self,
-        tx: &Arc<Transaction>,
-        graph_connection: &GraphConnection,
+        store: &dyn TripleStore,
+        graph: &Graph,
     ) -> Result<usize, ekg_error::Error> {
-        let graph = graph_connection.graph.as_display_iri();
+        let graph = graph.as_display_iri();
         let prefixes = Prefixes::builder().declare(&self.0.namespace).build()?;
         let sparql = formatdoc! {r##"
             SELECT DISTINCT ?thing
@@ -63,10 +60,6 @@ impl<'a> ClassReport<'a> {
             "##
         };
         tracing::debug!(target: "sparql", "\n{sparql}");
-        let count_result = Statement::new(prefixes, sparql.into())?
-            .cursor_with_default_parameters(&graph_connection.datastore_connection)?
-            .count(tx);
-        #[allow(clippy::let_and_return)]
-        count_result
+        store.count(&Statement::new(prefixes, sparql.into())?).await
     }
 }
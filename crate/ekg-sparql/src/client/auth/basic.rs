@@ -0,0 +1,35 @@
+use {
+    super::{AuthenticationFuture, AuthenticationProvider, set_header},
+    base64::Engine,
+    hyper::http::{header::AUTHORIZATION, request::Parts},
+};
+
+/// HTTP Basic authentication with a username and password.
+pub struct BasicAuthentication {
+    credentials: String,
+}
+
+impl BasicAuthentication {
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            credentials: base64::engine::general_purpose::STANDARD
+                .encode(format!("{username}:{password}")),
+        }
+    }
+}
+
+impl AuthenticationProvider for BasicAuthentication {
+    fn authenticate<'a>(
+        &'a self,
+        parts: &'a mut Parts,
+        _body: &'a [u8],
+    ) -> AuthenticationFuture<'a> {
+        Box::pin(async move {
+            set_header(
+                parts,
+                AUTHORIZATION,
+                format!("Basic {}", self.credentials).as_str(),
+            )
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use {
+    super::{AuthenticationFuture, AuthenticationProvider, set_header},
+    hyper::http::{header::AUTHORIZATION, request::Parts},
+};
+
+/// A static OAuth bearer token, see
+/// [`RefreshableToken`](super::RefreshableToken) for tokens that expire.
+pub struct BearerToken {
+    token: String,
+}
+
+impl BearerToken {
+    pub fn new(token: &str) -> Self { Self { token: token.to_string() } }
+}
+
+impl AuthenticationProvider for BearerToken {
+    fn authenticate<'a>(
+        &'a self,
+        parts: &'a mut Parts,
+        _body: &'a [u8],
+    ) -> AuthenticationFuture<'a> {
+        Box::pin(async move {
+            set_header(
+                parts,
+                AUTHORIZATION,
+                format!("Bearer {}", self.token).as_str(),
+            )
+        })
+    }
+}
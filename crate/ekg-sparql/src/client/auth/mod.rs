@@ -0,0 +1,146 @@
+//! Authentication of the requests that the
+//! [`SPARQLClient`](crate::SPARQLClient) sends to a SPARQL endpoint.
+//!
+//! An [`AuthenticationProvider`] adds credentials to every request, either
+//! configured explicitly with
+//! [`SPARQLClient::with_authentication`](crate::SPARQLClient::with_authentication)
+//! or from the following environment variables by
+//! [`SPARQLClient::from_env`](crate::SPARQLClient::from_env):
+//!
+//! - `EKG_SPARQL_AUTH`: one of `none`, `basic`, `bearer` or `sigv4`. When not
+//!   set, the method is derived from whichever of the variables below is set.
+//! - `EKG_SPARQL_USERNAME` and `EKG_SPARQL_PASSWORD` for `basic`
+//! - `EKG_SPARQL_BEARER_TOKEN` for `bearer`, or `EKG_SPARQL_BEARER_TOKEN_FILE`
+//!   for a token that is read from a file and re-read every
+//!   `EKG_SPARQL_BEARER_TOKEN_REFRESH_SECONDS` (default 300) seconds, such as a
+//!   projected service account token
+//! - `EKG_SPARQL_AWS_REGION` (or `AWS_REGION`) and optionally
+//!   `EKG_SPARQL_AWS_SERVICE` (default `neptune-db`) for `sigv4`, signed with
+//!   the credentials in `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+//!   `AWS_SESSION_TOKEN`
+pub use {
+    basic::BasicAuthentication,
+    bearer::BearerToken,
+    refreshable::{AccessToken, AccessTokenFuture, RefreshableToken},
+    sigv4::{AwsCredentials, SigV4Authentication},
+};
+use {
+    ekg_error::Error,
+    ekg_util::env::optional_env_var,
+    hyper::http::request::Parts,
+    std::{future::Future, pin::Pin, sync::Arc, time::Duration},
+};
+
+mod basic;
+mod bearer;
+mod refreshable;
+mod sigv4;
+#[cfg(test)]
+mod tests;
+
+/// The future returned by [`AuthenticationProvider::authenticate`].
+pub type AuthenticationFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'a>>;
+
+/// Adds credentials to the requests sent to a SPARQL endpoint.
+pub trait AuthenticationProvider: Send + Sync {
+    /// Add the credentials to the given request headers. The (form encoded)
+    /// body of the request is passed as well, for providers that sign it.
+    fn authenticate<'a>(&'a self, parts: &'a mut Parts, body: &'a [u8])
+    -> AuthenticationFuture<'a>;
+}
+
+const DEFAULT_TOKEN_REFRESH_SECONDS: u64 = 300;
+const DEFAULT_AWS_SERVICE: &str = "neptune-db";
+
+/// Create the [`AuthenticationProvider`] that is configured with the
+/// `EKG_SPARQL_*` environment variables, if any.
+pub(crate) fn authentication_from_env() -> Result<Option<Arc<dyn AuthenticationProvider>>, Error> {
+    authentication_from_lookup(optional_env_var)
+}
+
+/// Same as [`authentication_from_env`] but with the given function to look
+/// up the (non-empty) value of an environment variable.
+pub(crate) fn authentication_from_lookup<F>(
+    lookup: F,
+) -> Result<Option<Arc<dyn AuthenticationProvider>>, Error>
+where F: Fn(&str) -> Option<String> {
+    let mandatory = |name: &str| {
+        lookup(name).ok_or_else(|| Error::MandatoryEnvironmentVariableMissing(name.to_string()))
+    };
+    let method = match lookup("EKG_SPARQL_AUTH") {
+        Some(method) => method.trim().to_ascii_lowercase(),
+        None if lookup("EKG_SPARQL_USERNAME").is_some() => "basic".to_string(),
+        None if lookup("EKG_SPARQL_BEARER_TOKEN").is_some() ||
+            lookup("EKG_SPARQL_BEARER_TOKEN_FILE").is_some() =>
+        {
+            "bearer".to_string()
+        },
+        None => "none".to_string(),
+    };
+    let provider: Arc<dyn AuthenticationProvider> = match method.as_str() {
+        "none" => return Ok(None),
+        "basic" => {
+            Arc::new(BasicAuthentication::new(
+                mandatory("EKG_SPARQL_USERNAME")?.as_str(),
+                mandatory("EKG_SPARQL_PASSWORD")?.as_str(),
+            ))
+        },
+        "bearer" => {
+            match lookup("EKG_SPARQL_BEARER_TOKEN_FILE") {
+                Some(path) => {
+                    let refresh_seconds = match lookup("EKG_SPARQL_BEARER_TOKEN_REFRESH_SECONDS") {
+                        Some(seconds) => seconds.trim().parse()?,
+                        None => DEFAULT_TOKEN_REFRESH_SECONDS,
+                    };
+                    Arc::new(RefreshableToken::from_file(
+                        path.into(),
+                        Duration::from_secs(refresh_seconds),
+                    ))
+                },
+                None => {
+                    Arc::new(BearerToken::new(
+                        mandatory("EKG_SPARQL_BEARER_TOKEN")?.as_str(),
+                    ))
+                },
+            }
+        },
+        "sigv4" => {
+            let region = lookup("EKG_SPARQL_AWS_REGION")
+                .or_else(|| lookup("AWS_REGION"))
+                .ok_or_else(|| {
+                    Error::MandatoryEnvironmentVariableMissing("EKG_SPARQL_AWS_REGION".to_string())
+                })?;
+            let service =
+                lookup("EKG_SPARQL_AWS_SERVICE").unwrap_or(DEFAULT_AWS_SERVICE.to_string());
+            let credentials = AwsCredentials {
+                access_key_id:     mandatory("AWS_ACCESS_KEY_ID")?,
+                secret_access_key: mandatory("AWS_SECRET_ACCESS_KEY")?,
+                session_token:     lookup("AWS_SESSION_TOKEN"),
+            };
+            Arc::new(SigV4Authentication::new(
+                credentials,
+                region.as_str(),
+                service.as_str(),
+            ))
+        },
+        _ => return Err(Error::UnknownAuthenticationMethod(method)),
+    };
+    tracing::debug!(
+        target: ekg_util::log::LOG_TARGET_SPARQL,
+        "Using {method} authentication for the SPARQL endpoint"
+    );
+    Ok(Some(provider))
+}
+
+/// Set the given header, replacing any previous value. The value is marked
+/// as sensitive so that it does not end up in the logs.
+fn set_header(
+    parts: &mut Parts,
+    name: hyper::http::header::HeaderName,
+    value: &str,
+) -> Result<(), Error> {
+    let mut value = hyper::http::HeaderValue::from_str(value).map_err(hyper::http::Error::from)?;
+    value.set_sensitive(true);
+    parts.headers.insert(name, value);
+    Ok(())
+}
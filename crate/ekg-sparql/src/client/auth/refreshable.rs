@@ -0,0 +1,97 @@
+use {
+    super::{AuthenticationFuture, AuthenticationProvider, set_header},
+    ekg_error::Error,
+    ekg_util::log::LOG_TARGET_SPARQL,
+    hyper::http::{header::AUTHORIZATION, request::Parts},
+    std::{
+        future::Future,
+        path::PathBuf,
+        pin::Pin,
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+/// Tokens are refreshed this long before they expire.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(10);
+
+/// An access token and the moment that it expires, if ever.
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token:      String,
+    pub expires_at: Option<Instant>,
+}
+
+impl AccessToken {
+    fn is_valid(&self) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| Instant::now() + EXPIRY_MARGIN < expires_at)
+    }
+}
+
+/// The future returned by the function that fetches a new [`AccessToken`].
+pub type AccessTokenFuture = Pin<Box<dyn Future<Output = Result<AccessToken, Error>> + Send>>;
+
+type FetchAccessToken = dyn Fn() -> AccessTokenFuture + Send + Sync;
+
+/// An OAuth bearer token that is obtained from the given function, for
+/// instance from an OAuth token endpoint, and that is cached until shortly
+/// before it expires.
+pub struct RefreshableToken {
+    fetch:  Box<FetchAccessToken>,
+    cached: Mutex<Option<AccessToken>>,
+}
+
+impl RefreshableToken {
+    pub fn new<F>(fetch: F) -> Self
+    where F: Fn() -> AccessTokenFuture + Send + Sync + 'static {
+        Self { fetch: Box::new(fetch), cached: Mutex::new(None) }
+    }
+
+    /// A token that is read from the given file and read again after the
+    /// given interval, for tokens that are rotated by some other process
+    /// such as Kubernetes projected service account tokens.
+    pub fn from_file(path: PathBuf, refresh_interval: Duration) -> Self {
+        Self::new(move || {
+            let path = path.clone();
+            Box::pin(async move {
+                Ok(AccessToken {
+                    token:      std::fs::read_to_string(&path)?.trim().to_string(),
+                    expires_at: Some(Instant::now() + refresh_interval),
+                })
+            })
+        })
+    }
+
+    /// Return the cached token or fetch a new one if it has expired.
+    pub async fn token(&self) -> Result<String, Error> {
+        {
+            let cached = self.cached.lock().unwrap();
+            if let Some(token) = cached.as_ref().filter(|token| token.is_valid()) {
+                return Ok(token.token.clone());
+            }
+        }
+        let token = (self.fetch)().await?;
+        tracing::debug!(target: LOG_TARGET_SPARQL, "Refreshed the SPARQL endpoint access token");
+        let value = token.token.clone();
+        *self.cached.lock().unwrap() = Some(token);
+        Ok(value)
+    }
+}
+
+impl AuthenticationProvider for RefreshableToken {
+    fn authenticate<'a>(
+        &'a self,
+        parts: &'a mut Parts,
+        _body: &'a [u8],
+    ) -> AuthenticationFuture<'a> {
+        Box::pin(async move {
+            let token = self.token().await?;
+            set_header(
+                parts,
+                AUTHORIZATION,
+                format!("Bearer {token}").as_str(),
+            )
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use {
+    super::{AuthenticationFuture, AuthenticationProvider, set_header},
+    chrono::{DateTime, Utc},
+    ekg_error::Error,
+    hmac::{Hmac, Mac},
+    hyper::http::{
+        header::{AUTHORIZATION, HOST, HeaderName},
+        request::Parts,
+    },
+    sha2::{Digest, Sha256},
+};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// AWS credentials, as found in the usual `AWS_*` environment variables.
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id:     String,
+    pub secret_access_key: String,
+    pub session_token:     Option<String>,
+}
+
+/// Signs requests with [AWS Signature Version 4](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_sigv.html),
+/// as required by Amazon Neptune when IAM database authentication is enabled.
+pub struct SigV4Authentication {
+    credentials: AwsCredentials,
+    region:      String,
+    service:     String,
+}
+
+impl SigV4Authentication {
+    pub fn new(credentials: AwsCredentials, region: &str, service: &str) -> Self {
+        Self {
+            credentials,
+            region: region.to_string(),
+            service: service.to_string(),
+        }
+    }
+
+    /// Sign the request as if it was sent at the given time.
+    pub(crate) fn sign(
+        &self,
+        parts: &mut Parts,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let host = parts
+            .uri
+            .authority()
+            .map(|authority| authority.as_str().to_string())
+            .ok_or_else(|| Error::InvalidIri(parts.uri.to_string()))?;
+
+        // The headers to sign, in alphabetical order
+        let mut headers = vec![
+            (HOST, host),
+            (
+                HeaderName::from_static("x-amz-date"),
+                amz_date.clone(),
+            ),
+        ];
+        if let Some(session_token) = &self.credentials.session_token {
+            headers.push((
+                HeaderName::from_static("x-amz-security-token"),
+                session_token.clone(),
+            ));
+        }
+        let canonical_headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect::<String>();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+            parts.method,
+            canonical_uri(parts.uri.path()),
+            canonical_query(parts.uri.query().unwrap_or_default()),
+            hex(&Sha256::digest(body))
+        );
+        let scope = format!(
+            "{date}/{}/{}/aws4_request",
+            self.region, self.service
+        );
+        let string_to_sign = format!(
+            "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = [
+            date.as_str(),
+            self.region.as_str(),
+            self.service.as_str(),
+            "aws4_request",
+        ]
+        .iter()
+        .fold(
+            format!("AWS4{}", self.credentials.secret_access_key).into_bytes(),
+            |key, data| hmac_sha256(&key, data.as_bytes()),
+        );
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        for (name, value) in headers {
+            set_header(parts, name, value.as_str())?;
+        }
+        set_header(
+            parts,
+            AUTHORIZATION,
+            format!(
+                "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, \
+                 Signature={signature}",
+                self.credentials.access_key_id
+            )
+            .as_str(),
+        )
+    }
+}
+
+impl AuthenticationProvider for SigV4Authentication {
+    fn authenticate<'a>(
+        &'a self,
+        parts: &'a mut Parts,
+        body: &'a [u8],
+    ) -> AuthenticationFuture<'a> {
+        Box::pin(async move { self.sign(parts, body, Utc::now()) })
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String { bytes.iter().map(|byte| format!("{byte:02x}")).collect() }
+
+/// Encode everything but the unreserved characters of RFC 3986.
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (byte as char).to_string()
+                },
+                _ => format!("%{byte:02X}"),
+            }
+        })
+        .collect()
+}
+
+/// Decode the `%XX` sequences in the given (part of a) URI.
+fn uri_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' &&
+            let Some(byte) = value
+                .get(index + 1..index + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Every segment of the path is encoded (again), except for S3 which we
+/// don't need here.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The query parameters, sorted by name and value and encoded consistently.
+fn canonical_query(query: &str) -> String {
+    let mut params = query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            (
+                uri_encode(&uri_decode(name)),
+                uri_encode(&uri_decode(value)),
+            )
+        })
+        .collect::<Vec<_>>();
+    params.sort();
+    params
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
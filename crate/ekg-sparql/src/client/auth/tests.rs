@@ -0,0 +1,146 @@
+#![cfg(all(test, not(target_family = "wasm")))]
+
+use {
+    crate::client::auth::{
+        AccessToken,
+        AuthenticationProvider,
+        AwsCredentials,
+        RefreshableToken,
+        SigV4Authentication,
+        authentication_from_lookup,
+    },
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::{Duration, Instant},
+    },
+};
+
+fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+    move |name| vars.get(name).cloned()
+}
+
+fn request_parts(uri: &str) -> hyper::http::request::Parts {
+    hyper::Request::get(uri).body(()).unwrap().into_parts().0
+}
+
+/// Sign one of the requests of the AWS Signature Version 4 test suite
+fn sign_aws_test_request(uri: &str) -> Result<String, ekg_error::Error> {
+    let sigv4 = SigV4Authentication::new(
+        AwsCredentials {
+            access_key_id:     "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token:     None,
+        },
+        "us-east-1",
+        "service",
+    );
+    let mut parts = request_parts(uri);
+    let now = chrono::DateTime::parse_from_rfc3339("2015-08-30T12:36:00Z")
+        .unwrap()
+        .to_utc();
+    sigv4.sign(&mut parts, b"", now)?;
+    assert_eq!(parts.headers["x-amz-date"], "20150830T123600Z");
+    assert_eq!(
+        parts.headers[hyper::header::HOST],
+        "example.amazonaws.com"
+    );
+    Ok(parts.headers[hyper::header::AUTHORIZATION]
+        .to_str()
+        .unwrap()
+        .to_string())
+}
+
+#[test_log::test]
+fn test_sigv4_get_vanilla() -> Result<(), ekg_error::Error> {
+    assert_eq!(
+        sign_aws_test_request("https://example.amazonaws.com/")?,
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+         SignedHeaders=host;x-amz-date, \
+         Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_sigv4_get_vanilla_query_order_key_case() -> Result<(), ekg_error::Error> {
+    assert_eq!(
+        sign_aws_test_request("https://example.amazonaws.com/?Param2=value2&Param1=value1")?,
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+         SignedHeaders=host;x-amz-date, \
+         Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_authentication_from_env() -> Result<(), ekg_error::Error> {
+    assert!(authentication_from_lookup(lookup(&[]))?.is_none());
+    assert!(
+        authentication_from_lookup(lookup(&[
+            ("EKG_SPARQL_USERNAME", "user"),
+            ("EKG_SPARQL_PASSWORD", "secret"),
+        ]))?
+        .is_some()
+    );
+    assert!(authentication_from_lookup(lookup(&[("EKG_SPARQL_BEARER_TOKEN", "token")]))?.is_some());
+    assert!(matches!(
+        authentication_from_lookup(lookup(&[("EKG_SPARQL_USERNAME", "user")])),
+        Err(ekg_error::Error::MandatoryEnvironmentVariableMissing(name)) if name == "EKG_SPARQL_PASSWORD"
+    ));
+    assert!(matches!(
+        authentication_from_lookup(lookup(&[
+            ("EKG_SPARQL_AUTH", "sigv4"),
+            ("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+        ])),
+        Err(ekg_error::Error::MandatoryEnvironmentVariableMissing(_))
+    ));
+    assert!(matches!(
+        authentication_from_lookup(lookup(&[("EKG_SPARQL_AUTH", "kerberos")])),
+        Err(ekg_error::Error::UnknownAuthenticationMethod(method)) if method == "kerberos"
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_refreshable_token() -> Result<(), ekg_error::Error> {
+    let fetched = Arc::new(AtomicUsize::new(0));
+    let counter = fetched.clone();
+    let refreshable = RefreshableToken::new(move || {
+        let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        Box::pin(async move {
+            Ok(AccessToken {
+                token:      format!("token-{count}"),
+                // The first token is already (almost) expired, the second one is not
+                expires_at: Some(Instant::now() + Duration::from_secs(60 * (count as u64 - 1))),
+            })
+        })
+    });
+
+    let mut parts = request_parts("https://example.com/sparql");
+    refreshable.authenticate(&mut parts, b"").await?;
+    assert_eq!(
+        parts.headers[hyper::header::AUTHORIZATION],
+        "Bearer token-1"
+    );
+    refreshable.authenticate(&mut parts, b"").await?;
+    assert_eq!(
+        parts.headers[hyper::header::AUTHORIZATION],
+        "Bearer token-2"
+    );
+    refreshable.authenticate(&mut parts, b"").await?;
+    assert_eq!(
+        parts.headers[hyper::header::AUTHORIZATION],
+        "Bearer token-2"
+    );
+    assert_eq!(fetched.load(Ordering::SeqCst), 2);
+    Ok(())
+}
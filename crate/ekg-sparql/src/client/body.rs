@@ -1,4 +1,52 @@
-use {http_body_util::Full, hyper::body::Bytes};
+use {
+    ekg_error::Error,
+    http_body_util::Full,
+    hyper::body::{Bytes, Frame, Incoming, SizeHint},
+    std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+    tokio::time::{Instant, Sleep},
+};
 
 /// Body type for hyper requests using Full<Bytes>.
 pub type Body = Full<Bytes>;
+
+/// The body of a response that fails with [`Error::Timeout`] if it has not
+/// been received completely before the deadline of its request, so that an
+/// endpoint that stalls halfway through a response cannot hang the client.
+pub struct ResponseBody {
+    body:     Incoming,
+    deadline: Option<Pin<Box<Sleep>>>,
+}
+
+impl ResponseBody {
+    pub(crate) fn new(body: Incoming, deadline: Option<Instant>) -> Self {
+        Self {
+            body,
+            deadline: deadline.map(|deadline| Box::pin(tokio::time::sleep_until(deadline))),
+        }
+    }
+}
+
+impl hyper::body::Body for ResponseBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(deadline) = self.deadline.as_mut() &&
+            deadline.as_mut().poll(cx).is_ready()
+        {
+            return Poll::Ready(Some(Err(Error::Timeout)));
+        }
+        Pin::new(&mut self.body).poll_frame(cx).map_err(Error::from)
+    }
+
+    fn is_end_stream(&self) -> bool { self.body.is_end_stream() }
+
+    fn size_hint(&self) -> SizeHint { self.body.size_hint() }
+}
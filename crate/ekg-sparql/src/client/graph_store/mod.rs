@@ -0,0 +1,325 @@
+//! Client for the
+//! [SPARQL 1.1 Graph Store HTTP Protocol](https://www.w3.org/TR/sparql11-http-rdf-update/),
+//! to replace, add to, download or delete whole graphs.
+use {
+    crate::{
+        SPARQLClient,
+        client::{BoxSendFuture, body::ResponseBody},
+    },
+    ekg_error::Error,
+    ekg_metadata::{
+        APPLICATION_N_QUADS,
+        APPLICATION_N_TRIPLES,
+        APPLICATION_TRIG,
+        Graph,
+        TEXT_TURTLE,
+    },
+    ekg_util::{env::mandatory_env_var, log::LOG_TARGET_SPARQL},
+    http_body_util::BodyExt,
+    hyper::{Method, body::Bytes, http::header},
+    mime::Mime,
+    std::{io::Write, path::Path},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Client for a SPARQL Graph Store HTTP Protocol endpoint that addresses
+/// graphs by [`Graph`], where `None` stands for the default graph.
+///
+/// It shares the HTTP client, authentication, retry policy and maximum
+/// response size with the [`SPARQLClient`] it is created from. Requests with
+/// the `GET`, `PUT` and `DELETE` methods are idempotent and therefore
+/// retried, `POST` requests only if updates may be retried.
+#[derive(Clone)]
+pub struct GraphStoreClient {
+    client:   SPARQLClient,
+    endpoint: iri_string::types::IriReferenceString,
+}
+
+impl GraphStoreClient {
+    /// Create a client for the endpoint in `EKG_SPARQL_GRAPH_STORE_ENDPOINT`,
+    /// authenticating as configured by the `EKG_SPARQL_AUTH` variables.
+    pub async fn from_env<E>(executor: E) -> Result<Self, Error>
+    where E: hyper::rt::Executor<BoxSendFuture> + Send + Sync + Clone + 'static {
+        let endpoint = iri_string::types::IriReferenceString::try_from(
+            mandatory_env_var("EKG_SPARQL_GRAPH_STORE_ENDPOINT", None)?.as_str(),
+        )?;
+        let mut client = SPARQLClient::new(executor, &endpoint, None).await?;
+        client.authentication = crate::client::auth::authentication_from_env()?;
+        Ok(Self { client, endpoint })
+    }
+
+    pub async fn new<E>(
+        executor: E,
+        endpoint: &iri_string::types::IriReferenceStr,
+    ) -> Result<Self, Error>
+    where
+        E: hyper::rt::Executor<BoxSendFuture> + Send + Sync + Clone + 'static,
+    {
+        Ok(Self {
+            client:   SPARQLClient::new(executor, endpoint, None).await?,
+            endpoint: endpoint.to_owned(),
+        })
+    }
+
+    /// Create a client for the given endpoint that uses the connections and
+    /// settings of the given [`SPARQLClient`].
+    pub fn from_sparql_client(
+        client: &SPARQLClient,
+        endpoint: &iri_string::types::IriReferenceStr,
+    ) -> Self {
+        Self {
+            client:   client.clone(),
+            endpoint: endpoint.to_owned(),
+        }
+    }
+
+    /// The underlying [`SPARQLClient`], use its `with_*` methods to change
+    /// the authentication, retry policy or maximum response size.
+    pub fn with_sparql_client<F>(mut self, f: F) -> Self
+    where F: FnOnce(SPARQLClient) -> SPARQLClient {
+        self.client = f(self.client);
+        self
+    }
+
+    /// Replace the content of the given graph with the given RDF.
+    pub async fn put_graph<B>(
+        &self,
+        graph: Option<&Graph>,
+        content_type: &Mime,
+        data: B,
+    ) -> Result<(), Error>
+    where
+        B: Into<Bytes>,
+    {
+        self.upload(Method::PUT, graph, content_type, data.into())
+            .await
+    }
+
+    /// Add the given RDF to the given graph, creating the graph if it does
+    /// not exist yet.
+    pub async fn post_graph<B>(
+        &self,
+        graph: Option<&Graph>,
+        content_type: &Mime,
+        data: B,
+    ) -> Result<(), Error>
+    where
+        B: Into<Bytes>,
+    {
+        self.upload(Method::POST, graph, content_type, data.into())
+            .await
+    }
+
+    /// Replace the content of the given graph with the RDF in the given file,
+    /// whose format is derived from its extension, see
+    /// [`rdf_content_type_of_file`].
+    #[cfg(feature = "fs")]
+    pub async fn put_file<P>(&self, graph: Option<&Graph>, file: P) -> Result<(), Error>
+    where P: AsRef<Path> {
+        let (content_type, data) = read_rdf_file(file.as_ref()).await?;
+        self.put_graph(graph, &content_type, data).await
+    }
+
+    /// Add the RDF in the given file to the given graph, like
+    /// `DataStoreConnection::import_data_from_file` does for RDFox.
+    #[cfg(feature = "fs")]
+    pub async fn post_file<P>(&self, graph: Option<&Graph>, file: P) -> Result<(), Error>
+    where P: AsRef<Path> {
+        let (content_type, data) = read_rdf_file(file.as_ref()).await?;
+        self.post_graph(graph, &content_type, data).await
+    }
+
+    /// Download the given graph in the given format and write it to the given
+    /// writer while it is being received. Returns the number of bytes.
+    pub async fn get_graph_to_writer<W: Write>(
+        &self,
+        graph: Option<&Graph>,
+        content_type: &Mime,
+        mut writer: W,
+    ) -> Result<usize, Error> {
+        let description = Self::describe(&Method::GET, graph)?;
+        let mut body = self
+            .send(
+                Method::GET,
+                graph,
+                description.as_str(),
+                true,
+                |builder| {
+                    (
+                        builder.header(header::ACCEPT, content_type.as_ref()),
+                        Bytes::new(),
+                    )
+                },
+            )
+            .await?
+            .into_body();
+        let mut bytes = 0_usize;
+        while let Some(frame) = body.frame().await {
+            // Trailers are ignored
+            if let Ok(data) = frame?.into_data() {
+                bytes += data.len();
+                if let Some(max_bytes) = self.client.max_bytes &&
+                    bytes > max_bytes
+                {
+                    return Err(Error::ExceededMaximumResponseSize {
+                        max_bytes,
+                        query: description,
+                    });
+                }
+                writer.write_all(&data)?;
+            }
+        }
+        writer.flush()?;
+        Ok(bytes)
+    }
+
+    /// Delete the given graph, or clear the default graph.
+    pub async fn delete_graph(&self, graph: Option<&Graph>) -> Result<(), Error> {
+        let description = Self::describe(&Method::DELETE, graph)?;
+        let body = self
+            .send(
+                Method::DELETE,
+                graph,
+                description.as_str(),
+                true,
+                |builder| (builder, Bytes::new()),
+            )
+            .await?
+            .into_body();
+        body.collect().await?;
+        Ok(())
+    }
+
+    async fn upload(
+        &self,
+        method: Method,
+        graph: Option<&Graph>,
+        content_type: &Mime,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        let description = Self::describe(&method, graph)?;
+        let may_retry = method != Method::POST || self.client.retry_policy.retry_updates;
+        tracing::debug!(
+            target: LOG_TARGET_SPARQL,
+            "{description} ({} bytes of {content_type})",
+            data.len()
+        );
+        let body = self
+            .send(
+                method,
+                graph,
+                description.as_str(),
+                may_retry,
+                |builder| {
+                    (
+                        builder.header(header::CONTENT_TYPE, content_type.as_ref()),
+                        data.clone(),
+                    )
+                },
+            )
+            .await?
+            .into_body();
+        body.collect().await?;
+        Ok(())
+    }
+
+    /// Send a request with the given method for the given graph, with the
+    /// headers and body added by the given function.
+    async fn send<F>(
+        &self,
+        method: Method,
+        graph: Option<&Graph>,
+        description: &str,
+        may_retry: bool,
+        f: F,
+    ) -> Result<hyper::Response<ResponseBody>, Error>
+    where
+        F: Fn(hyper::http::request::Builder) -> (hyper::http::request::Builder, Bytes),
+    {
+        let uri = self.graph_uri(graph)?;
+        self.client
+            .send_with_retries(
+                &self.endpoint,
+                description,
+                may_retry,
+                self.client.retry_policy.timeout,
+                || {
+                    let (builder, body) = f(hyper::http::request::Builder::new()
+                        .method(method.clone())
+                        .uri(uri.clone()));
+                    async move { self.client.authenticate(builder.body(())?, body).await }
+                },
+            )
+            .await
+    }
+
+    /// The URI of the given graph: the endpoint with the `graph` parameter
+    /// for a named graph or the `default` parameter for the default graph.
+    fn graph_uri(&self, graph: Option<&Graph>) -> Result<hyper::Uri, Error> {
+        let parameter = match graph {
+            Some(graph) => serde_urlencoded::to_string([("graph", graph.as_iri()?.as_str())])?,
+            None => "default".to_string(),
+        };
+        let separator = if self.endpoint.as_str().contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        Ok(hyper::Uri::try_from(format!(
+            "{}{separator}{parameter}",
+            self.endpoint
+        ))?)
+    }
+
+    /// A description of a request, used in logs and errors.
+    fn describe(method: &Method, graph: Option<&Graph>) -> Result<String, Error> {
+        Ok(match graph {
+            Some(graph) => format!("{method} graph <{}>", graph.as_iri()?),
+            None => format!("{method} default graph"),
+        })
+    }
+}
+
+impl SPARQLClient {
+    /// Create a [`GraphStoreClient`] for the given Graph Store HTTP Protocol
+    /// endpoint that uses the connections and settings of this client.
+    pub fn graph_store(&self, endpoint: &iri_string::types::IriReferenceStr) -> GraphStoreClient {
+        GraphStoreClient::from_sparql_client(self, endpoint)
+    }
+}
+
+/// The media type of an RDF file based on its extension: `.ttl` for Turtle,
+/// `.nt` for N-Triples, `.nq` for N-Quads and `.trig` for TriG.
+pub fn rdf_content_type_of_file(file: &Path) -> Result<Mime, Error> {
+    let extension = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("ttl") => Ok(TEXT_TURTLE.clone()),
+        Some("nt") => Ok(APPLICATION_N_TRIPLES.clone()),
+        Some("nq") => Ok(APPLICATION_N_QUADS.clone()),
+        Some("trig") => Ok(APPLICATION_TRIG.clone()),
+        _ => {
+            Err(Error::UnknownRDFFormat(
+                file.display().to_string(),
+            ))
+        },
+    }
+}
+
+#[cfg(feature = "fs")]
+async fn read_rdf_file(file: &Path) -> Result<(Mime, Bytes), Error> {
+    let content_type = rdf_content_type_of_file(file)?;
+    if !file.exists() {
+        return Err(Error::PathDoesNotExist(
+            file.display().to_string(),
+        ));
+    }
+    Ok((
+        content_type,
+        Bytes::from(tokio::fs::read(file).await?),
+    ))
+}
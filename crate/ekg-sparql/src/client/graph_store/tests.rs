@@ -0,0 +1,174 @@
+#![cfg(all(test, not(target_family = "wasm")))]
+
+use {
+    crate::{
+        GraphStoreClient,
+        RetryPolicy,
+        client::mock_server::{MockResponse, MockServer},
+    },
+    ekg_metadata::{APPLICATION_N_TRIPLES, Graph, Namespace, TEXT_TURTLE},
+    hyper::{Method, StatusCode},
+    std::time::Duration,
+};
+
+const TURTLE: &str = "<https://example.com/s> <https://example.com/p> \"o\" .\n";
+
+fn graph() -> Result<Graph, ekg_error::Error> {
+    Ok(Graph::declare(
+        Namespace::declare_from_str("graph:", "https://example.com/graph/")?,
+        "test",
+    ))
+}
+
+async fn client(server: &MockServer) -> Result<GraphStoreClient, ekg_error::Error> {
+    GraphStoreClient::new(
+        hyper_util::rt::TokioExecutor::new(),
+        &server.endpoint,
+    )
+    .await
+}
+
+fn no_content() -> MockResponse { MockResponse::status(StatusCode::NO_CONTENT, "") }
+
+#[test_log::test(tokio::test)]
+async fn test_graph_store_put_named_graph() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| no_content()).await;
+    let client = client(&server).await?;
+
+    client
+        .put_graph(Some(&graph()?), &TEXT_TURTLE, TURTLE)
+        .await?;
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, Method::PUT);
+    assert_eq!(
+        requests[0].uri.query(),
+        Some("graph=https%3A%2F%2Fexample.com%2Fgraph%2Ftest")
+    );
+    assert_eq!(
+        requests[0].headers[hyper::header::CONTENT_TYPE],
+        "text/turtle"
+    );
+    assert_eq!(requests[0].body, TURTLE.as_bytes());
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_graph_store_post_default_graph() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| no_content()).await;
+    let client = client(&server).await?;
+
+    client
+        .post_graph(
+            None,
+            &APPLICATION_N_TRIPLES,
+            TURTLE.as_bytes().to_vec(),
+        )
+        .await?;
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, Method::POST);
+    assert_eq!(requests[0].uri.query(), Some("default"));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_graph_store_get_graph_to_writer() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/turtle", TURTLE)).await;
+    let client = client(&server).await?;
+
+    let mut buffer = Vec::new();
+    let bytes = client
+        .get_graph_to_writer(Some(&graph()?), &TEXT_TURTLE, &mut buffer)
+        .await?;
+
+    assert_eq!(bytes, TURTLE.len());
+    assert_eq!(String::from_utf8(buffer).unwrap(), TURTLE);
+    let requests = server.requests();
+    assert_eq!(requests[0].method, Method::GET);
+    assert_eq!(
+        requests[0].headers[hyper::header::ACCEPT],
+        "text/turtle"
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_graph_store_get_graph_max_bytes() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/turtle", TURTLE)).await;
+    let client = client(&server)
+        .await?
+        .with_sparql_client(|client| client.with_max_bytes(10));
+
+    let result = client
+        .get_graph_to_writer(None, &TEXT_TURTLE, std::io::sink())
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::ExceededMaximumResponseSize { max_bytes: 10, .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_graph_store_delete_missing_graph() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::status(StatusCode::NOT_FOUND, "")).await;
+    let client = client(&server).await?;
+
+    let error = client.delete_graph(Some(&graph()?)).await.unwrap_err();
+
+    assert_eq!(error.http_status(), Some(404));
+    assert_eq!(server.requests()[0].method, Method::DELETE);
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+#[test_log::test(tokio::test)]
+async fn test_graph_store_post_file() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| no_content()).await;
+    let client = client(&server).await?;
+    let file = std::env::temp_dir().join(format!(
+        "ekg-sparql-graph-store-{}.nt",
+        std::process::id()
+    ));
+    std::fs::write(&file, TURTLE)?;
+
+    let result = client.post_file(Some(&graph()?), &file).await;
+    std::fs::remove_file(&file)?;
+    result?;
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].headers[hyper::header::CONTENT_TYPE],
+        "application/n-triples"
+    );
+    assert_eq!(requests[0].body, TURTLE.as_bytes());
+
+    assert!(matches!(
+        client.post_file(None, "data.rdf").await,
+        Err(ekg_error::Error::UnknownRDFFormat(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_graph_store_retries_put_but_not_post() -> Result<(), ekg_error::Error> {
+    let server =
+        MockServer::start(|_| MockResponse::status(StatusCode::SERVICE_UNAVAILABLE, "")).await;
+    let client = client(&server).await?.with_sparql_client(|client| {
+        client.with_retry_policy(
+            RetryPolicy::default()
+                .with_max_retries(2)
+                .with_backoff(Duration::from_millis(1), Duration::from_millis(1)),
+        )
+    });
+
+    assert!(client.put_graph(None, &TEXT_TURTLE, TURTLE).await.is_err());
+    assert_eq!(server.requests().len(), 3);
+
+    assert!(client.post_graph(None, &TEXT_TURTLE, TURTLE).await.is_err());
+    assert_eq!(server.requests().len(), 4);
+    Ok(())
+}
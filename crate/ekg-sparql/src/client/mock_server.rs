@@ -0,0 +1,180 @@
+//! A minimal HTTP server on `127.0.0.1` to test the SPARQL client against.
+//! It answers every request with the response returned by a given closure
+//! and records all the requests it received.
+use {
+    http_body_util::BodyExt,
+    hyper::{
+        HeaderMap,
+        Method,
+        StatusCode,
+        body::{Bytes, Frame},
+    },
+    hyper_util::rt::TokioIo,
+    std::{
+        future::Future,
+        pin::Pin,
+        sync::{Arc, Mutex},
+        task::{Context, Poll},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub method:  Method,
+    pub uri:     hyper::Uri,
+    pub headers: HeaderMap,
+    pub body:    Bytes,
+}
+
+impl RecordedRequest {
+    /// Decode the `application/x-www-form-urlencoded` body
+    pub fn form(&self) -> Vec<(String, String)> {
+        serde_urlencoded::from_bytes(&self.body).unwrap()
+    }
+}
+
+pub(crate) struct MockResponse {
+    pub status:       StatusCode,
+    pub content_type: &'static str,
+    pub body:         Bytes,
+    pub delay:        Option<std::time::Duration>,
+    pub stall:        Option<std::time::Duration>,
+}
+
+impl MockResponse {
+    pub fn ok(content_type: &'static str, body: &str) -> Self {
+        Self {
+            status: StatusCode::OK,
+            content_type,
+            body: Bytes::from(body.to_string()),
+            delay: None,
+            stall: None,
+        }
+    }
+
+    pub fn status(status: StatusCode, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: Bytes::from(body.to_string()),
+            delay: None,
+            stall: None,
+        }
+    }
+
+    /// Wait for the given time before responding
+    pub fn delayed(mut self, delay: std::time::Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Send the headers and the first half of the body right away but wait
+    /// for the given time before sending the rest of the body
+    pub fn stalled(mut self, stall: std::time::Duration) -> Self {
+        self.stall = Some(stall);
+        self
+    }
+}
+
+/// A response body that sends its first half, then waits before sending
+/// the second half
+struct StalledBody {
+    first:  Option<Bytes>,
+    second: Option<Bytes>,
+    stall:  Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl StalledBody {
+    fn new(mut body: Bytes, stall: Option<std::time::Duration>) -> Self {
+        let second = body.split_off(body.len() / 2);
+        Self {
+            first:  Some(body),
+            second: Some(second),
+            stall:  stall.map(|stall| Box::pin(tokio::time::sleep(stall))),
+        }
+    }
+}
+
+impl hyper::body::Body for StalledBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(first) = self.first.take() {
+            return Poll::Ready(Some(Ok(Frame::data(first))));
+        }
+        if let Some(stall) = self.stall.as_mut() {
+            if stall.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            self.stall = None;
+        }
+        Poll::Ready(self.second.take().map(|second| Ok(Frame::data(second))))
+    }
+}
+
+type Responder = dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync;
+
+pub(crate) struct MockServer {
+    pub endpoint: iri_string::types::IriReferenceString,
+    requests:     Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    pub async fn start<F>(respond: F) -> Self
+    where F: Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = iri_string::types::IriReferenceString::try_from(format!(
+            "http://{}/sparql",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let respond: Arc<Responder> = Arc::new(respond);
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let respond = respond.clone();
+                let recorded = recorded.clone();
+                let service = hyper::service::service_fn(
+                    move |request: hyper::Request<hyper::body::Incoming>| {
+                        let respond = respond.clone();
+                        let recorded = recorded.clone();
+                        async move {
+                            let (parts, body) = request.into_parts();
+                            let request = RecordedRequest {
+                                method:  parts.method,
+                                uri:     parts.uri,
+                                headers: parts.headers,
+                                body:    body.collect().await?.to_bytes(),
+                            };
+                            let response = respond(&request);
+                            recorded.lock().unwrap().push(request);
+                            if let Some(delay) = response.delay {
+                                tokio::time::sleep(delay).await;
+                            }
+                            Ok::<_, hyper::Error>(
+                                hyper::Response::builder()
+                                    .status(response.status)
+                                    .header(hyper::header::CONTENT_TYPE, response.content_type)
+                                    .body(StalledBody::new(response.body, response.stall))
+                                    .unwrap(),
+                            )
+                        }
+                    },
+                );
+                tokio::spawn(async move {
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        Self { endpoint, requests }
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> { self.requests.lock().unwrap().clone() }
+}
@@ -1,41 +1,87 @@
 use {
-    crate::{client::body::Body, statement::Statement, ParsedStatement},
+    crate::{
+        ParsedStatement,
+        SolutionSet,
+        client::{
+            auth::{AuthenticationProvider, authentication_from_env},
+            body::{Body, ResponseBody},
+            quads::{QuadDecoder, quad_from_oxrdf},
+        },
+        solution::boolean_from_sparql_results_json,
+        statement::Statement,
+    },
     ekg_error::Error,
+    ekg_metadata::APPLICATION_SPARQL_RESULTS_JSON,
     ekg_util::{env::mandatory_env_var, log::LOG_TARGET_SPARQL},
     http_body_util::BodyExt,
+    hyper::body::Bytes,
     hyper_rustls::HttpsConnector,
-    hyper_util::client::legacy::{connect::HttpConnector, Client},
+    hyper_util::client::legacy::{Client, connect::HttpConnector},
     mime::APPLICATION_WWW_FORM_URLENCODED,
-    std::{future::Future, pin::Pin},
+    std::{future::Future, io::Write, pin::Pin, sync::Arc, time::Duration},
+};
+pub use {
+    graph_store::{GraphStoreClient, rdf_content_type_of_file},
+    quads::Quad,
+    retry::RetryPolicy,
+    solution_stream::SolutionStream,
 };
 
+pub(crate) mod auth;
 mod body;
+mod graph_store;
+#[cfg(test)]
+pub(crate) mod mock_server;
+pub(crate) mod quads;
+mod retry;
+mod solution_stream;
+#[cfg(test)]
+mod tests;
+
+/// The maximum length of the body of an error response that is kept in
+/// [`Error::SPARQLEndpointError`].
+const MAX_ERROR_BODY_LENGTH: usize = 4096;
 
-type BoxSendFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+pub(crate) type BoxSendFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
 
 /// Simple SPARQL client for sending SPARQL queries (or update statements) to a
 /// SPARQL endpoint.
 #[derive(Clone)]
 pub struct SPARQLClient {
-    pub(crate) client:          Client<HttpsConnector<HttpConnector>, Body>,
-    pub(crate) query_endpoint:  iri_string::types::IriReferenceString,
-    pub(crate) update_endpoint: iri_string::types::IriReferenceString,
+    pub(crate) client:             Client<HttpsConnector<HttpConnector>, Body>,
+    pub(crate) query_endpoint:     iri_string::types::IriReferenceString,
+    pub(crate) update_endpoint:    iri_string::types::IriReferenceString,
+    /// Maximum number of solutions that a query may return
+    pub(crate) max_rows:           Option<usize>,
+    /// Maximum number of bytes that the body of a response may have
+    pub(crate) max_bytes:          Option<usize>,
+    /// Adds credentials to every request
+    pub(crate) authentication:     Option<Arc<dyn AuthenticationProvider>>,
+    /// Timeout and retries of every request
+    pub(crate) retry_policy:       RetryPolicy,
+    /// Extra parameters sent with every request, such as `default-graph-uri`
+    pub(crate) request_parameters: Vec<(String, String)>,
 }
 
 impl SPARQLClient {
+    /// Create a client for the endpoints in `EKG_SPARQL_QUERY_ENDPOINT` and
+    /// `EKG_SPARQL_UPDATE_ENDPOINT`, authenticating as configured by the
+    /// `EKG_SPARQL_AUTH` variables, see [`AuthenticationProvider`].
     pub async fn from_env<E>(executor: E) -> Result<Self, Error>
     where E: hyper::rt::Executor<BoxSendFuture> + Send + Sync + Clone + 'static {
         let query_endpoint = mandatory_env_var("EKG_SPARQL_QUERY_ENDPOINT", None)?;
         let update_endpoint = mandatory_env_var("EKG_SPARQL_UPDATE_ENDPOINT", None)?;
 
-        Self::new(
+        let mut client = Self::new(
             executor,
             &iri_string::types::IriReferenceString::try_from(query_endpoint.as_str())?,
             Some(&iri_string::types::IriReferenceString::try_from(
                 update_endpoint.as_str(),
             )?),
         )
-        .await
+        .await?;
+        client.authentication = authentication_from_env()?;
+        Ok(client)
     }
 
     pub async fn new<E>(
@@ -46,45 +92,104 @@ impl SPARQLClient {
     where
         E: hyper::rt::Executor<BoxSendFuture> + Send + Sync + Clone + 'static,
     {
-        let tls_connector = ekg_util::tls_connector::create().await?;
+        let tls_connector = ekg_util::tls_connector::create_https_or_http().await?;
 
         // Build the hyper client from the HTTPS connector.
         let builder = hyper_util::client::legacy::Client::builder(executor);
         let http_client = builder.build(tls_connector);
 
         Ok(Self {
-            client:          http_client,
-            query_endpoint:  query_endpoint.to_owned(),
-            update_endpoint: if let Some(update_endpoint) = update_endpoint {
+            client:             http_client,
+            query_endpoint:     query_endpoint.to_owned(),
+            update_endpoint:    if let Some(update_endpoint) = update_endpoint {
                 update_endpoint.to_owned()
             } else {
                 query_endpoint.to_owned()
             },
+            max_rows:           None,
+            max_bytes:          None,
+            authentication:     None,
+            retry_policy:       RetryPolicy::default(),
+            request_parameters: Vec::new(),
         })
     }
 
-    /// Convert a SPARQL statement into a hyper::Body, properly encoded.
-    fn statement_as_body(parsed_statement: &ParsedStatement) -> Result<Body, Error> {
-        let operation = if parsed_statement.statement_type.is_update_statement() {
-            "update"
-        } else {
-            "query"
-        };
-        let meal = &[(operation, parsed_statement.statement.as_str())];
+    /// Fail with [`Error::ExceededMaximumNumberOfRows`] when a query returns
+    /// more than the given number of solutions, or than the `ekg:max-rows`
+    /// directive of the statement if that is lower.
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Fail with [`Error::ExceededMaximumResponseSize`] when the body of a
+    /// response is larger than the given number of bytes.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Add credentials to every request with the given provider.
+    pub fn with_authentication<A>(mut self, authentication: A) -> Self
+    where A: AuthenticationProvider + 'static {
+        self.authentication = Some(Arc::new(authentication));
+        self
+    }
+
+    /// Use the given timeout and retries for every request.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Send the given parameter with every request, it can be given more
+    /// than once. The SPARQL protocol parameters `default-graph-uri` and
+    /// `named-graph-uri` are only sent with queries, `using-graph-uri` and
+    /// `using-named-graph-uri` only with update statements.
+    pub fn with_request_parameter(mut self, name: &str, value: &str) -> Self {
+        self.request_parameters
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Convert a SPARQL statement into a request body, properly encoded,
+    /// with the parameters found in the comments of the statement and the
+    /// extra request parameters as additional form fields.
+    fn statement_as_body(&self, parsed_statement: &ParsedStatement) -> Result<Bytes, Error> {
+        let is_update = parsed_statement.statement_type.is_update_statement();
+        let operation = if is_update { "update" } else { "query" };
+        let mut meal = vec![(operation, parsed_statement.statement.as_str())];
+        meal.extend(parsed_statement.statement.request_parameters());
+        meal.extend(
+            self.request_parameters
+                .iter()
+                .filter(|(name, _)| {
+                    match name.as_str() {
+                        "default-graph-uri" | "named-graph-uri" => !is_update,
+                        "using-graph-uri" | "using-named-graph-uri" => is_update,
+                        _ => true,
+                    }
+                })
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
         let body_str = serde_urlencoded::to_string(meal)?;
-        Ok(Body::from(body_str))
+        Ok(Bytes::from(body_str))
     }
 
-    async fn build_request(
-        &self,
-        statement: &Statement,
-    ) -> Result<hyper::Request<Body>, ekg_error::Error> {
-        let parsed_statement = ParsedStatement::parse(statement, None)?;
-        let iri = if parsed_statement.statement_type.is_query_statement() {
+    /// The endpoint that the given statement is sent to.
+    fn endpoint(&self, parsed_statement: &ParsedStatement) -> &iri_string::types::IriReferenceStr {
+        if parsed_statement.statement_type.is_query_statement() {
             &self.query_endpoint
         } else {
             &self.update_endpoint
-        };
+        }
+    }
+
+    async fn build_request(
+        &self,
+        parsed_statement: &ParsedStatement,
+    ) -> Result<hyper::Request<Body>, ekg_error::Error> {
+        let iri = self.endpoint(parsed_statement);
         let hyper_uri = hyper::Uri::try_from(iri.as_str())?;
         let accept_header = parsed_statement
             .statement_type
@@ -104,38 +209,413 @@ impl SPARQLClient {
             )
             // See https://docs.aws.amazon.com/neptune/latest/userguide/access-graph-sparql-http-trailing-headers.html
             .header(hyper::http::header::TE, "trailers, deflate, gzip")
-            .body(Self::statement_as_body(&parsed_statement)?)?;
-        tracing::info!("request: {:?}", request);
+            .body(())?;
+        self.authenticate(request, self.statement_as_body(parsed_statement)?)
+            .await
+    }
+
+    /// Add the credentials of the [`AuthenticationProvider`], if any, to the
+    /// given request and attach the given body to it.
+    pub(crate) async fn authenticate(
+        &self,
+        request: hyper::Request<()>,
+        body: Bytes,
+    ) -> Result<hyper::Request<Body>, Error> {
+        let (mut parts, ()) = request.into_parts();
+        if let Some(authentication) = &self.authentication {
+            authentication.authenticate(&mut parts, &body).await?;
+        }
+        let request = hyper::Request::from_parts(parts, Body::from(body));
+        tracing::trace!(target: LOG_TARGET_SPARQL, "request: {:?}", request);
         Ok(request)
     }
 
-    pub async fn execute(&self, statement: &Statement) -> Result<(), Error> {
-        tracing::debug!(target: LOG_TARGET_SPARQL, "Execute SPARQL statement:\n{}", statement);
-
-        let req = self.build_request(statement).await?;
-        match self.client.request(req).await {
-            Ok(response) => {
-                let status_code = response.status();
-                let (parts, body) = response.into_parts();
-                if !status_code.is_success() {
-                    tracing::info!(
-                        "response: status={:} headers={:?}",
-                        parts.status.as_str(),
-                        parts.headers
+    /// Send the given statement to the endpoint and return the response,
+    /// without receiving its body yet, if the endpoint returned a success
+    /// status code. Transient errors are retried as far as the
+    /// [`RetryPolicy`] allows.
+    async fn send(
+        &self,
+        parsed_statement: &ParsedStatement,
+    ) -> Result<hyper::Response<ResponseBody>, Error> {
+        tracing::debug!(
+            target: LOG_TARGET_SPARQL,
+            "Execute SPARQL statement:\n{}",
+            parsed_statement.statement
+        );
+
+        let may_retry =
+            parsed_statement.statement_type.is_query_statement() || self.retry_policy.retry_updates;
+        let timeout = parsed_statement
+            .statement
+            .directives
+            .timeout
+            .or(self.retry_policy.timeout);
+        self.send_with_retries(
+            self.endpoint(parsed_statement),
+            parsed_statement.statement.as_str(),
+            may_retry,
+            timeout,
+            || self.build_request(parsed_statement),
+        )
+        .await
+    }
+
+    /// Send the request built by the given function to the given endpoint
+    /// and return the response if it has a success status code, building
+    /// and sending it again for transient errors if `may_retry` allows and
+    /// as far as the [`RetryPolicy`] allows, waiting at most the given timeout
+    /// for each attempt. The given statement is only used in the error when
+    /// the endpoint returns another status code.
+    pub(crate) async fn send_with_retries<F, R>(
+        &self,
+        endpoint: &iri_string::types::IriReferenceStr,
+        statement: &str,
+        may_retry: bool,
+        timeout: Option<Duration>,
+        build_request: F,
+    ) -> Result<hyper::Response<ResponseBody>, Error>
+    where
+        F: Fn() -> R,
+        R: Future<Output = Result<hyper::Request<Body>, Error>>,
+    {
+        let policy = &self.retry_policy;
+        let mut retry = 0_u32;
+        loop {
+            let can_retry = may_retry && retry < policy.max_retries;
+            let reason = match self.send_once(build_request().await?, timeout).await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response)
+                    if can_retry && RetryPolicy::is_retryable_status(response.status()) =>
+                {
+                    response.status().to_string()
+                },
+                Ok(response) => {
+                    return Err(Self::endpoint_error(response, endpoint, statement).await);
+                },
+                Err(error @ (Error::Timeout | Error::ServiceError(_))) if can_retry => {
+                    error.to_string()
+                },
+                Err(error) => {
+                    tracing::error!(
+                        target: LOG_TARGET_SPARQL,
+                        "SPARQL request failed after {} attempt(s): {error}",
+                        retry + 1
                     );
-                }
-                // TODO: limit the amount of memory used here
-                let body_bytes = body.collect().await?.to_bytes();
-                let v: serde_json::Value = serde_json::from_slice::<serde_json::Value>(&body_bytes)
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-                tracing::info!("response3: {:?}", serde_json::to_string(&v));
-                Ok(())
+                    return Err(error);
+                },
+            };
+            let backoff = policy.backoff(retry);
+            retry += 1;
+            tracing::warn!(
+                target: LOG_TARGET_SPARQL,
+                "SPARQL request failed ({reason}), retry {retry} of {} in {backoff:?}",
+                policy.max_retries
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Make one attempt to send the given request and return the response
+    /// whatever its status code. The given timeout covers receiving the
+    /// whole response, so reading its body fails with [`Error::Timeout`] as
+    /// well once the timeout has passed.
+    async fn send_once(
+        &self,
+        req: hyper::Request<Body>,
+        timeout: Option<Duration>,
+    ) -> Result<hyper::Response<ResponseBody>, Error> {
+        let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        let response = match deadline {
+            Some(deadline) => {
+                tokio::time::timeout_at(deadline, self.client.request(req))
+                    .await
+                    .map_err(|_| Error::Timeout)?
             },
-            Err(error) => {
-                tracing::error!("error: {:?}", error);
-                // Convert hyper_util::client::legacy::Error to Error via ServiceError
-                Err(Error::ServiceError(format!("{}", error)))
+            None => self.client.request(req).await,
+        };
+        // Convert hyper_util::client::legacy::Error to Error via ServiceError
+        let response = response.map_err(|error| {
+            tracing::debug!(target: LOG_TARGET_SPARQL, "error: {:?}", error);
+            Error::ServiceError(format!("{}", error))
+        })?;
+        Ok(response.map(|body| ResponseBody::new(body, deadline)))
+    }
+
+    /// Turn a response with a status code other than 2xx into an
+    /// [`Error::SPARQLEndpointError`], with (at most) the first
+    /// [`MAX_ERROR_BODY_LENGTH`] bytes of its body.
+    async fn endpoint_error(
+        response: hyper::Response<ResponseBody>,
+        endpoint: &iri_string::types::IriReferenceStr,
+        statement: &str,
+    ) -> Error {
+        let (parts, mut body) = response.into_parts();
+        tracing::error!(
+            target: LOG_TARGET_SPARQL,
+            "response: status={:} headers={:?}",
+            parts.status.as_str(),
+            parts.headers
+        );
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(Ok(frame)) = body.frame().await {
+            if let Ok(data) = frame.into_data() {
+                bytes.extend_from_slice(&data);
+                if bytes.len() > MAX_ERROR_BODY_LENGTH {
+                    truncated = true;
+                    break;
+                }
+            }
+        }
+        let mut body = String::from_utf8_lossy(&bytes).into_owned();
+        if truncated {
+            let mut length = MAX_ERROR_BODY_LENGTH;
+            while !body.is_char_boundary(length) {
+                length -= 1;
+            }
+            body.truncate(length);
+            body.push_str("...");
+        }
+        Error::SPARQLEndpointError {
+            status: parts.status.as_u16(),
+            endpoint: endpoint.to_string(),
+            body,
+            statement: statement.to_string(),
+        }
+    }
+
+    /// Receive the whole body of a response, as long as it does not exceed
+    /// the maximum number of bytes.
+    async fn receive(
+        &self,
+        body: ResponseBody,
+        parsed_statement: &ParsedStatement,
+    ) -> Result<Bytes, Error> {
+        let body = match self.max_bytes {
+            Some(max_bytes) => {
+                http_body_util::Limited::new(body, max_bytes)
+                    .collect()
+                    .await
+                    .map_err(|error| {
+                        // Errors of the body itself, such as a timeout, are passed on
+                        match error.downcast::<Error>() {
+                            Ok(error) => *error,
+                            Err(_) => {
+                                Error::ExceededMaximumResponseSize {
+                                    max_bytes,
+                                    query: parsed_statement.statement.to_string(),
+                                }
+                            },
+                        }
+                    })?
             },
+            None => body.collect().await?,
+        };
+        Ok(body.to_bytes())
+    }
+
+    /// The maximum number of solutions that the given statement may return:
+    /// the lowest of the maximum of this client and its `ekg:max-rows`
+    /// directive.
+    fn max_rows_for(&self, statement: &Statement) -> Option<usize> {
+        match (self.max_rows, statement.directives.max_rows) {
+            (Some(max_rows), Some(directive)) => Some(max_rows.min(directive)),
+            (max_rows, directive) => max_rows.or(directive),
+        }
+    }
+
+    /// Parse the given statement and check that it is of the expected type.
+    fn parse_expecting(
+        statement: &Statement,
+        expected: &str,
+        is_expected: fn(&ParsedStatement) -> bool,
+    ) -> Result<ParsedStatement, Error> {
+        let parsed_statement = ParsedStatement::parse(statement, None)?;
+        if !is_expected(&parsed_statement) {
+            return Err(Error::UnexpectedSPARQLStatementType {
+                expected:  expected.to_string(),
+                statement: statement.to_string(),
+            });
         }
+        Ok(parsed_statement)
+    }
+
+    /// Execute the given statement, ignoring whatever the endpoint returns
+    /// other than its status code. Use this for SPARQL update-statements.
+    pub async fn execute(&self, statement: &Statement) -> Result<(), Error> {
+        let parsed_statement = ParsedStatement::parse(statement, None)?;
+        let body = self.send(&parsed_statement).await?.into_body();
+        self.receive(body, &parsed_statement).await?;
+        Ok(())
+    }
+
+    /// Execute the given SPARQL SELECT query and return its solutions,
+    /// decoded from `application/sparql-results+json`. Other formats, such
+    /// as `application/sparql-results+xml`, are not supported and fail with
+    /// [`Error::InvalidSPARQLResults`].
+    pub async fn query_select(&self, statement: &Statement) -> Result<SolutionSet, Error> {
+        self.query_select_stream(statement)
+            .await?
+            .into_solution_set()
+            .await
+    }
+
+    /// Execute the given SPARQL SELECT query and return a stream of its
+    /// solutions that are decoded while the response is being received.
+    pub async fn query_select_stream(
+        &self,
+        statement: &Statement,
+    ) -> Result<SolutionStream, Error> {
+        let parsed_statement = Self::parse_expecting(
+            statement,
+            "SELECT",
+            ParsedStatement::is_select_statement,
+        )?;
+        let response = self.send(&parsed_statement).await?;
+        check_sparql_results_json(&response)?;
+        Ok(SolutionStream::new(
+            response.into_body(),
+            self.max_rows_for(statement),
+            self.max_bytes,
+            statement.to_string(),
+        ))
+    }
+
+    /// Execute the given SPARQL ASK query and return its boolean result.
+    pub async fn query_ask(&self, statement: &Statement) -> Result<bool, Error> {
+        let parsed_statement = Self::parse_expecting(
+            statement,
+            "ASK",
+            ParsedStatement::is_ask_statement,
+        )?;
+        let response = self.send(&parsed_statement).await?;
+        check_sparql_results_json(&response)?;
+        boolean_from_sparql_results_json(
+            &self
+                .receive(response.into_body(), &parsed_statement)
+                .await?,
+        )
+    }
+
+    /// Execute the given SPARQL CONSTRUCT or DESCRIBE query and return the
+    /// quads of the resulting graph.
+    ///
+    /// The response is parsed as N-Quads, N-Triples, Turtle or TriG depending
+    /// on the `Content-Type` that the endpoint returned.
+    pub async fn query_construct(&self, statement: &Statement) -> Result<Vec<Quad>, Error> {
+        let mut quads = Vec::new();
+        self.for_each_quad(statement, |quad| {
+            quads.push(quad_from_oxrdf(quad, &statement.prefixes)?);
+            Ok(())
+        })
+        .await?;
+        Ok(quads)
+    }
+
+    /// Execute the given SPARQL CONSTRUCT or DESCRIBE query and write the
+    /// quads of the resulting graph in N-Quads format to the given writer
+    /// while the response is being received. Returns the number of quads.
+    pub async fn query_construct_to_writer<W: Write>(
+        &self,
+        statement: &Statement,
+        writer: W,
+    ) -> Result<usize, Error> {
+        let mut serializer = oxttl::NQuadsSerializer::new().for_writer(writer);
+        let count = self
+            .for_each_quad(statement, |quad| {
+                Ok(serializer.serialize_quad(&quad)?)
+            })
+            .await?;
+        serializer.finish().flush()?;
+        Ok(count)
+    }
+
+    /// Execute the given SPARQL CONSTRUCT or DESCRIBE query and call the given
+    /// function for each quad as soon as it has been received.
+    async fn for_each_quad<F>(&self, statement: &Statement, mut f: F) -> Result<usize, Error>
+    where F: FnMut(oxrdf::Quad) -> Result<(), Error> {
+        let parsed_statement =
+            Self::parse_expecting(statement, "CONSTRUCT or DESCRIBE", |parsed| {
+                parsed.is_construct_statement() || parsed.is_describe_statement()
+            })?;
+        let response = self.send(&parsed_statement).await?;
+        let mut decoder = QuadDecoder::for_content_type(
+            response
+                .headers()
+                .get(hyper::http::header::CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok()),
+        )?;
+        let mut body = response.into_body();
+        let max_rows = self.max_rows_for(statement);
+        let mut count = 0_usize;
+        let mut bytes = 0_usize;
+        let mut ended = false;
+        loop {
+            while let Some(quad) = decoder.next_quad()? {
+                count += 1;
+                if let Some(maxrow) = max_rows &&
+                    count > maxrow
+                {
+                    return Err(Error::ExceededMaximumNumberOfRows {
+                        maxrow,
+                        query: statement.to_string(),
+                    });
+                }
+                f(quad)?;
+            }
+            if ended {
+                return Ok(count);
+            }
+            match body.frame().await {
+                Some(frame) => {
+                    // Trailers are ignored, only data frames are decoded
+                    if let Ok(data) = frame?.into_data() {
+                        bytes += data.len();
+                        if let Some(max_bytes) = self.max_bytes &&
+                            bytes > max_bytes
+                        {
+                            return Err(Error::ExceededMaximumResponseSize {
+                                max_bytes,
+                                query: statement.to_string(),
+                            });
+                        }
+                        decoder.push(&data);
+                    }
+                },
+                None => {
+                    decoder.end();
+                    ended = true;
+                },
+            }
+        }
+    }
+}
+
+/// Check that the given response to a SELECT or ASK query has the
+/// `application/sparql-results+json` format that the `Accept` header asks
+/// for (or plain `application/json`), which is assumed if the response has
+/// no `Content-Type`.
+fn check_sparql_results_json(response: &hyper::Response<ResponseBody>) -> Result<(), Error> {
+    let Some(content_type) = response.headers().get(hyper::http::header::CONTENT_TYPE) else {
+        return Ok(());
+    };
+    let mime_type = content_type
+        .to_str()
+        .ok()
+        .and_then(|content_type| content_type.parse::<mime::Mime>().ok());
+    match mime_type {
+        Some(mime_type)
+            if mime_type.essence_str() == APPLICATION_SPARQL_RESULTS_JSON.essence_str() ||
+                mime_type.essence_str() == mime::APPLICATION_JSON.essence_str() =>
+        {
+            Ok(())
+        },
+        _ => {
+            Err(Error::InvalidSPARQLResults(format!(
+                "unsupported content type {content_type:?}, expected {}",
+                APPLICATION_SPARQL_RESULTS_JSON.as_ref()
+            )))
+        },
     }
 }
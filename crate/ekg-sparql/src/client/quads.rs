@@ -0,0 +1,148 @@
+//! Decoding of the RDF that a SPARQL endpoint returns for CONSTRUCT and
+//! DESCRIBE queries.
+use {
+    crate::Prefixes,
+    ekg_error::Error,
+    ekg_metadata::{
+        APPLICATION_N_QUADS,
+        APPLICATION_N_TRIPLES,
+        APPLICATION_TRIG,
+        Graph,
+        Namespace,
+        TEXT_TURTLE,
+        Term,
+    },
+    ekg_util::log::LOG_TARGET_SPARQL,
+    oxttl::{TurtleSyntaxError, nquads::LowLevelNQuadsParser, trig::LowLevelTriGParser},
+};
+
+/// A quad as returned by
+/// [`SPARQLClient::query_construct`](crate::SPARQLClient::query_construct):
+/// subject, predicate, object and the named graph, if any.
+pub type Quad = (Term, Term, Term, Option<Graph>);
+
+/// Incremental parser for the RDF formats that a SPARQL endpoint may return
+/// for a CONSTRUCT or DESCRIBE query, selected by the `Content-Type` of the
+/// response.
+pub(crate) enum QuadDecoder {
+    NQuads(LowLevelNQuadsParser),
+    TriG(LowLevelTriGParser),
+}
+
+impl QuadDecoder {
+    pub(crate) fn for_content_type(content_type: Option<&str>) -> Result<Self, Error> {
+        // Ignore parameters such as "; charset=utf-8"
+        let mime_type = content_type
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|mime_type| mime_type.trim().to_ascii_lowercase());
+        match mime_type.as_deref() {
+            // N-Triples is a subset of N-Quads
+            None => {
+                Ok(Self::NQuads(
+                    oxttl::NQuadsParser::new().low_level(),
+                ))
+            },
+            Some(mime_type)
+                if mime_type == APPLICATION_N_QUADS.as_ref() ||
+                    mime_type == APPLICATION_N_TRIPLES.as_ref() =>
+            {
+                Ok(Self::NQuads(
+                    oxttl::NQuadsParser::new().low_level(),
+                ))
+            },
+            // Turtle is a subset of TriG
+            Some(mime_type)
+                if mime_type == TEXT_TURTLE.as_ref() || mime_type == APPLICATION_TRIG.as_ref() =>
+            {
+                Ok(Self::TriG(oxttl::TriGParser::new().low_level()))
+            },
+            Some(mime_type) => {
+                Err(Error::InvalidRDF(format!(
+                    "unsupported content type {mime_type}"
+                )))
+            },
+        }
+    }
+
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        match self {
+            Self::NQuads(parser) => parser.extend_from_slice(chunk),
+            Self::TriG(parser) => parser.extend_from_slice(chunk),
+        }
+    }
+
+    /// Signal that the last chunk has been pushed.
+    pub(crate) fn end(&mut self) {
+        match self {
+            Self::NQuads(parser) => parser.end(),
+            Self::TriG(parser) => parser.end(),
+        }
+    }
+
+    /// Return the next complete quad, or `None` if more input is needed (or
+    /// at the end).
+    pub(crate) fn next_quad(&mut self) -> Result<Option<oxrdf::Quad>, Error> {
+        let next = match self {
+            Self::NQuads(parser) => parser.parse_next(),
+            Self::TriG(parser) => parser.parse_next(),
+        };
+        next.transpose().map_err(invalid_rdf)
+    }
+}
+
+fn invalid_rdf(error: TurtleSyntaxError) -> Error { Error::InvalidRDF(error.to_string()) }
+
+/// Convert a quad parsed by `oxttl` into a [`Quad`], using the given prefixes
+/// to find the namespace of its named graph.
+pub(crate) fn quad_from_oxrdf(quad: oxrdf::Quad, prefixes: &Prefixes) -> Result<Quad, Error> {
+    let subject = match quad.subject {
+        oxrdf::NamedOrBlankNode::NamedNode(node) => Term::try_from(node)?,
+        oxrdf::NamedOrBlankNode::BlankNode(node) => Term::try_from(node)?,
+    };
+    let predicate = Term::try_from(quad.predicate)?;
+    let object = term_from_oxrdf(quad.object)?;
+    let graph = match quad.graph_name {
+        oxrdf::GraphName::DefaultGraph => None,
+        oxrdf::GraphName::NamedNode(node) => Some(graph_from_iri(node.as_ref(), prefixes)?),
+        oxrdf::GraphName::BlankNode(node) => {
+            return Err(Error::InvalidRDF(format!(
+                "unsupported blank node graph name {node}"
+            )));
+        },
+    };
+    Ok((subject, predicate, object, graph))
+}
+
+/// Convert an RDF term parsed by `oxttl` (or evaluated by `spargebra`) into
+/// a [`Term`]. Literals with a datatype that [`Literal`](ekg_metadata::Literal)
+/// does not support fail with [`Error::UnknownDataType`].
+pub(crate) fn term_from_oxrdf(term: oxrdf::Term) -> Result<Term, Error> { Term::try_from(term) }
+
+/// Split the IRI of a named graph into a [`Namespace`] and a local name,
+/// using the longest matching namespace of the given prefixes or otherwise
+/// the unnamed namespace of `Graph::try_from`.
+fn graph_from_iri(node: oxrdf::NamedNodeRef<'_>, prefixes: &Prefixes) -> Result<Graph, Error> {
+    let iri = node.as_str();
+    let mut namespace: Option<Namespace> = None;
+    prefixes.for_each_namespace_do(|_, candidate| {
+        let is_longer = namespace
+            .as_ref()
+            .is_none_or(|namespace| namespace.iri.as_str().len() < candidate.iri.as_str().len());
+        if is_longer &&
+            iri.strip_prefix(candidate.iri.as_str())
+                .is_some_and(|local_name| !local_name.contains(['/', '#', ':']))
+        {
+            namespace = Some(candidate.clone());
+        }
+        Ok::<(), Error>(())
+    })?;
+    let Some(namespace) = namespace else {
+        tracing::trace!(
+            target: LOG_TARGET_SPARQL,
+            "No prefix declared for the namespace of graph <{iri}>"
+        );
+        return Graph::try_from(node);
+    };
+    let local_name = &iri[namespace.iri.as_str().len()..];
+    Ok(Graph::declare(namespace, local_name))
+}
@@ -0,0 +1,102 @@
+use {
+    hyper::StatusCode,
+    std::{
+        hash::{BuildHasher, Hasher},
+        time::Duration,
+    },
+};
+
+/// Determines how long the [`SPARQLClient`](crate::SPARQLClient) waits for
+/// a response and how often, and when, it retries a request that failed
+/// with a transient error.
+///
+/// Transient errors are timeouts, connection errors and the HTTP status
+/// codes 429, 502, 503 and 504. Only queries are retried unless
+/// [`retry_updates`](RetryPolicy::with_retry_updates) is enabled, since
+/// retrying an update that was (partially) applied may apply it twice.
+///
+/// The default policy has no timeout and does not retry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum time to wait for the response headers of each attempt,
+    /// receiving the body of the response is not limited by this. The
+    /// `ekg:timeout` directive of a statement takes precedence.
+    pub timeout:         Option<Duration>,
+    /// The maximum number of retries after the first attempt
+    pub max_retries:     u32,
+    /// The delay before the first retry, doubled for every next retry
+    pub initial_backoff: Duration,
+    /// The maximum delay between two attempts
+    pub max_backoff:     Duration,
+    /// Whether update-statements are retried as well
+    pub retry_updates:   bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout:         None,
+            max_retries:     0,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff:     Duration::from_secs(10),
+            retry_updates:   false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_retry_updates(mut self, retry_updates: bool) -> Self {
+        self.retry_updates = retry_updates;
+        self
+    }
+
+    /// The delay before the given retry (starting at 0): the exponential
+    /// backoff with a random jitter of up to half of it, so that clients that
+    /// failed at the same time do not all retry at the same time.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        let half = backoff / 2;
+        half + half.mul_f64(random_fraction())
+    }
+
+    pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS |
+                StatusCode::BAD_GATEWAY |
+                StatusCode::SERVICE_UNAVAILABLE |
+                StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+}
+
+/// A random number between 0 and 1, good enough for jitter.
+fn random_fraction() -> f64 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1_u64 << 53) as f64
+}
@@ -0,0 +1,137 @@
+use {
+    crate::{
+        SolutionSet,
+        client::body::ResponseBody,
+        solution::{Solution, SparqlResultsJsonDecoder},
+    },
+    ekg_error::Error,
+    futures_core::Stream,
+    hyper::body::Body as _,
+    std::{
+        pin::Pin,
+        task::{Context, Poll, ready},
+    },
+};
+
+/// The solutions of a SPARQL SELECT query, decoded from the body of the
+/// response of the SPARQL endpoint while it is being received.
+///
+/// Only the row that is currently being decoded is kept in memory, so this
+/// can be used for result sets that are too large to be buffered. The
+/// optional row and byte limits of the [`SPARQLClient`](crate::SPARQLClient)
+/// are enforced while streaming, including for the rows that have to be
+/// buffered when an endpoint sends the `results` before the `head`, and so
+/// is the timeout of its [`RetryPolicy`](crate::RetryPolicy) or of the
+/// `ekg:timeout` directive of the statement.
+pub struct SolutionStream {
+    body:      ResponseBody,
+    decoder:   SparqlResultsJsonDecoder,
+    max_rows:  Option<usize>,
+    max_bytes: Option<usize>,
+    rows:      usize,
+    bytes:     usize,
+    done:      bool,
+    query:     String,
+}
+
+impl SolutionStream {
+    pub(crate) fn new(
+        body: ResponseBody,
+        max_rows: Option<usize>,
+        max_bytes: Option<usize>,
+        query: String,
+    ) -> Self {
+        Self {
+            body,
+            decoder: SparqlResultsJsonDecoder::new().with_max_pending(max_rows),
+            max_rows,
+            max_bytes,
+            rows: 0,
+            bytes: 0,
+            done: false,
+            query,
+        }
+    }
+
+    /// The variables of the result set, available as soon as the `head` of
+    /// the response has been received.
+    pub fn variables(&self) -> Option<&[String]> { self.decoder.variables() }
+
+    /// Return the next solution, or `None` at the end of the result set.
+    pub async fn next_solution(&mut self) -> Option<Result<Solution, Error>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Receive all remaining solutions into a [`SolutionSet`].
+    pub async fn into_solution_set(mut self) -> Result<SolutionSet, Error> {
+        let mut solutions = Vec::new();
+        while let Some(solution) = self.next_solution().await {
+            solutions.push(solution?);
+        }
+        let variables = self.variables().unwrap_or_default().to_vec();
+        Ok(SolutionSet::new(variables, solutions))
+    }
+
+    fn fail(&mut self, error: Error) -> Poll<Option<Result<Solution, Error>>> {
+        self.done = true;
+        Poll::Ready(Some(Err(error)))
+    }
+}
+
+impl Stream for SolutionStream {
+    type Item = Result<Solution, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match this.decoder.next_solution() {
+                Ok(Some(solution)) => {
+                    this.rows += 1;
+                    if let Some(maxrow) = this.max_rows &&
+                        this.rows > maxrow
+                    {
+                        let query = this.query.clone();
+                        return this.fail(Error::ExceededMaximumNumberOfRows { maxrow, query });
+                    }
+                    return Poll::Ready(Some(Ok(solution)));
+                },
+                Ok(None) => {
+                    if let Some(maxrow) = this.max_rows &&
+                        this.decoder.pending() > maxrow
+                    {
+                        let query = this.query.clone();
+                        return this.fail(Error::ExceededMaximumNumberOfRows { maxrow, query });
+                    }
+                },
+                Err(error) => return this.fail(error),
+            }
+            match ready!(Pin::new(&mut this.body).poll_frame(cx)) {
+                Some(Ok(frame)) => {
+                    // Trailers are ignored, only data frames are decoded
+                    if let Ok(data) = frame.into_data() {
+                        this.bytes += data.len();
+                        if let Some(max_bytes) = this.max_bytes &&
+                            this.bytes > max_bytes
+                        {
+                            let query = this.query.clone();
+                            return this
+                                .fail(Error::ExceededMaximumResponseSize { max_bytes, query });
+                        }
+                        this.decoder.push(&data);
+                    }
+                },
+                Some(Err(error)) => return this.fail(error),
+                None => {
+                    this.done = true;
+                    return match this.decoder.finish() {
+                        Ok(()) => Poll::Ready(None),
+                        Err(error) => Poll::Ready(Some(Err(error))),
+                    };
+                },
+            }
+        }
+    }
+}
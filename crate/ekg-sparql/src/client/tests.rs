@@ -0,0 +1,817 @@
+#![cfg(all(test, not(target_family = "wasm")))]
+
+use {
+    crate::{
+        AwsCredentials,
+        BasicAuthentication,
+        BearerToken,
+        Prefixes,
+        RetryPolicy,
+        SPARQLClient,
+        SigV4Authentication,
+        Statement,
+        client::mock_server::{MockResponse, MockServer},
+    },
+    ekg_metadata::{
+        APPLICATION_N_QUADS,
+        APPLICATION_SPARQL_RESULTS_JSON,
+        APPLICATION_SPARQL_RESULTS_XML,
+        Term,
+    },
+    std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    },
+};
+
+const SELECT_RESPONSE: &str = r#"{
+  "head": { "vars": [ "s", "label", "count" ] },
+  "results": {
+    "bindings": [
+      {
+        "s": { "type": "uri", "value": "https://example.com/thing-1" },
+        "label": { "type": "literal", "value": "Thing 1" },
+        "count": {
+          "type": "literal",
+          "value": "42",
+          "datatype": "http://www.w3.org/2001/XMLSchema#integer"
+        }
+      },
+      {
+        "s": { "type": "bnode", "value": "b0" }
+      }
+    ]
+  }
+}"#;
+
+async fn client(server: &MockServer) -> Result<SPARQLClient, ekg_error::Error> {
+    SPARQLClient::new(
+        hyper_util::rt::TokioExecutor::new(),
+        server.endpoint.as_ref(),
+        None,
+    )
+    .await
+}
+
+fn statement(sparql: &str) -> Result<Statement, ekg_error::Error> {
+    Statement::new(Prefixes::builder().build()?, sparql.into())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_select() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            SELECT_RESPONSE,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let solutions = sparql_client
+        .query_select(&statement(
+            "SELECT ?s ?label ?count WHERE { ?s ?label ?count } LIMIT 10",
+        )?)
+        .await?;
+
+    assert_eq!(solutions.variables(), ["s", "label", "count"]);
+    assert_eq!(solutions.len(), 2);
+    let first = &solutions.solutions()[0];
+    assert_eq!(
+        first.value(0).and_then(|s| s.as_iri()).unwrap().as_str(),
+        "https://example.com/thing-1"
+    );
+    assert_eq!(
+        first.value(1).and_then(|l| l.as_str()),
+        Some("Thing 1")
+    );
+    assert_eq!(
+        first.value(2).and_then(|c| c.as_signed_long()),
+        Some(42)
+    );
+    let second = &solutions.solutions()[1];
+    assert!(second.value(0).unwrap().data_type.is_blank_node());
+    assert!(second.value(1).is_none());
+    assert!(second.value(2).is_none());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, hyper::Method::POST);
+    assert_eq!(requests[0].uri.path(), "/sparql");
+    assert_eq!(
+        requests[0].headers[hyper::header::ACCEPT],
+        APPLICATION_SPARQL_RESULTS_JSON.as_ref()
+    );
+    assert_eq!(requests[0].form()[0].0, "query");
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_ask() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            r#"{ "head": {}, "boolean": true }"#,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let answer = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await?;
+
+    assert!(answer);
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_rejects_xml_results() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_XML.as_ref(),
+            r#"<?xml version="1.0"?><sparql xmlns="http://www.w3.org/2005/sparql-results#"/>"#,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let result = sparql_client
+        .query_select(&statement("SELECT * WHERE { ?s ?p ?o }")?)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::InvalidSPARQLResults(message))
+            if message.contains("application/sparql-results+xml")
+    ));
+    let result = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::InvalidSPARQLResults(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_select_rejects_ask() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "")).await;
+    let sparql_client = client(&server).await?;
+
+    let result = sparql_client
+        .query_select(&statement("ASK { ?s ?p ?o }")?)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnexpectedSPARQLStatementType { .. })
+    ));
+    assert!(server.requests().is_empty());
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_execute_update() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "OK")).await;
+    let sparql_client = client(&server).await?;
+
+    sparql_client
+        .execute(&statement(
+            "INSERT DATA { <https://example.com/s> <https://example.com/p> 1 }",
+        )?)
+        .await?;
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].form()[0].0, "update");
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_request_parameters() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|request| {
+        if request.form()[0].0 == "query" {
+            MockResponse::ok(
+                APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+                r#"{ "head": {}, "boolean": true }"#,
+            )
+        } else {
+            MockResponse::ok("text/plain", "OK")
+        }
+    })
+    .await;
+    let sparql_client = client(&server)
+        .await?
+        .with_request_parameter("default-graph-uri", "https://example.com/graph/a")
+        .with_request_parameter("named-graph-uri", "https://example.com/graph/b")
+        .with_request_parameter("using-graph-uri", "https://example.com/graph/c")
+        .with_request_parameter("timeout", "30");
+
+    sparql_client
+        .query_ask(&statement(
+            "# rdfox-query-validation: standard-compliant\nASK { ?s ?p ?o }",
+        )?)
+        .await?;
+    sparql_client
+        .execute(&statement(
+            "INSERT DATA { <https://example.com/s> <https://example.com/p> 1 }",
+        )?)
+        .await?;
+
+    let requests = server.requests();
+    let form = |index: usize| {
+        requests[index]
+            .form()
+            .into_iter()
+            .skip(1)
+            .collect::<Vec<_>>()
+    };
+    let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+    assert_eq!(form(0), vec![
+        pair("query-validation", "standard-compliant"),
+        pair("default-graph-uri", "https://example.com/graph/a"),
+        pair("named-graph-uri", "https://example.com/graph/b"),
+        pair("timeout", "30"),
+    ]);
+    assert_eq!(form(1), vec![
+        pair("using-graph-uri", "https://example.com/graph/c"),
+        pair("timeout", "30"),
+    ]);
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_error_status() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::status(
+            hyper::StatusCode::SERVICE_UNAVAILABLE,
+            "try again later",
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let result = sparql_client
+        .query_select(&statement("SELECT * WHERE { ?s ?p ?o }")?)
+        .await;
+
+    let Err(error) = result else {
+        panic!("expected an error");
+    };
+    assert_eq!(error.http_status(), Some(503));
+    assert!(error.is_server_error());
+    let ekg_error::Error::SPARQLEndpointError { endpoint, body, statement, .. } = &error else {
+        panic!("expected a SPARQLEndpointError, got {error}");
+    };
+    assert_eq!(endpoint, server.endpoint.as_str());
+    assert_eq!(body, "try again later");
+    assert!(statement.contains("SELECT * WHERE"));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_distinguishes_error_statuses() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|request| {
+        if request.form()[0].1.contains("ASK") {
+            MockResponse::status(hyper::StatusCode::UNAUTHORIZED, "who are you?")
+        } else {
+            MockResponse::status(
+                hyper::StatusCode::BAD_REQUEST,
+                "x".repeat(10_000).as_str(),
+            )
+        }
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let error = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await
+        .unwrap_err();
+    assert!(error.is_unauthorized());
+    assert!(!error.is_bad_request());
+
+    let error = sparql_client
+        .query_select(&statement("SELECT * WHERE { ?s ?p ?o }")?)
+        .await
+        .unwrap_err();
+    assert!(error.is_bad_request());
+    let ekg_error::Error::SPARQLEndpointError { body, .. } = &error else {
+        panic!("expected a SPARQLEndpointError, got {error}");
+    };
+    assert!(body.len() < 5_000);
+    assert!(body.ends_with("..."));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_select_stream() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            SELECT_RESPONSE,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let mut stream = sparql_client
+        .query_select_stream(&statement(
+            "SELECT ?s ?label ?count WHERE { ?s ?label ?count }",
+        )?)
+        .await?;
+
+    let first = stream.next_solution().await.unwrap()?;
+    assert_eq!(stream.variables().unwrap(), [
+        "s", "label", "count"
+    ]);
+    assert_eq!(
+        first.value(1).and_then(|l| l.as_str()),
+        Some("Thing 1")
+    );
+    assert!(stream.next_solution().await.unwrap().is_ok());
+    assert!(stream.next_solution().await.is_none());
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_max_rows() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            SELECT_RESPONSE,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?.with_max_rows(1);
+
+    let result = sparql_client
+        .query_select(&statement(
+            "SELECT ?s ?label ?count WHERE { ?s ?label ?count }",
+        )?)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::ExceededMaximumNumberOfRows { maxrow: 1, .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_max_rows_before_head() -> Result<(), ekg_error::Error> {
+    // The rows cannot be handed out before the head arrives, so they are
+    // buffered and have to be counted against the limit right away
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            r#"{
+              "results": { "bindings": [
+                { "s": { "type": "literal", "value": "1" } },
+                { "s": { "type": "literal", "value": "2" } }
+              ] },
+              "head": { "vars": [ "s" ] }
+            }"#,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?.with_max_rows(1);
+
+    let mut stream = sparql_client
+        .query_select_stream(&statement("SELECT ?s WHERE { ?s ?p ?o }")?)
+        .await?;
+
+    assert!(matches!(
+        stream.next_solution().await,
+        Some(Err(
+            ekg_error::Error::ExceededMaximumNumberOfRows { maxrow: 1, .. }
+        ))
+    ));
+    assert!(stream.next_solution().await.is_none());
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_max_rows_directive() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            SELECT_RESPONSE,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?.with_max_rows(100);
+
+    let result = sparql_client
+        .query_select(&statement(
+            "# ekg:max-rows: 1\nSELECT ?s ?label ?count WHERE { ?s ?label ?count }",
+        )?)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::ExceededMaximumNumberOfRows { maxrow: 1, .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_max_bytes() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            SELECT_RESPONSE,
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?.with_max_bytes(64);
+
+    let result = sparql_client
+        .query_select(&statement(
+            "SELECT ?s ?label ?count WHERE { ?s ?label ?count }",
+        )?)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::ExceededMaximumResponseSize { max_bytes: 64, .. })
+    ));
+    Ok(())
+}
+
+const CONSTRUCT_RESPONSE: &str = r#"<https://example.com/s> <https://example.com/p> "chat"@fr <https://example.com/graph/g1> .
+<https://example.com/s> <https://example.com/p> "42"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:b0 <https://example.com/p> <https://example.com/o> .
+"#;
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_construct() -> Result<(), ekg_error::Error> {
+    let server =
+        MockServer::start(|_| MockResponse::ok(APPLICATION_N_QUADS.as_ref(), CONSTRUCT_RESPONSE))
+            .await;
+    let sparql_client = client(&server).await?;
+
+    let quads = sparql_client
+        .query_construct(&statement(
+            "CONSTRUCT { ?s ?p ?o } WHERE { ?s ?p ?o }",
+        )?)
+        .await?;
+
+    assert_eq!(quads.len(), 3);
+    let (subject, _, object, graph) = &quads[0];
+    assert!(matches!(subject, Term::Iri(_)));
+    assert_eq!(object.display_turtle().to_string(), "\"chat\"@fr");
+    let graph = graph.as_ref().unwrap();
+    assert_eq!(
+        graph.as_iri()?.as_str(),
+        "https://example.com/graph/g1"
+    );
+    assert_eq!(graph.local_name, "g1");
+    let (_, _, object, graph) = &quads[1];
+    assert!(matches!(object, Term::Literal(literal) if literal.as_signed_long() == Some(42)));
+    assert!(graph.is_none());
+    assert!(matches!(quads[2].0, Term::BlankNode(_)));
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].headers[hyper::header::ACCEPT],
+        APPLICATION_N_QUADS.as_ref()
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_construct_urn_graph() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_N_QUADS.as_ref(),
+            "<https://example.com/s> <https://example.com/p> <https://example.com/o> \
+             <urn:example:g1> .\n",
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let quads = sparql_client
+        .query_construct(&statement(
+            "CONSTRUCT { ?s ?p ?o } WHERE { ?s ?p ?o }",
+        )?)
+        .await?;
+
+    let graph = quads[0].3.as_ref().unwrap();
+    assert_eq!(graph.as_iri()?.as_str(), "urn:example:g1");
+    assert_eq!(graph.local_name, "g1");
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_describe_turtle() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            "text/turtle; charset=utf-8",
+            "@prefix ex: <https://example.com/> .\nex:s ex:p ex:o1, ex:o2 .\n",
+        )
+    })
+    .await;
+    let sparql_client = client(&server).await?;
+
+    let quads = sparql_client
+        .query_construct(&statement("DESCRIBE <https://example.com/s>")?)
+        .await?;
+
+    assert_eq!(quads.len(), 2);
+    assert_eq!(
+        quads[1].2.display_turtle().to_string(),
+        "<https://example.com/o2>"
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_construct_to_writer() -> Result<(), ekg_error::Error> {
+    let server =
+        MockServer::start(|_| MockResponse::ok(APPLICATION_N_QUADS.as_ref(), CONSTRUCT_RESPONSE))
+            .await;
+    let sparql_client = client(&server).await?;
+
+    let mut buffer = Vec::new();
+    let count = sparql_client
+        .query_construct_to_writer(
+            &statement("CONSTRUCT { ?s ?p ?o } WHERE { ?s ?p ?o }")?,
+            &mut buffer,
+        )
+        .await?;
+
+    assert_eq!(count, 3);
+    let nquads = String::from_utf8(buffer).unwrap();
+    assert_eq!(nquads.lines().count(), 3);
+    assert!(nquads.starts_with(
+        "<https://example.com/s> <https://example.com/p> \"chat\"@fr <https://example.com/graph/g1> ."
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_query_construct_rejects_select() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "")).await;
+    let sparql_client = client(&server).await?;
+
+    let result = sparql_client
+        .query_construct(&statement("SELECT * WHERE { ?s ?p ?o }")?)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnexpectedSPARQLStatementType { .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_basic_authentication() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|request| {
+        match request.headers.get(hyper::header::AUTHORIZATION) {
+            // base64 of "user:secret"
+            Some(value) if value == "Basic dXNlcjpzZWNyZXQ=" => {
+                MockResponse::ok(
+                    APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+                    r#"{ "head": {}, "boolean": true }"#,
+                )
+            },
+            _ => MockResponse::status(hyper::StatusCode::UNAUTHORIZED, ""),
+        }
+    })
+    .await;
+    let sparql_client = client(&server)
+        .await?
+        .with_authentication(BasicAuthentication::new("user", "secret"));
+
+    assert!(
+        sparql_client
+            .query_ask(&statement("ASK { ?s ?p ?o }")?)
+            .await?
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_bearer_token() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "OK")).await;
+    let sparql_client = client(&server)
+        .await?
+        .with_authentication(BearerToken::new("let-me-in"));
+
+    sparql_client.execute(&statement("CLEAR DEFAULT")?).await?;
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].headers[hyper::header::AUTHORIZATION],
+        "Bearer let-me-in"
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_sigv4_authentication() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "OK")).await;
+    let sparql_client = client(&server)
+        .await?
+        .with_authentication(SigV4Authentication::new(
+            AwsCredentials {
+                access_key_id:     "AKIDEXAMPLE".to_string(),
+                secret_access_key: "secret".to_string(),
+                session_token:     Some("session".to_string()),
+            },
+            "eu-west-1",
+            "neptune-db",
+        ));
+
+    sparql_client.execute(&statement("CLEAR DEFAULT")?).await?;
+
+    let requests = server.requests();
+    let headers = &requests[0].headers;
+    let authorization = headers[hyper::header::AUTHORIZATION].to_str().unwrap();
+    assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+    assert!(authorization.contains("/eu-west-1/neptune-db/aws4_request"));
+    assert!(authorization.contains("SignedHeaders=host;x-amz-date;x-amz-security-token"));
+    assert_eq!(headers["x-amz-security-token"], "session");
+    assert_eq!(
+        headers[hyper::header::HOST],
+        server.endpoint.authority_str().unwrap()
+    );
+    Ok(())
+}
+
+/// A mock server that responds with 503 to the first `failures` requests
+async fn flaky_server(failures: usize) -> MockServer {
+    let count = AtomicUsize::new(0);
+    MockServer::start(move |_| {
+        if count.fetch_add(1, Ordering::SeqCst) < failures {
+            MockResponse::status(hyper::StatusCode::SERVICE_UNAVAILABLE, "busy")
+        } else {
+            MockResponse::ok(
+                APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+                r#"{ "head": {}, "boolean": true }"#,
+            )
+        }
+    })
+    .await
+}
+
+fn fast_retries(max_retries: u32) -> RetryPolicy {
+    RetryPolicy::default()
+        .with_max_retries(max_retries)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_retries_query() -> Result<(), ekg_error::Error> {
+    let server = flaky_server(2).await;
+    let sparql_client = client(&server).await?.with_retry_policy(fast_retries(3));
+
+    assert!(
+        sparql_client
+            .query_ask(&statement("ASK { ?s ?p ?o }")?)
+            .await?
+    );
+    assert_eq!(server.requests().len(), 3);
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_gives_up_after_max_retries() -> Result<(), ekg_error::Error> {
+    let server = flaky_server(5).await;
+    let sparql_client = client(&server).await?.with_retry_policy(fast_retries(2));
+
+    let result = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await;
+
+    assert_eq!(result.unwrap_err().http_status(), Some(503));
+    assert_eq!(server.requests().len(), 3);
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_retries_update_only_when_opted_in() -> Result<(), ekg_error::Error> {
+    let update = statement("CLEAR DEFAULT")?;
+
+    let server = flaky_server(1).await;
+    let sparql_client = client(&server).await?.with_retry_policy(fast_retries(3));
+    assert!(sparql_client.execute(&update).await.is_err());
+    assert_eq!(server.requests().len(), 1);
+
+    let server = flaky_server(1).await;
+    let sparql_client = client(&server)
+        .await?
+        .with_retry_policy(fast_retries(3).with_retry_updates(true));
+    sparql_client.execute(&update).await?;
+    assert_eq!(server.requests().len(), 2);
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_timeout() -> Result<(), ekg_error::Error> {
+    let server =
+        MockServer::start(|_| MockResponse::ok("text/plain", "OK").delayed(Duration::from_secs(5)))
+            .await;
+    let sparql_client = client(&server)
+        .await?
+        .with_retry_policy(fast_retries(1).with_timeout(Duration::from_millis(50)));
+
+    let result = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await;
+
+    assert!(matches!(result, Err(ekg_error::Error::Timeout)));
+    assert_eq!(server.requests().len(), 2);
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_timeout_while_reading_body() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|request| {
+        let query = request.form()[0].1.clone();
+        let response = if query.contains("ASK") {
+            MockResponse::ok(
+                APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+                r#"{ "head": {}, "boolean": true }"#,
+            )
+        } else if query.contains("SELECT") {
+            MockResponse::ok(
+                APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+                r#"{ "head": { "vars": [ "s" ] }, "results": { "bindings": [ ] } }"#,
+            )
+        } else {
+            MockResponse::ok(
+                "application/n-triples",
+                "<https://example.com/s> <https://example.com/p> <https://example.com/o> .\n",
+            )
+        };
+        response.stalled(Duration::from_secs(5))
+    })
+    .await;
+    let sparql_client = client(&server)
+        .await?
+        .with_retry_policy(RetryPolicy::default().with_timeout(Duration::from_millis(100)));
+
+    let result = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await;
+    assert!(matches!(result, Err(ekg_error::Error::Timeout)));
+
+    let result = sparql_client
+        .query_select(&statement("SELECT ?s WHERE { ?s ?p ?o }")?)
+        .await;
+    assert!(matches!(result, Err(ekg_error::Error::Timeout)));
+
+    let result = sparql_client
+        .query_construct(&statement("CONSTRUCT WHERE { ?s ?p ?o }")?)
+        .await;
+    assert!(matches!(result, Err(ekg_error::Error::Timeout)));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sparql_client_timeout_directive() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            r#"{ "head": {}, "boolean": true }"#,
+        )
+        .delayed(Duration::from_millis(200))
+    })
+    .await;
+    let sparql_client = client(&server)
+        .await?
+        .with_retry_policy(RetryPolicy::default().with_timeout(Duration::from_millis(50)));
+
+    let result = sparql_client
+        .query_ask(&statement("ASK { ?s ?p ?o }")?)
+        .await;
+    assert!(matches!(result, Err(ekg_error::Error::Timeout)));
+
+    assert!(
+        sparql_client
+            .query_ask(&statement("# ekg:timeout: 5s\nASK { ?s ?p ?o }")?)
+            .await?
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_retry_policy_backoff() {
+    let policy = RetryPolicy::default().with_backoff(
+        Duration::from_millis(100),
+        Duration::from_millis(1000),
+    );
+    for _ in 0..10 {
+        let first = policy.backoff(0);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let third = policy.backoff(2);
+        assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+        let capped = policy.backoff(20);
+        assert!(capped >= Duration::from_millis(500) && capped <= Duration::from_millis(1000));
+    }
+}
@@ -1,5 +1,17 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FactDomain {
     ASSERTED,
     INFERRED,
     ALL,
 }
+
+impl FactDomain {
+    /// The value of the `fact-domain` parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FactDomain::ASSERTED => "explicit",
+            FactDomain::INFERRED => "derived",
+            FactDomain::ALL => "all",
+        }
+    }
+}
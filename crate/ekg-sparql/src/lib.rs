@@ -3,28 +3,57 @@
 // #![feature(rustc_private)]
 // #![feature(ptr_metadata)]
 
+#[cfg(feature = "fs")]
+pub use library::StatementLibrary;
 pub use {
-    client::SPARQLClient,
+    class_report::ClassReport,
+    client::{
+        GraphStoreClient,
+        Quad,
+        RetryPolicy,
+        SPARQLClient,
+        SolutionStream,
+        auth::{
+            AccessToken,
+            AccessTokenFuture,
+            AuthenticationFuture,
+            AuthenticationProvider,
+            AwsCredentials,
+            BasicAuthentication,
+            BearerToken,
+            RefreshableToken,
+            SigV4Authentication,
+        },
+        rdf_content_type_of_file,
+    },
     datastore_type::DatastoreType,
     fact_domain::FactDomain,
     flavor::SPARQLFlavor,
-    parameters::Parameters,
-    parser::ParsedStatement,
+    parameters::{EngineVersion, ParameterDomain, ParameterSpec, Parameters, PARAMETER_SPECS},
+    parser::{ParsedStatement, StatementAnalysis},
     persistence_mode::PersistenceMode,
-    prefixes::Prefixes,
+    prefixes::{Prefixes, PrefixesDeclareOutcome, PrefixesDeclareResult},
+    solution::{Solution, SolutionSet},
     statement::{
-        no_comments, SPARQLStatementType, Statement, RDFOX_QUERY_VALIDATION,
-        RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT,
+        no_comments, ParameterDirective, SPARQLStatementType, Statement, StatementDirectives,
+        RDFOX_QUERY_VALIDATION, RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT,
     },
+    store::{Federation, StoreFuture, TripleStore},
 };
-
+#[cfg(feature = "oxigraph-support")]
+pub use store::OxigraphStore;
+mod class_report;
 mod client;
 mod flavor;
+#[cfg(feature = "fs")]
+mod library;
 mod parser;
 mod prefixes;
+mod solution;
 mod statement;
 #[cfg(test)]
 mod tests;
+mod store;
 
 mod datastore_type;
 mod fact_domain;
@@ -32,6 +61,3 @@ mod parameters;
 mod persistence_mode;
 #[cfg(feature = "_rdfox")]
 pub mod rdfox;
-
-#[cfg(all(feature = "fs", not(feature = "_rdfox")))]
-use ignore as _;
@@ -0,0 +1,6 @@
+//! A library of SPARQL statements loaded from a directory of `.sparql` files
+pub use this::StatementLibrary;
+
+#[cfg(test)]
+mod tests;
+mod this;
@@ -0,0 +1,130 @@
+#![cfg(all(test, not(target_family = "wasm")))]
+
+use std::path::{Path, PathBuf};
+
+/// Create a fresh directory with the given files for one test.
+fn directory(test: &str, files: &[(&str, &str)]) -> std::io::Result<PathBuf> {
+    let root = std::env::temp_dir().join(format!(
+        "ekg-sparql-{test}-{}",
+        std::process::id()
+    ));
+    if root.exists() {
+        std::fs::remove_dir_all(&root)?;
+    }
+    for (path, content) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+        std::fs::write(path, content)?;
+    }
+    Ok(root)
+}
+
+fn prefixes() -> Result<crate::Prefixes, ekg_error::Error> {
+    crate::Prefixes::builder()
+        .default_namespaces()
+        .declare(&ekg_metadata::Namespace::declare_from_str(
+            "ex:",
+            "https://example.com/",
+        )?)
+        .build()
+}
+
+#[test_log::test]
+fn test_load_statement_library() -> Result<(), ekg_error::Error> {
+    let root = directory("library", &[
+        (
+            "people/by-email.sparql",
+            "# ekg:name: people-by-email\n# ekg:param ?email\nSELECT ?person WHERE { ?person \
+             ex:email ?email }",
+        ),
+        (
+            "people/count.sparql",
+            "SELECT (COUNT(?person) AS ?count) WHERE { ?person a ex:Person }",
+        ),
+        (
+            "cleanup.sparql",
+            "DELETE WHERE { ?person ex:email ?email }",
+        ),
+        ("notes.txt", "not a statement"),
+        (".gitignore", "scratch/\n"),
+        ("scratch/broken.sparql", "SELECT WHERE"),
+    ])?;
+    let library = crate::StatementLibrary::load(&root, &prefixes()?)?;
+
+    assert_eq!(
+        library.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+        vec!["cleanup", "people/by-email", "people/count"]
+    );
+    assert_eq!(library.names().collect::<Vec<_>>(), vec![
+        "people-by-email"
+    ]);
+    let by_email = library.statement("people-by-email")?;
+    assert!(by_email.is_select_statement());
+    assert_eq!(by_email.statement.directives.params.len(), 1);
+    assert!(std::ptr::eq(
+        by_email,
+        library.statement("people/by-email.sparql")?
+    ));
+    assert!(library.get("people/count").is_some());
+    assert!(library.statement("cleanup")?.is_update_statement());
+    assert!(matches!(
+        library.statement("people/unknown"),
+        Err(ekg_error::Error::UnknownStatement { .. })
+    ));
+
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+}
+
+#[test_log::test]
+fn test_load_invalid_statement_library() -> Result<(), ekg_error::Error> {
+    let root = directory("invalid-library", &[
+        ("a.sparql", "# ekg:name: same\nASK { ?s ?p ?o }"),
+        ("b.sparql", "# ekg:name: same\nASK { ?s ?p ?o }"),
+        ("c.sparql", "SELECT ?s WHERE { ?s unknown:p ?o }"),
+        ("d/e.sparql", "SELECT ?s WHERE { ?s ?p }"),
+        ("f.sparql", "ASK { ?s ?p ?o }"),
+    ])?;
+    let result = crate::StatementLibrary::load(&root, &prefixes()?);
+    std::fs::remove_dir_all(&root)?;
+
+    let Err(ekg_error::Error::InvalidStatementLibrary { errors, .. }) = result else {
+        panic!("expected an invalid statement library");
+    };
+    assert_eq!(
+        errors
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect::<Vec<_>>(),
+        vec!["b", "c", "d/e"]
+    );
+    assert!(matches!(
+        errors[0].1,
+        ekg_error::Error::DuplicateStatementName { .. }
+    ));
+    assert!(matches!(
+        errors[1].1,
+        ekg_error::Error::UndeclaredPrefix { .. }
+    ));
+    assert!(matches!(
+        errors[2].1,
+        ekg_error::Error::SPARQLStatementError { .. }
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_load_missing_statement_library() -> Result<(), ekg_error::Error> {
+    let root = directory("missing-library", &[])?;
+    let result = crate::StatementLibrary::load(&root.join("missing"), &prefixes()?);
+
+    let Err(ekg_error::Error::InvalidStatementLibrary { errors, .. }) = result else {
+        panic!("expected an invalid statement library");
+    };
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0].1,
+        ekg_error::Error::WalkError(_)
+    ));
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use {
+    crate::{ParsedStatement, Prefixes, Statement},
+    ekg_error::Error,
+    ekg_util::log::LOG_TARGET_FILES,
+    std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The extension of the files that are loaded into a [`StatementLibrary`].
+const EXTENSION: &str = "sparql";
+
+/// A set of parsed SPARQL statements loaded from a directory of `.sparql`
+/// files, typically kept in git next to the service that uses them.
+///
+/// Each statement can be found by its path relative to the root directory,
+/// with or without the `.sparql` extension (such as `people/by-email`), or
+/// by the name that it declares with an `# ekg:name:` directive.
+#[derive(Clone)]
+pub struct StatementLibrary {
+    root:       PathBuf,
+    prefixes:   Prefixes,
+    /// The statements by their relative path without extension
+    statements: BTreeMap<String, ParsedStatement>,
+    /// The relative paths of the statements by their declared name
+    names:      BTreeMap<String, String>,
+}
+
+impl StatementLibrary {
+    /// Load all `.sparql` files in the given directory and its
+    /// subdirectories, skipping the files that are ignored by `.gitignore`
+    /// files (also outside of a git repository).
+    ///
+    /// Each file is parsed with the prefixes that it uses from the given
+    /// prefixes (see [`Statement::new_with_inferred_prefixes`]). All files
+    /// are validated before returning, so that
+    /// [`Error::InvalidStatementLibrary`] reports every file that could not
+    /// be loaded or parsed, every directory that could not be read and every
+    /// name that is declared more than once.
+    pub fn load(root: &Path, prefixes: &Prefixes) -> Result<Self, Error> {
+        tracing::debug!(
+            target: LOG_TARGET_FILES,
+            "Loading SPARQL statements from directory {root:?}"
+        );
+        let mut types = ignore::types::TypesBuilder::new();
+        types.add(EXTENSION, "*.sparql")?;
+        let file_types = types.select(EXTENSION).build()?;
+
+        let walker = ignore::WalkBuilder::new(root)
+            .standard_filters(true)
+            .ignore(false)
+            .git_global(true)
+            .git_ignore(true)
+            .git_exclude(true)
+            .require_git(false)
+            .follow_links(false)
+            .parents(false)
+            .types(file_types)
+            // So that the first of two statements with the same name wins
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+
+        let mut library = Self {
+            root:       root.to_path_buf(),
+            prefixes:   prefixes.clone(),
+            statements: BTreeMap::new(),
+            names:      BTreeMap::new(),
+        };
+        let mut errors = Vec::new();
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    let path = walk_error_path(&err).unwrap_or(root);
+                    errors.push((relative_key(root, path), Error::from(err)));
+                    continue;
+                },
+            };
+            if !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                continue;
+            }
+            let key = relative_key(root, entry.path());
+            match load_file(entry.path(), prefixes) {
+                Ok(statement) => {
+                    if let Some(name) = statement.statement.directives.name.clone() {
+                        if let Some(path) = library.names.get(&name) {
+                            let path = path.clone();
+                            errors.push((key, Error::DuplicateStatementName { name, path }));
+                            continue;
+                        }
+                        library.names.insert(name, key.clone());
+                    }
+                    library.statements.insert(key, statement);
+                },
+                Err(err) => errors.push((key, err)),
+            }
+        }
+        if !errors.is_empty() {
+            errors.sort_by(|a, b| a.0.cmp(&b.0));
+            return Err(Error::InvalidStatementLibrary { root: root.display().to_string(), errors });
+        }
+        tracing::debug!(
+            target: LOG_TARGET_FILES,
+            "Loaded {} SPARQL statements from directory {root:?}",
+            library.len()
+        );
+        Ok(library)
+    }
+
+    /// The statement with the given name or relative path, with or without
+    /// its `.sparql` extension.
+    pub fn get(&self, name_or_path: &str) -> Option<&ParsedStatement> {
+        let path = self
+            .names
+            .get(name_or_path)
+            .map(String::as_str)
+            .unwrap_or(name_or_path);
+        let path = path
+            .strip_suffix(".sparql")
+            .unwrap_or(path)
+            .trim_start_matches("./");
+        self.statements.get(path)
+    }
+
+    /// The statement with the given name or relative path, see
+    /// [`StatementLibrary::get`].
+    pub fn statement(&self, name_or_path: &str) -> Result<&ParsedStatement, Error> {
+        self.get(name_or_path).ok_or_else(|| {
+            Error::UnknownStatement {
+                name: name_or_path.to_string(),
+                root: self.root.display().to_string(),
+            }
+        })
+    }
+
+    /// The statements with their relative paths without extension, sorted by
+    /// path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ParsedStatement)> {
+        self.statements
+            .iter()
+            .map(|(path, statement)| (path.as_str(), statement))
+    }
+
+    /// The declared names of the statements, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> { self.names.keys().map(String::as_str) }
+
+    pub fn root(&self) -> &Path { self.root.as_path() }
+
+    pub fn prefixes(&self) -> &Prefixes { &self.prefixes }
+
+    pub fn len(&self) -> usize { self.statements.len() }
+
+    pub fn is_empty(&self) -> bool { self.statements.is_empty() }
+}
+
+fn load_file(path: &Path, prefixes: &Prefixes) -> Result<ParsedStatement, Error> {
+    let text = std::fs::read_to_string(path)?;
+    let statement = Statement::new_with_inferred_prefixes(prefixes, text.into())?;
+    ParsedStatement::parse(&statement, None)
+}
+
+/// The path of the given file relative to the given root, with `/` as
+/// separator and without extension.
+fn relative_key(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The path of the file or directory that the given error of the directory
+/// walk is about, if any.
+fn walk_error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            walk_error_path(err)
+        },
+        _ => None,
+    }
+}
@@ -1,6 +1,6 @@
 use {
     super::Parameters,
-    crate::{fact_domain::FactDomain, DatastoreType, PersistenceMode},
+    crate::{DatastoreType, PersistenceMode, fact_domain::FactDomain},
     std::{
         default::Default,
         path::{Path, PathBuf},
@@ -16,6 +16,8 @@ pub struct ParametersBuilder {
     server_directory:               Option<PathBuf>,
     import_rename_user_blank_nodes: Option<bool>,
     datastore_type:                 Option<DatastoreType>,
+    parameters:                     Vec<(String, String)>,
+    unlisted_parameters:            Vec<(String, String)>,
 }
 
 impl ParametersBuilder {
@@ -79,6 +81,22 @@ impl ParametersBuilder {
         self
     }
 
+    /// Set any parameter by key, such as `num-threads`. The key and value are
+    /// checked against the schema of known parameters when the parameters
+    /// are built.
+    pub fn set(&mut self, key: &str, value: impl Into<String>) -> &mut Self {
+        self.parameters.push((key.to_string(), value.into()));
+        self
+    }
+
+    /// Set a parameter that is not in the schema of known parameters, see
+    /// [`Parameters::set_unlisted_string`].
+    pub fn set_unlisted(&mut self, key: &str, value: impl Into<String>) -> &mut Self {
+        self.unlisted_parameters
+            .push((key.to_string(), value.into()));
+        self
+    }
+
     pub fn build(&self) -> Result<Parameters, ekg_error::Error> {
         let mut to_build = Parameters::empty()?;
         if let Some(fact_domain) = &self.fact_domain {
@@ -99,11 +117,15 @@ impl ParametersBuilder {
         if let Some(datastore_type) = self.datastore_type {
             to_build.datastore_type(datastore_type)?;
         }
+        for (key, value) in &self.parameters {
+            to_build.set_string(key.as_str(), value.as_str())?;
+        }
+        for (key, value) in &self.unlisted_parameters {
+            to_build.set_unlisted_string(key.as_str(), value.as_str())?;
+        }
         // Do this one last in case sandbox directory has been set
-        if let Some(file_access_sandboxing) = self.file_access_sandboxing {
-            if !file_access_sandboxing {
-                to_build.switch_off_file_access_sandboxing()?;
-            }
+        if let Some(false) = self.file_access_sandboxing {
+            to_build.switch_off_file_access_sandboxing()?;
         }
         Ok(to_build)
     }
@@ -1,11 +1,13 @@
 extern crate alloc;
 
-pub use this::Parameters;
+pub use {
+    schema::{EngineVersion, PARAMETER_SPECS, ParameterDomain, ParameterSpec},
+    this::Parameters,
+};
 
 mod builder;
 mod handle;
+mod schema;
 #[cfg(test)]
 mod tests;
 mod this;
-
-const SENSITIVE_PARAMETERS: [&str; 1] = ["license-content"];
@@ -0,0 +1,156 @@
+use ekg_error::Error;
+
+const PERSISTENCE_MODES: &[&str] = &["file", "file-sequence", "off"];
+
+/// The parameters that [`crate::Parameters`] accepts, sorted by key.
+pub const PARAMETER_SPECS: &[ParameterSpec] = &[
+    ParameterSpec::new("api-log", ParameterDomain::Enum(&["on", "off"])),
+    ParameterSpec::new("api-log.directory", ParameterDomain::Path),
+    ParameterSpec::new(
+        "fact-domain",
+        ParameterDomain::Enum(&["explicit", "derived", "all"]),
+    ),
+    ParameterSpec::new(
+        "import.rename-user-blank-nodes",
+        ParameterDomain::Boolean,
+    ),
+    ParameterSpec::new("license-content", ParameterDomain::String).sensitive(),
+    ParameterSpec::new("license-file", ParameterDomain::Path),
+    ParameterSpec::new("num-threads", ParameterDomain::Integer),
+    ParameterSpec::new(
+        "persist-ds",
+        ParameterDomain::Enum(PERSISTENCE_MODES),
+    )
+    .only(&[EngineVersion::RDFox7_2a]),
+    ParameterSpec::new(
+        "persist-roles",
+        ParameterDomain::Enum(PERSISTENCE_MODES),
+    )
+    .only(&[EngineVersion::RDFox7_2a]),
+    ParameterSpec::new(
+        "persistence",
+        ParameterDomain::Enum(PERSISTENCE_MODES),
+    )
+    .only(&[EngineVersion::RDFox7_0a]),
+    ParameterSpec::new(
+        "query-validation",
+        ParameterDomain::Enum(&["strict", "standard-compliant"]),
+    ),
+    ParameterSpec::new("sandbox-directory", ParameterDomain::Path),
+    ParameterSpec::new("server-directory", ParameterDomain::Path),
+    ParameterSpec::new(
+        "type",
+        ParameterDomain::Enum(&["parallel-nn", "parallel-nw", "parallel-ww"]),
+    ),
+];
+
+/// The values that a parameter accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterDomain {
+    /// One of the given values
+    Enum(&'static [&'static str]),
+    /// `true` or `false`
+    Boolean,
+    /// A non-negative integer
+    Integer,
+    /// A file or directory, or an empty string for none
+    Path,
+    /// Any string
+    String,
+}
+
+/// A version of the database engine that the parameters are passed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineVersion {
+    RDFox7_0a,
+    RDFox7_2a,
+}
+
+impl EngineVersion {
+    pub const ALL: &'static [Self] = &[Self::RDFox7_0a, Self::RDFox7_2a];
+
+    /// The version selected with the `rdfox-7-0a` or `rdfox-7-2a` feature,
+    /// RDFox 7.2a if neither is selected.
+    pub const fn current() -> Self {
+        if cfg!(feature = "rdfox-7-0a") {
+            Self::RDFox7_0a
+        } else {
+            Self::RDFox7_2a
+        }
+    }
+}
+
+/// A known parameter: its key, the values that it accepts, the engine
+/// versions that accept it and whether its value should be kept out of logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterSpec {
+    pub key:       &'static str,
+    pub domain:    ParameterDomain,
+    pub versions:  &'static [EngineVersion],
+    pub sensitive: bool,
+}
+
+impl ParameterSpec {
+    const fn new(key: &'static str, domain: ParameterDomain) -> Self {
+        Self {
+            key,
+            domain,
+            versions: EngineVersion::ALL,
+            sensitive: false,
+        }
+    }
+
+    const fn only(self, versions: &'static [EngineVersion]) -> Self { Self { versions, ..self } }
+
+    const fn sensitive(self) -> Self { Self { sensitive: true, ..self } }
+
+    /// The specification of the parameter with the given key, if it is known.
+    pub fn get(key: &str) -> Option<&'static Self> {
+        PARAMETER_SPECS.iter().find(|spec| spec.key == key)
+    }
+
+    /// Whether the current engine version accepts this parameter.
+    pub fn is_supported(&self) -> bool { self.versions.contains(&EngineVersion::current()) }
+
+    /// Check that the given key is a known parameter that the current engine
+    /// version accepts and that the given value is in its domain.
+    pub fn validate(key: &str, value: &str) -> Result<&'static Self, Error> {
+        let spec = Self::get(key).ok_or_else(|| Error::UnknownParameter(key.to_string()))?;
+        if !spec.is_supported() {
+            return Err(Error::UnsupportedParameter {
+                key:     key.to_string(),
+                version: format!("{:?}", EngineVersion::current()),
+            });
+        }
+        let invalid = |expected: String| {
+            Error::InvalidParameterValue {
+                key: key.to_string(),
+                value: spec.display_value(value).to_string(),
+                expected,
+            }
+        };
+        if value.contains('\0') {
+            // RDFox takes parameters as C strings
+            return Err(invalid(
+                "a value without NUL characters".to_string(),
+            ));
+        }
+        match spec.domain {
+            ParameterDomain::Enum(values) if !values.contains(&value) => {
+                Err(invalid(values.join(", ")))
+            },
+            ParameterDomain::Boolean if value != "true" && value != "false" => {
+                Err(invalid("true or false".to_string()))
+            },
+            ParameterDomain::Integer if value.parse::<u64>().is_err() => {
+                Err(invalid("a non-negative integer".to_string()))
+            },
+            _ => Ok(spec),
+        }
+    }
+
+    /// The given value of this parameter as it can be logged.
+    pub fn display_value<'a>(&self, value: &'a str) -> &'a str {
+        if self.sensitive { "***" } else { value }
+    }
+}
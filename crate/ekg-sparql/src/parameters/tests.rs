@@ -3,7 +3,147 @@
 #[test]
 fn test_set_param() {
     let mut params = crate::Parameters::empty().unwrap();
-    params.set_string("key1", "value1").unwrap();
-    let value = params.get_string("key1", "whatever").unwrap();
-    assert_eq!(value, "value1");
+    params.set_string("num-threads", "4").unwrap();
+    let value = params.get_string("num-threads", "whatever").unwrap();
+    assert_eq!(value, "4");
+}
+
+#[test_log::test]
+fn test_schema_is_sorted() {
+    assert!(
+        crate::PARAMETER_SPECS
+            .windows(2)
+            .all(|specs| specs[0].key < specs[1].key)
+    );
+}
+
+#[test_log::test]
+fn test_invalid_params() -> Result<(), ekg_error::Error> {
+    let mut params = crate::Parameters::empty()?;
+    assert!(matches!(
+        params.set_string("key1", "value1"),
+        Err(ekg_error::Error::UnknownParameter(key)) if key == "key1"
+    ));
+    assert!(matches!(
+        params.set_string("fact-domain", "everything"),
+        Err(ekg_error::Error::InvalidParameterValue { expected, .. })
+            if expected == "explicit, derived, all"
+    ));
+    assert!(matches!(
+        params.set_string("num-threads", "-1"),
+        Err(ekg_error::Error::InvalidParameterValue { .. })
+    ));
+    assert!(matches!(
+        params.set_string("import.rename-user-blank-nodes", "yes"),
+        Err(ekg_error::Error::InvalidParameterValue { .. })
+    ));
+    assert!(matches!(
+        params.set_string("api-log.directory", "/tmp/\0logs"),
+        Err(ekg_error::Error::InvalidParameterValue { .. })
+    ));
+    let unsupported = crate::PARAMETER_SPECS
+        .iter()
+        .find(|spec| !spec.is_supported())
+        .expect("a parameter that the current engine version does not accept");
+    assert!(matches!(
+        params.set_string(unsupported.key, "off"),
+        Err(ekg_error::Error::UnsupportedParameter { .. })
+    ));
+    assert_eq!(params.iter().count(), 0);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_sensitive_params() -> Result<(), ekg_error::Error> {
+    let mut params = crate::Parameters::empty()?;
+    params.license_content("secret")?;
+    assert_eq!(
+        params.get_string("license-content", "")?,
+        "secret\n"
+    );
+    assert!(!params.to_string().contains("secret"));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_unlisted_params() -> Result<(), ekg_error::Error> {
+    let mut params = crate::Parameters::builder()
+        .set_unlisted("max-memory", "1024")
+        .build()?;
+    assert_eq!(params.get_string("max-memory", "")?, "1024");
+    assert!(!params.to_string().contains("1024"));
+
+    // Keys in the schema are still validated
+    assert!(matches!(
+        params.set_unlisted_string("num-threads", "many"),
+        Err(ekg_error::Error::InvalidParameterValue { .. })
+    ));
+    assert!(matches!(
+        params.set_unlisted_string("max-memory", "1024\0"),
+        Err(ekg_error::Error::InvalidParameterValue { .. })
+    ));
+    params.set_unlisted_string("num-threads", "2")?;
+    assert_eq!(params.iter().collect::<Vec<_>>(), [
+        ("max-memory", "1024"),
+        ("num-threads", "2")
+    ]);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_build_params() -> Result<(), ekg_error::Error> {
+    let params = crate::Parameters::builder()
+        .fact_domain_all()
+        .persist_datastore(crate::PersistenceMode::Off)
+        .set("num-threads", "8")
+        .build()?;
+    assert_eq!(params.iter().count(), 3);
+    assert_eq!(params.get_string("fact-domain", "")?, "all");
+    assert_eq!(params.get_string("num-threads", "")?, "8");
+
+    assert!(matches!(
+        crate::Parameters::builder()
+            .set("num-threads", "many")
+            .build(),
+        Err(ekg_error::Error::InvalidParameterValue { .. })
+    ));
+    assert!(matches!(
+        crate::Parameters::builder().set("threads", "8").build(),
+        Err(ekg_error::Error::UnknownParameter(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_serialize_params() -> Result<(), ekg_error::Error> {
+    let params = crate::Parameters::builder()
+        .fact_domain_all()
+        .set("num-threads", "8")
+        .set("import.rename-user-blank-nodes", "true")
+        .build()?;
+    let json = serde_json::to_value(&params)?;
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "fact-domain": "all",
+            "import.rename-user-blank-nodes": true,
+            "num-threads": 8
+        })
+    );
+    let deserialized: crate::Parameters = serde_json::from_value(json)?;
+    assert_eq!(deserialized, params);
+
+    assert!(serde_json::from_str::<crate::Parameters>(r#"{ "num-threads": "eight" }"#).is_err());
+    assert!(serde_json::from_str::<crate::Parameters>(r#"{ "threads": 8 }"#).is_err());
+
+    // Sensitive and unlisted parameters are not written
+    let mut params = params;
+    params.license_content("secret")?;
+    params.set_unlisted_string("max-memory", "1024")?;
+    let json = serde_json::to_string(&params)?;
+    assert!(!json.contains("license-content"));
+    assert!(!json.contains("secret"));
+    assert!(!json.contains("max-memory"));
+    assert!(json.contains("num-threads"));
+    Ok(())
 }
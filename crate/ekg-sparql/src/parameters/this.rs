@@ -6,12 +6,19 @@ use {
     std::sync::{Arc, Mutex},
 };
 use {
-    super::{builder::ParametersBuilder, SENSITIVE_PARAMETERS},
-    crate::{fact_domain::FactDomain, persistence_mode::PersistenceMode, DatastoreType},
+    super::{
+        builder::ParametersBuilder,
+        schema::{ParameterDomain, ParameterSpec},
+    },
+    crate::{DatastoreType, fact_domain::FactDomain, persistence_mode::PersistenceMode},
     ekg_error::Error,
     ekg_util::log::LOG_TARGET_DATABASE,
+    serde::{
+        de::{Deserialize, Deserializer, Error as _},
+        ser::{Serialize, SerializeMap, Serializer},
+    },
     std::{
-        collections::HashMap,
+        collections::{BTreeMap, HashMap},
         fmt::{Display, Formatter},
         path::Path,
     },
@@ -19,7 +26,7 @@ use {
 
 #[derive(Debug, Clone)]
 pub struct Parameters {
-    map:              HashMap<&'static str, String>,
+    map:              BTreeMap<String, String>,
     #[cfg(feature = "_rdfox")]
     pub(crate) inner: Arc<Mutex<CParametersHandle>>,
 }
@@ -40,7 +47,12 @@ impl PartialEq for Parameters {
 
 impl Display for Parameters {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Parameters[{:?}]", self.map)
+        let map = self
+            .map
+            .iter()
+            .map(|(key, value)| (key.as_str(), display_value(key, value)))
+            .collect::<BTreeMap<_, _>>();
+        write!(f, "Parameters[{map:?}]")
     }
 }
 
@@ -49,17 +61,37 @@ impl Parameters {
 
     pub fn empty() -> Result<Self, Error> {
         Ok(Parameters {
-            map:                              HashMap::new(),
+            map:                              BTreeMap::new(),
             #[cfg(feature = "_rdfox")]
             inner:                            Arc::new(Mutex::new(CParametersHandle::new()?)),
         })
     }
 
+    /// Set the given parameter, failing with [`Error::UnknownParameter`],
+    /// [`Error::UnsupportedParameter`] or [`Error::InvalidParameterValue`]
+    /// if it is not in the schema of known parameters (see
+    /// [`ParameterSpec`]).
+    pub fn set_string(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let msg = self.map_set_string(key, value, false)?;
+        self.apply_string(key, value, msg)
+    }
+
+    /// Set a parameter that is not in the schema of known parameters (see
+    /// [`crate::PARAMETER_SPECS`]), such as a parameter of a newer engine
+    /// version, without checking its key. Parameters that are in the schema
+    /// are validated as by [`Parameters::set_string`].
+    ///
+    /// The values of unlisted parameters are never logged or serialised
+    /// since they might be sensitive.
+    pub fn set_unlisted_string(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let msg = self.map_set_string(key, value, true)?;
+        self.apply_string(key, value, msg)
+    }
+
     #[cfg(feature = "_rdfox")]
-    pub fn set_string(&mut self, key: &'static str, value: &str) -> Result<(), Error> {
-        let msg = self.map_set_string(key, value)?;
-        let c_key = CString::new(key).unwrap();
-        let c_value = CString::new(value).unwrap();
+    fn apply_string(&mut self, key: &str, value: &str, msg: String) -> Result<(), Error> {
+        let c_key = CString::new(key)?;
+        let c_value = CString::new(value)?;
         let mut c_ptr = self.inner.lock().unwrap();
         Ok(rdfox_sys::database_call!(
             msg.as_str(),
@@ -68,23 +100,46 @@ impl Parameters {
     }
 
     #[cfg(not(feature = "_rdfox"))]
-    pub fn set_string(&mut self, key: &'static str, value: &str) -> Result<(), Error> {
-        let msg = self.map_set_string(key, value)?;
+    fn apply_string(&mut self, _key: &str, _value: &str, msg: String) -> Result<(), Error> {
         tracing::info!(target: ekg_util::log::LOG_TARGET_DATABASE, "{}", msg);
         Ok(())
     }
 
-    fn map_set_string(&mut self, key: &'static str, value: &str) -> Result<String, Error> {
-        let msg = if SENSITIVE_PARAMETERS.contains(&key) {
-            format!("Setting parameter {key}=[***]",)
-        } else {
-            format!("Setting parameter {key}=[{value}]",)
-        };
-
-        self.map.insert(key, value.to_string());
+    fn map_set_string(&mut self, key: &str, value: &str, unlisted: bool) -> Result<String, Error> {
+        match ParameterSpec::validate(key, value) {
+            Err(Error::UnknownParameter(_)) if unlisted => {
+                if value.contains('\0') {
+                    // RDFox takes parameters as C strings
+                    return Err(Error::InvalidParameterValue {
+                        key:      key.to_string(),
+                        value:    UNLISTED_VALUE.to_string(),
+                        expected: "a value without NUL characters".to_string(),
+                    });
+                }
+                tracing::warn!(
+                    target: LOG_TARGET_DATABASE,
+                    "Setting parameter {key} that is not in the schema of known parameters"
+                );
+            },
+            result => {
+                result?;
+            },
+        }
+        let msg = format!(
+            "Setting parameter {key}=[{}]",
+            display_value(key, value)
+        );
+        self.map.insert(key.to_string(), value.to_string());
         Ok(msg)
     }
 
+    /// The parameters that have been set, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.map
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
     #[cfg(feature = "_rdfox")]
     pub fn get_string(&self, key: &'static str, default: &'static str) -> Result<String, Error> {
         let c_key = CString::new(key).unwrap();
@@ -119,11 +174,7 @@ impl Parameters {
     }
 
     pub fn fact_domain(&mut self, fact_domain: &FactDomain) -> Result<&mut Self, Error> {
-        match fact_domain {
-            FactDomain::ASSERTED => self.set_string("fact-domain", "explicit")?,
-            FactDomain::INFERRED => self.set_string("fact-domain", "derived")?,
-            FactDomain::ALL => self.set_string("fact-domain", "all")?,
-        };
+        self.set_string("fact-domain", fact_domain.as_str())?;
         Ok(self)
     }
 
@@ -134,18 +185,12 @@ impl Parameters {
     }
 
     pub fn persist_datastore(&mut self, mode: &PersistenceMode) -> Result<&mut Self, Error> {
-        #[cfg(feature = "rdfox-7-0a")]
-        match mode {
-            PersistenceMode::File => self.set_string("persistence", "file")?,
-            PersistenceMode::FileSequence => self.set_string("persistence", "file-sequence")?,
-            PersistenceMode::Off => self.set_string("persistence", "off")?,
-        };
-        #[cfg(not(feature = "rdfox-7-0a"))]
-        match mode {
-            PersistenceMode::File => self.set_string("persist-ds", "file")?,
-            PersistenceMode::FileSequence => self.set_string("persist-ds", "file-sequence")?,
-            PersistenceMode::Off => self.set_string("persist-ds", "off")?,
-        };
+        // RDFox 7.0a calls this parameter `persistence`, later versions `persist-ds`
+        let key = ["persistence", "persist-ds"]
+            .into_iter()
+            .find(|key| ParameterSpec::get(key).is_some_and(ParameterSpec::is_supported))
+            .unwrap_or("persist-ds");
+        self.set_string(key, mode.as_str())?;
         match mode {
             PersistenceMode::File => {
                 tracing::info!(target: LOG_TARGET_DATABASE, "File persistence")
@@ -158,7 +203,7 @@ impl Parameters {
         Ok(self)
     }
 
-    #[cfg(not(feature = "rdfox-7-0a"))]
+    /// Not supported by RDFox 7.0a, see [`Error::UnsupportedParameter`].
     pub fn persist_roles(&mut self, mode: PersistenceMode) -> Result<&mut Self, Error> {
         self.set_string("persist-roles", mode.as_str())?;
         Ok(self)
@@ -255,3 +300,78 @@ impl Parameters {
         Ok(self)
     }
 }
+
+/// What is shown instead of the value of a parameter that is not in the
+/// schema of known parameters.
+const UNLISTED_VALUE: &str = "***";
+
+/// The given value of the given parameter as it can be logged, which hides
+/// the values of sensitive and unlisted parameters.
+fn display_value<'a>(key: &str, value: &'a str) -> &'a str {
+    ParameterSpec::get(key).map_or(UNLISTED_VALUE, |spec| spec.display_value(value))
+}
+
+/// Serialises the parameters as a map from key to value, with booleans and
+/// integers as such, so that they can be stored in TOML or JSON.
+///
+/// Sensitive parameters such as `license-content` and parameters that are
+/// not in the schema of known parameters are left out, so that they never
+/// end up in a configuration file or log. They have to be set again after
+/// deserialising.
+impl Serialize for Parameters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let specs = self
+            .iter()
+            .filter_map(|(key, value)| Some((ParameterSpec::get(key)?, value)))
+            .filter(|(spec, _)| !spec.sensitive)
+            .collect::<Vec<_>>();
+        let mut map = serializer.serialize_map(Some(specs.len()))?;
+        for (spec, value) in specs {
+            let key = spec.key;
+            match spec.domain {
+                ParameterDomain::Boolean => {
+                    match value.parse::<bool>() {
+                        Ok(value) => map.serialize_entry(key, &value)?,
+                        Err(_) => map.serialize_entry(key, value)?,
+                    }
+                },
+                ParameterDomain::Integer => {
+                    match value.parse::<u64>() {
+                        Ok(value) => map.serialize_entry(key, &value)?,
+                        Err(_) => map.serialize_entry(key, value)?,
+                    }
+                },
+                _ => map.serialize_entry(key, value)?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// Deserialises the parameters from a map from key to value, validating
+/// every parameter against the schema of known parameters.
+impl<'de> Deserialize<'de> for Parameters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Value {
+            Boolean(bool),
+            Integer(u64),
+            String(String),
+        }
+
+        let map = HashMap::<String, Value>::deserialize(deserializer)?;
+        let mut parameters = Parameters::empty().map_err(D::Error::custom)?;
+        for (key, value) in map {
+            let value = match value {
+                Value::Boolean(value) => value.to_string(),
+                Value::Integer(value) => value.to_string(),
+                Value::String(value) => value,
+            };
+            parameters
+                .set_string(key.as_str(), value.as_str())
+                .map_err(D::Error::custom)?;
+        }
+        Ok(parameters)
+    }
+}
@@ -0,0 +1,645 @@
+//! Static analysis of the `spargebra` algebra of a parsed SPARQL statement.
+use {
+    super::ParsedStatement,
+    crate::{Prefixes, SPARQLFlavor},
+    ekg_identifier::Namespace,
+    spargebra::{
+        GraphUpdateOperation,
+        Query,
+        algebra::{
+            AggregateExpression,
+            Expression,
+            Function,
+            GraphPattern,
+            GraphTarget,
+            OrderExpression,
+            PropertyPathExpression,
+            QueryDataset,
+        },
+        term::{
+            GraphName,
+            GraphNamePattern,
+            GroundTerm,
+            GroundTermPattern,
+            Literal,
+            NamedNode,
+            NamedNodePattern,
+            NamedOrBlankNode,
+            QuadPattern,
+            Term,
+            TermPattern,
+            TriplePattern,
+            Variable,
+        },
+    },
+    std::collections::BTreeSet,
+};
+
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+/// What a SPARQL statement uses and returns, as found by
+/// [`ParsedStatement::analysis`] without executing it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementAnalysis {
+    /// The variables returned by a `SELECT` query, in the order of the
+    /// projection, empty for other statements
+    pub projected_variables: Vec<Variable>,
+    /// The variables that are in scope of the `WHERE` clause(s), before any
+    /// grouping or projection
+    pub in_scope_variables:  BTreeSet<Variable>,
+    /// All variables that occur anywhere in the statement, including the ones
+    /// that only occur in expressions or templates
+    pub variables:           BTreeSet<Variable>,
+    /// The named graphs that are read or written: `GRAPH <iri>` patterns,
+    /// `FROM`, `FROM NAMED` and `USING` clauses, graphs in templates and the
+    /// targets of graph management operations
+    pub named_graphs:        BTreeSet<NamedNode>,
+    /// All IRIs in the statement, including predicates, graph names,
+    /// functions and (other than `xsd:string`) datatypes of literals
+    pub iris:                BTreeSet<NamedNode>,
+    /// The triple patterns that are matched by the `WHERE` clause(s), in
+    /// order of occurrence
+    pub triple_patterns:     Vec<TriplePattern>,
+    /// The predicates of all triple patterns, templates and property paths
+    pub predicates:          BTreeSet<NamedNode>,
+    /// Whether the statement has a `SERVICE` clause
+    pub uses_service:        bool,
+    /// Whether the statement uses aggregates (or `GROUP BY`)
+    pub uses_aggregates:     bool,
+    /// Whether the statement uses property paths other than a single IRI
+    pub uses_property_paths: bool,
+    /// Whether the statement has subqueries
+    pub uses_subqueries:     bool,
+    /// Whether the statement uses `BIND` or expressions in its projection
+    pub uses_bind:           bool,
+    /// Whether the statement has `VALUES` clauses
+    pub uses_values:         bool,
+    /// Whether the statement has `MINUS` clauses
+    pub uses_minus:          bool,
+    /// Whether the statement uses `EXISTS` or `NOT EXISTS`
+    pub uses_exists:         bool,
+    /// Whether the statement uses `IN`, `NOT IN`, `IF`, `COALESCE` or a
+    /// function that is not in SPARQL 1.0, such as `STRLEN` or `NOW`
+    pub uses_new_functions:  bool,
+    /// Whether the statement is an update-statement
+    pub is_update:           bool,
+    /// Whether the next projection that is found is a subquery
+    projection_is_subquery:  bool,
+}
+
+impl StatementAnalysis {
+    /// Analyse the given query.
+    pub fn of_query(query: &Query) -> Self {
+        let mut analysis = Self::default();
+        match query {
+            Query::Select { dataset, .. } |
+            Query::Describe { dataset, .. } |
+            Query::Ask { dataset, .. } => analysis.add_dataset(dataset.as_ref()),
+            Query::Construct { template, dataset, .. } => {
+                analysis.add_dataset(dataset.as_ref());
+                for triple in template {
+                    analysis.add_template_triple(triple);
+                }
+            },
+        }
+        let pattern = query_pattern(query);
+        let projection = outer_projection(pattern);
+        if let (Query::Select { .. }, Some(variables)) = (query, projection) {
+            analysis.projected_variables = variables.clone();
+        }
+        analysis.projection_is_subquery = projection.is_none();
+        analysis.add_where_clause(pattern);
+        analysis
+    }
+
+    /// Analyse the given operation of an update-statement.
+    pub fn of_update_operation(operation: &GraphUpdateOperation) -> Self {
+        let mut analysis = Self {
+            is_update: true,
+            projection_is_subquery: true,
+            ..Self::default()
+        };
+        match operation {
+            GraphUpdateOperation::InsertData { data } => {
+                for quad in data {
+                    analysis.add_named_or_blank_node(&quad.subject);
+                    analysis.add_predicate(&quad.predicate);
+                    analysis.add_term(&quad.object);
+                    analysis.add_graph_name(&quad.graph_name);
+                }
+            },
+            GraphUpdateOperation::DeleteData { data } => {
+                for quad in data {
+                    analysis.iris.insert(quad.subject.clone());
+                    analysis.add_predicate(&quad.predicate);
+                    analysis.add_ground_term(&quad.object);
+                    analysis.add_graph_name(&quad.graph_name);
+                }
+            },
+            GraphUpdateOperation::DeleteInsert { delete, insert, using, pattern } => {
+                for quad in delete {
+                    analysis.add_ground_term_pattern(&quad.subject);
+                    analysis.add_named_node_pattern(&quad.predicate, true);
+                    analysis.add_ground_term_pattern(&quad.object);
+                    analysis.add_graph_name_pattern(&quad.graph_name);
+                }
+                for quad in insert {
+                    analysis.add_quad_pattern(quad);
+                }
+                analysis.add_dataset(using.as_ref());
+                analysis.add_where_clause(pattern);
+            },
+            GraphUpdateOperation::Load { source, destination, .. } => {
+                analysis.iris.insert(source.clone());
+                analysis.add_graph_name(destination);
+            },
+            GraphUpdateOperation::Clear { graph, .. } |
+            GraphUpdateOperation::Drop { graph, .. } => {
+                if let GraphTarget::NamedNode(graph) = graph {
+                    analysis.add_named_graph(graph);
+                }
+            },
+            GraphUpdateOperation::Create { graph, .. } => analysis.add_named_graph(graph),
+        }
+        analysis
+    }
+
+    /// The namespaces of the given prefixes that are used by at least one
+    /// IRI of the statement, sorted by prefix.
+    pub fn namespaces(&self, prefixes: &Prefixes) -> Vec<Namespace> {
+        prefixes
+            .iter()
+            .filter(|namespace| {
+                self.iris
+                    .iter()
+                    .any(|iri| iri.as_str().starts_with(namespace.iri.as_str()))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Add the analysis of another part of the same statement.
+    fn merge(&mut self, other: Self) {
+        self.projected_variables.extend(other.projected_variables);
+        self.in_scope_variables.extend(other.in_scope_variables);
+        self.variables.extend(other.variables);
+        self.named_graphs.extend(other.named_graphs);
+        self.iris.extend(other.iris);
+        self.triple_patterns.extend(other.triple_patterns);
+        self.predicates.extend(other.predicates);
+        self.uses_service |= other.uses_service;
+        self.uses_aggregates |= other.uses_aggregates;
+        self.uses_property_paths |= other.uses_property_paths;
+        self.uses_subqueries |= other.uses_subqueries;
+        self.uses_bind |= other.uses_bind;
+        self.uses_values |= other.uses_values;
+        self.uses_minus |= other.uses_minus;
+        self.uses_exists |= other.uses_exists;
+        self.uses_new_functions |= other.uses_new_functions;
+        self.is_update |= other.is_update;
+    }
+
+    /// The features of SPARQL 1.1 that the statement uses, empty if it is a
+    /// SPARQL 1.0 statement.
+    pub fn sparql11_features(&self) -> Vec<&'static str> {
+        [
+            (self.is_update, "update"),
+            (self.uses_property_paths, "property paths"),
+            (self.uses_aggregates, "aggregates"),
+            (self.uses_subqueries, "subqueries"),
+            (self.uses_bind, "BIND"),
+            (self.uses_values, "VALUES"),
+            (self.uses_minus, "MINUS"),
+            (self.uses_exists, "EXISTS"),
+            (self.uses_new_functions, "SPARQL 1.1 functions"),
+            (self.uses_service, "SERVICE"),
+        ]
+        .into_iter()
+        .filter_map(|(used, feature)| used.then_some(feature))
+        .collect()
+    }
+
+    /// The oldest version of SPARQL that supports all features that are used.
+    pub fn flavor(&self) -> SPARQLFlavor {
+        if self.sparql11_features().is_empty() {
+            SPARQLFlavor::SPARQL10
+        } else {
+            SPARQLFlavor::SPARQL11
+        }
+    }
+
+    fn add_where_clause(&mut self, pattern: &GraphPattern) {
+        where_clause(pattern).on_in_scope_variable(|variable| {
+            self.in_scope_variables.insert(variable.clone());
+        });
+        self.add_pattern(pattern);
+    }
+
+    fn add_dataset(&mut self, dataset: Option<&QueryDataset>) {
+        let Some(dataset) = dataset else {
+            return;
+        };
+        for graph in dataset.default.iter().chain(dataset.named.iter().flatten()) {
+            self.add_named_graph(graph);
+        }
+    }
+
+    fn add_pattern(&mut self, pattern: &GraphPattern) {
+        match pattern {
+            GraphPattern::Bgp { patterns } => {
+                for triple in patterns {
+                    self.add_term_pattern(&triple.subject);
+                    self.add_named_node_pattern(&triple.predicate, true);
+                    self.add_term_pattern(&triple.object);
+                    self.triple_patterns.push(triple.clone());
+                }
+            },
+            GraphPattern::Path { subject, path, object } => {
+                self.add_term_pattern(subject);
+                self.add_path(path);
+                self.add_term_pattern(object);
+            },
+            GraphPattern::Minus { left, right } => {
+                self.uses_minus = true;
+                self.add_pattern(left);
+                self.add_pattern(right);
+            },
+            GraphPattern::Join { left, right } | GraphPattern::Union { left, right } => {
+                self.add_pattern(left);
+                self.add_pattern(right);
+            },
+            GraphPattern::LeftJoin { left, right, expression } => {
+                self.add_pattern(left);
+                self.add_pattern(right);
+                if let Some(expression) = expression {
+                    self.add_expression(expression);
+                }
+            },
+            GraphPattern::Filter { expr, inner } => {
+                self.add_expression(expr);
+                self.add_pattern(inner);
+            },
+            GraphPattern::Graph { name, inner } => {
+                if let NamedNodePattern::NamedNode(graph) = name {
+                    self.add_named_graph(graph);
+                }
+                self.add_named_node_pattern(name, false);
+                self.add_pattern(inner);
+            },
+            GraphPattern::Service { name, inner, .. } => {
+                self.uses_service = true;
+                self.add_named_node_pattern(name, false);
+                self.add_pattern(inner);
+            },
+            GraphPattern::Extend { inner, variable, expression } => {
+                // Aggregates on top of a grouping are not bound with BIND
+                self.uses_bind |= !is_grouped(inner);
+                self.variables.insert(variable.clone());
+                self.add_expression(expression);
+                self.add_pattern(inner);
+            },
+            GraphPattern::Values { variables, bindings } => {
+                self.uses_values = true;
+                self.variables.extend(variables.iter().cloned());
+                for value in bindings.iter().flatten().flatten() {
+                    self.add_ground_term(value);
+                }
+            },
+            GraphPattern::OrderBy { inner, expression } => {
+                for order in expression {
+                    match order {
+                        OrderExpression::Asc(expression) | OrderExpression::Desc(expression) => {
+                            self.add_expression(expression)
+                        },
+                    }
+                }
+                self.add_pattern(inner);
+            },
+            GraphPattern::Project { inner, variables } => {
+                self.uses_subqueries |= self.projection_is_subquery;
+                self.projection_is_subquery = true;
+                self.variables.extend(variables.iter().cloned());
+                self.add_pattern(inner);
+            },
+            GraphPattern::Distinct { inner } |
+            GraphPattern::Reduced { inner } |
+            GraphPattern::Slice { inner, .. } => self.add_pattern(inner),
+            GraphPattern::Group { inner, variables, aggregates } => {
+                self.uses_aggregates = true;
+                self.variables.extend(variables.iter().cloned());
+                for (variable, aggregate) in aggregates {
+                    self.variables.insert(variable.clone());
+                    if let AggregateExpression::FunctionCall { expr, .. } = aggregate {
+                        self.add_expression(expr);
+                    }
+                }
+                self.add_pattern(inner);
+            },
+            // Such as lateral joins when the "sep-0006" feature of spargebra is enabled
+            #[allow(unreachable_patterns)]
+            _ => {},
+        }
+    }
+
+    fn add_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::NamedNode(iri) => {
+                self.iris.insert(iri.clone());
+            },
+            Expression::Literal(literal) => self.add_literal(literal),
+            Expression::Variable(variable) | Expression::Bound(variable) => {
+                self.variables.insert(variable.clone());
+            },
+            Expression::Or(left, right) |
+            Expression::And(left, right) |
+            Expression::Equal(left, right) |
+            Expression::SameTerm(left, right) |
+            Expression::Greater(left, right) |
+            Expression::GreaterOrEqual(left, right) |
+            Expression::Less(left, right) |
+            Expression::LessOrEqual(left, right) |
+            Expression::Add(left, right) |
+            Expression::Subtract(left, right) |
+            Expression::Multiply(left, right) |
+            Expression::Divide(left, right) => {
+                self.add_expression(left);
+                self.add_expression(right);
+            },
+            Expression::In(expression, list) => {
+                self.uses_new_functions = true;
+                self.add_expression(expression);
+                for expression in list {
+                    self.add_expression(expression);
+                }
+            },
+            Expression::UnaryPlus(expression) |
+            Expression::UnaryMinus(expression) |
+            Expression::Not(expression) => self.add_expression(expression),
+            Expression::Exists(pattern) => {
+                self.uses_exists = true;
+                self.add_pattern(pattern)
+            },
+            Expression::If(condition, then, otherwise) => {
+                self.uses_new_functions = true;
+                self.add_expression(condition);
+                self.add_expression(then);
+                self.add_expression(otherwise);
+            },
+            Expression::Coalesce(list) => {
+                self.uses_new_functions = true;
+                for expression in list {
+                    self.add_expression(expression);
+                }
+            },
+            Expression::FunctionCall(function, list) => {
+                if let Function::Custom(iri) = function {
+                    self.iris.insert(iri.clone());
+                }
+                self.uses_new_functions |= !is_sparql10_function(function);
+                for expression in list {
+                    self.add_expression(expression);
+                }
+            },
+        }
+    }
+
+    fn add_path(&mut self, path: &PropertyPathExpression) {
+        match path {
+            PropertyPathExpression::NamedNode(predicate) => self.add_predicate(predicate),
+            PropertyPathExpression::Reverse(path) |
+            PropertyPathExpression::ZeroOrMore(path) |
+            PropertyPathExpression::OneOrMore(path) |
+            PropertyPathExpression::ZeroOrOne(path) => {
+                self.uses_property_paths = true;
+                self.add_path(path);
+            },
+            PropertyPathExpression::Sequence(left, right) |
+            PropertyPathExpression::Alternative(left, right) => {
+                self.uses_property_paths = true;
+                self.add_path(left);
+                self.add_path(right);
+            },
+            PropertyPathExpression::NegatedPropertySet(predicates) => {
+                self.uses_property_paths = true;
+                for predicate in predicates {
+                    self.add_predicate(predicate);
+                }
+            },
+        }
+    }
+
+    fn add_template_triple(&mut self, triple: &TriplePattern) {
+        self.add_term_pattern(&triple.subject);
+        self.add_named_node_pattern(&triple.predicate, true);
+        self.add_term_pattern(&triple.object);
+    }
+
+    fn add_quad_pattern(&mut self, quad: &QuadPattern) {
+        self.add_term_pattern(&quad.subject);
+        self.add_named_node_pattern(&quad.predicate, true);
+        self.add_term_pattern(&quad.object);
+        self.add_graph_name_pattern(&quad.graph_name);
+    }
+
+    fn add_term_pattern(&mut self, term: &TermPattern) {
+        match term {
+            TermPattern::NamedNode(iri) => {
+                self.iris.insert(iri.clone());
+            },
+            TermPattern::Literal(literal) => self.add_literal(literal),
+            TermPattern::Variable(variable) => {
+                self.variables.insert(variable.clone());
+            },
+            // Blank nodes, and quoted triples when the "sparql-12" feature of spargebra is
+            // enabled
+            #[allow(unreachable_patterns)]
+            _ => {},
+        }
+    }
+
+    fn add_ground_term_pattern(&mut self, term: &GroundTermPattern) {
+        match term {
+            GroundTermPattern::NamedNode(iri) => {
+                self.iris.insert(iri.clone());
+            },
+            GroundTermPattern::Literal(literal) => self.add_literal(literal),
+            GroundTermPattern::Variable(variable) => {
+                self.variables.insert(variable.clone());
+            },
+            #[allow(unreachable_patterns)]
+            _ => {},
+        }
+    }
+
+    fn add_named_node_pattern(&mut self, pattern: &NamedNodePattern, is_predicate: bool) {
+        match pattern {
+            NamedNodePattern::NamedNode(iri) if is_predicate => self.add_predicate(iri),
+            NamedNodePattern::NamedNode(iri) => {
+                self.iris.insert(iri.clone());
+            },
+            NamedNodePattern::Variable(variable) => {
+                self.variables.insert(variable.clone());
+            },
+        }
+    }
+
+    fn add_graph_name_pattern(&mut self, pattern: &GraphNamePattern) {
+        match pattern {
+            GraphNamePattern::NamedNode(graph) => self.add_named_graph(graph),
+            GraphNamePattern::DefaultGraph => {},
+            GraphNamePattern::Variable(variable) => {
+                self.variables.insert(variable.clone());
+            },
+        }
+    }
+
+    fn add_graph_name(&mut self, graph_name: &GraphName) {
+        if let GraphName::NamedNode(graph) = graph_name {
+            self.add_named_graph(graph);
+        }
+    }
+
+    fn add_term(&mut self, term: &Term) {
+        match term {
+            Term::NamedNode(iri) => {
+                self.iris.insert(iri.clone());
+            },
+            Term::Literal(literal) => self.add_literal(literal),
+            #[allow(unreachable_patterns)]
+            _ => {},
+        }
+    }
+
+    fn add_ground_term(&mut self, term: &GroundTerm) {
+        match term {
+            GroundTerm::NamedNode(iri) => {
+                self.iris.insert(iri.clone());
+            },
+            GroundTerm::Literal(literal) => self.add_literal(literal),
+            #[allow(unreachable_patterns)]
+            _ => {},
+        }
+    }
+
+    fn add_named_or_blank_node(&mut self, node: &NamedOrBlankNode) {
+        if let NamedOrBlankNode::NamedNode(iri) = node {
+            self.iris.insert(iri.clone());
+        }
+    }
+
+    fn add_literal(&mut self, literal: &Literal) {
+        let data_type = literal.datatype();
+        if data_type.as_str() != XSD_STRING && data_type.as_str() != RDF_LANG_STRING {
+            self.iris.insert(data_type.into_owned());
+        }
+    }
+
+    fn add_predicate(&mut self, predicate: &NamedNode) {
+        self.predicates.insert(predicate.clone());
+        self.iris.insert(predicate.clone());
+    }
+
+    fn add_named_graph(&mut self, graph: &NamedNode) {
+        self.named_graphs.insert(graph.clone());
+        self.iris.insert(graph.clone());
+    }
+}
+
+impl ParsedStatement {
+    /// Analyse the statement, see [`StatementAnalysis`].
+    pub fn analysis(&self) -> StatementAnalysis {
+        if let Some(query) = &self.query_statement {
+            StatementAnalysis::of_query(query)
+        } else if let Some(update) = &self.update_statement {
+            let mut analysis = StatementAnalysis::default();
+            for operation in update.operations.iter() {
+                analysis.merge(StatementAnalysis::of_update_operation(operation));
+            }
+            analysis
+        } else {
+            StatementAnalysis::default()
+        }
+    }
+}
+
+/// The graph pattern of the `WHERE` clause of the given query, including its
+/// solution modifiers.
+pub(crate) fn query_pattern(query: &Query) -> &GraphPattern {
+    match query {
+        Query::Select { pattern, .. } |
+        Query::Construct { pattern, .. } |
+        Query::Describe { pattern, .. } |
+        Query::Ask { pattern, .. } => pattern,
+    }
+}
+
+pub(crate) fn query_pattern_mut(query: &mut Query) -> &mut GraphPattern {
+    match query {
+        Query::Select { pattern, .. } |
+        Query::Construct { pattern, .. } |
+        Query::Describe { pattern, .. } |
+        Query::Ask { pattern, .. } => pattern,
+    }
+}
+
+/// The variables of the outermost projection of the given pattern, if it
+/// has one below its solution modifiers.
+fn outer_projection(pattern: &GraphPattern) -> Option<&Vec<Variable>> {
+    match pattern {
+        GraphPattern::Slice { inner, .. } |
+        GraphPattern::Distinct { inner } |
+        GraphPattern::Reduced { inner } |
+        GraphPattern::OrderBy { inner, .. } => outer_projection(inner),
+        GraphPattern::Project { variables, .. } => Some(variables),
+        _ => None,
+    }
+}
+
+/// The given pattern without its solution modifiers, outermost projection
+/// and grouping.
+fn where_clause(pattern: &GraphPattern) -> &GraphPattern {
+    match pattern {
+        GraphPattern::Slice { inner, .. } |
+        GraphPattern::Distinct { inner } |
+        GraphPattern::Reduced { inner } |
+        GraphPattern::OrderBy { inner, .. } |
+        GraphPattern::Project { inner, .. } => where_clause(inner),
+        GraphPattern::Group { inner, .. } => inner,
+        // The aggregates of the projection and the HAVING clause on top of the grouping
+        GraphPattern::Extend { inner, .. } | GraphPattern::Filter { inner, .. }
+            if is_grouped(inner) =>
+        {
+            where_clause(inner)
+        },
+        _ => pattern,
+    }
+}
+
+fn is_grouped(pattern: &GraphPattern) -> bool {
+    match pattern {
+        GraphPattern::Group { .. } => true,
+        GraphPattern::Extend { inner, .. } | GraphPattern::Filter { inner, .. } => {
+            is_grouped(inner)
+        },
+        _ => false,
+    }
+}
+
+/// Whether the given function is part of SPARQL 1.0, next to `BOUND` and
+/// `sameTerm` which are expressions of their own. Casts such as
+/// `xsd:integer(?x)` are custom functions.
+fn is_sparql10_function(function: &Function) -> bool {
+    matches!(
+        function,
+        Function::Str |
+            Function::Lang |
+            Function::LangMatches |
+            Function::Datatype |
+            Function::IsIri |
+            Function::IsBlank |
+            Function::IsLiteral |
+            Function::Regex |
+            Function::Custom(_)
+    )
+}
@@ -0,0 +1,225 @@
+use {
+    super::{ParsedStatement, StatementAnalysis, analysis::query_pattern_mut},
+    crate::{
+        Statement,
+        StatementDirectives,
+        statement::directives::{directive_of_line, parameter_name_of_directive},
+    },
+    ekg_error::Error,
+    ekg_metadata::{DataType, Literal, Term},
+    spargebra::{
+        GraphUpdateOperation,
+        algebra::GraphPattern,
+        term::{GroundTerm, NamedNode, Variable},
+    },
+    std::collections::BTreeMap,
+};
+
+impl ParsedStatement {
+    /// Bind the given values to variables of this statement and return the
+    /// resulting statement.
+    ///
+    /// The values are added as a single-row `VALUES` block at the start of
+    /// the `WHERE` clause (of each `DELETE`/`INSERT` operation that uses them
+    /// in the case of an update-statement) so that they can never change the
+    /// structure of the statement, unlike string substitution.
+    ///
+    /// Variable names may be given with or without their leading `?` or `$`.
+    /// Binding a variable that does not occur in the statement is an error,
+    /// as is leaving out a variable that is declared with an `# ekg:param`
+    /// directive or binding it to a value of another datatype.
+    pub fn bind<K, V>(&self, bindings: impl IntoIterator<Item = (K, V)>) -> Result<Statement, Error>
+    where
+        K: AsRef<str>,
+        V: Into<Term>,
+    {
+        let bindings = self.check_bindings(bindings)?;
+        let text = if let Some(query) = &self.query_statement {
+            let mut query = query.clone();
+            inject_values(query_pattern_mut(&mut query), &bindings, true);
+            query.to_string()
+        } else if let Some(update) = &self.update_statement {
+            let mut update = update.clone();
+            for operation in update.operations.iter_mut() {
+                let variables = StatementAnalysis::of_update_operation(operation).variables;
+                if let GraphUpdateOperation::DeleteInsert { pattern, .. } = operation {
+                    let bindings = bindings
+                        .iter()
+                        .filter(|(variable, _)| variables.contains(*variable))
+                        .map(|(variable, value)| (variable.clone(), value.clone()))
+                        .collect::<BTreeMap<_, _>>();
+                    inject_values(pattern, &bindings, true);
+                }
+            }
+            update.to_string()
+        } else {
+            return Err(Error::UnexpectedSPARQLStatementType {
+                expected:  "query or update".to_string(),
+                statement: self.statement.to_string(),
+            });
+        };
+        let is_bound = |name: &str| bindings.keys().any(|variable| variable.as_str() == name);
+        let mut directives: StatementDirectives = self.statement.directives.clone();
+        directives
+            .params
+            .retain(|param| !is_bound(param.name.as_str()));
+        // Keep the directive comments, except the declarations of the variables
+        // that are now bound, so that the text still carries the directives
+        let mut directive_lines = String::new();
+        for line in self.statement.text.lines() {
+            if let Some(directive) = directive_of_line(line) &&
+                !parameter_name_of_directive(directive).is_some_and(is_bound)
+            {
+                directive_lines.push_str(line.trim());
+                directive_lines.push('\n');
+            }
+        }
+        Ok(Statement {
+            prefixes: self.statement.prefixes.clone(),
+            text: format!(
+                "{directive_lines}{}\n{text}",
+                self.statement.prefixes
+            ),
+            params: self.statement.params.clone(),
+            directives,
+        })
+    }
+
+    /// Convert the given bindings, sorted by variable name, and check them
+    /// against the variables of the statement and its `# ekg:param`
+    /// directives.
+    fn check_bindings<K, V>(
+        &self,
+        bindings: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<BTreeMap<Variable, GroundTerm>, Error>
+    where
+        K: AsRef<str>,
+        V: Into<Term>,
+    {
+        let variables = self.analysis().variables;
+        let mut result = BTreeMap::new();
+        for (name, value) in bindings {
+            let name = name.as_ref();
+            let name = name.strip_prefix(['?', '$']).unwrap_or(name);
+            let invalid = |message: String| {
+                Error::InvalidSPARQLBinding { variable: name.to_string(), message }
+            };
+            let variable = Variable::new(name).map_err(|err| invalid(err.to_string()))?;
+            if !variables.contains(&variable) {
+                return Err(Error::UnknownSPARQLBinding {
+                    variable:  name.to_string(),
+                    statement: self.statement.to_string(),
+                });
+            }
+            let value = value.into();
+            if let Some(expected) = self
+                .statement
+                .directives
+                .parameter(name)
+                .and_then(|param| param.data_type)
+            {
+                let actual = term_data_type(&value);
+                if !is_compatible_data_type(expected, actual) {
+                    return Err(invalid(format!(
+                        "expected a value of type {expected:?} but got {actual:?}"
+                    )));
+                }
+            }
+            result.insert(variable, ground_term(&value).map_err(invalid)?);
+        }
+        if let Some(param) = self.statement.directives.params.iter().find(|param| {
+            !result
+                .keys()
+                .any(|variable| variable.as_str() == param.name)
+        }) {
+            return Err(Error::MissingSPARQLBinding {
+                variable:  param.name.clone(),
+                statement: self.statement.to_string(),
+            });
+        }
+        Ok(result)
+    }
+}
+
+impl Statement {
+    /// Parse this statement and bind the given values to its variables, see
+    /// [`ParsedStatement::bind`].
+    pub fn bind<K, V>(&self, bindings: impl IntoIterator<Item = (K, V)>) -> Result<Statement, Error>
+    where
+        K: AsRef<str>,
+        V: Into<Term>,
+    {
+        ParsedStatement::parse(self, None)?.bind(bindings)
+    }
+}
+
+/// Join a `VALUES` block with the given bindings to the given pattern, below
+/// its solution modifiers and (only the outermost) projection so that the
+/// bound variables are in scope of the whole `WHERE` clause.
+fn inject_values(
+    pattern: &mut GraphPattern,
+    bindings: &BTreeMap<Variable, GroundTerm>,
+    outermost: bool,
+) {
+    if bindings.is_empty() {
+        return;
+    }
+    match pattern {
+        GraphPattern::Slice { inner, .. } |
+        GraphPattern::Distinct { inner } |
+        GraphPattern::Reduced { inner } |
+        GraphPattern::OrderBy { inner, .. } |
+        GraphPattern::Group { inner, .. } => inject_values(inner, bindings, outermost),
+        GraphPattern::Project { inner, .. } if outermost => inject_values(inner, bindings, false),
+        _ => {
+            let values = GraphPattern::Values {
+                variables: bindings.keys().cloned().collect(),
+                bindings:  vec![bindings.values().cloned().map(Some).collect()],
+            };
+            let inner = std::mem::take(pattern);
+            *pattern = GraphPattern::Join { left: Box::new(values), right: Box::new(inner) };
+        },
+    }
+}
+
+fn term_data_type(term: &Term) -> DataType {
+    match term {
+        Term::Iri(value) | Term::Literal(value) | Term::BlankNode(value) => value.data_type,
+    }
+}
+
+/// Whether a value of the given actual datatype may be bound to a parameter
+/// that is declared with the given expected datatype.
+fn is_compatible_data_type(expected: DataType, actual: DataType) -> bool {
+    expected == actual ||
+        (expected.is_iri() && actual.is_iri()) ||
+        (expected.is_string() && actual.is_string()) ||
+        (expected.is_integer() && actual.is_integer())
+}
+
+fn ground_term(term: &Term) -> Result<GroundTerm, String> {
+    match term {
+        Term::Iri(value) => {
+            let iri = value
+                .as_iri()
+                .ok_or_else(|| format!("{value} is not an IRI"))?;
+            Ok(GroundTerm::NamedNode(
+                NamedNode::new(iri.as_str()).map_err(|err| err.to_string())?,
+            ))
+        },
+        Term::Literal(value) => Ok(GroundTerm::Literal(sparql_literal(value)?)),
+        Term::BlankNode(value) => Err(format!("cannot bind blank node {value}")),
+    }
+}
+
+/// The given literal as it appears in a SPARQL statement, using the
+/// conversion to `oxrdf` that takes care of the lexical form of each
+/// datatype.
+fn sparql_literal(value: &Literal) -> Result<spargebra::term::Literal, String> {
+    spargebra::term::Literal::try_from(value).map_err(|_| {
+        format!(
+            "values of type {:?} are not supported",
+            value.data_type
+        )
+    })
+}
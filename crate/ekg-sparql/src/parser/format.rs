@@ -0,0 +1,188 @@
+use {
+    super::ParsedStatement,
+    crate::{
+        Prefixes,
+        Statement,
+        statement::scanner::{self, Token, TokenKind},
+    },
+    ekg_error::Error,
+    std::fmt::Write,
+};
+
+const INDENT: &str = "    ";
+
+/// The keywords that start a solution modifier clause, each of which is put
+/// on its own line.
+const SOLUTION_MODIFIERS: [&str; 5] = ["GROUP", "HAVING", "ORDER", "LIMIT", "OFFSET"];
+
+impl ParsedStatement {
+    /// Format the statement in a canonical way: the directives in its
+    /// comments (such as `# ekg:timeout: 30s`), followed by the `PREFIX`
+    /// declarations that are used sorted by prefix and the statement itself,
+    /// regenerated from its algebra with one triple pattern per line and
+    /// indented blocks.
+    ///
+    /// Full IRIs are compacted with the given prefixes only, whatever
+    /// prefixes the text of the statement declares, so statements that only
+    /// differ in layout, comments, their prefix declarations or the use of
+    /// prefixed names versus full IRIs have the same canonical form. That
+    /// makes it usable as a cache key and for reviewing changes.
+    pub fn canonical_string(&self, prefixes: &Prefixes) -> String {
+        let algebra = if let Some(query) = &self.query_statement {
+            query.to_string()
+        } else if let Some(update) = &self.update_statement {
+            update.to_string()
+        } else {
+            String::new()
+        };
+        let mut namespaces = prefixes
+            .iter()
+            .map(|namespace| (namespace.name.as_str(), namespace.iri.as_str()))
+            .collect::<Vec<_>>();
+        // The longest namespace IRI wins if more than one matches
+        namespaces.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+        let (body, mut used) = layout(algebra.as_str(), &namespaces);
+        used.sort();
+        used.dedup();
+
+        let mut output = String::new();
+        for line in self.statement.as_str().lines() {
+            if is_directive_line(line) {
+                output.push_str(line.trim());
+                output.push('\n');
+            }
+        }
+        for (prefix, iri) in used {
+            writeln!(output, "PREFIX {prefix} <{iri}>").unwrap();
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(body.as_str());
+        output
+    }
+}
+
+impl Statement {
+    /// Parse this statement and return it in its canonical form, with full
+    /// IRIs compacted using the prefixes of this statement, see
+    /// [`ParsedStatement::canonical_string`].
+    pub fn canonical(&self) -> Result<Statement, Error> {
+        let parsed = ParsedStatement::parse(self, None)?;
+        Ok(Statement {
+            prefixes:   self.prefixes.clone(),
+            text:       parsed.canonical_string(&self.prefixes),
+            params:     self.params.clone(),
+            directives: self.directives.clone(),
+        })
+    }
+}
+
+/// Whether the given line is a comment with an `ekg:` directive or a
+/// parameter for the SPARQL endpoint.
+fn is_directive_line(line: &str) -> bool {
+    let Some(comment) = line.trim().strip_prefix('#') else {
+        return false;
+    };
+    comment.trim_start().starts_with("ekg:") ||
+        comment
+            .strip_prefix(' ')
+            .and_then(Statement::scan_for_param)
+            .is_some()
+}
+
+fn iri_of<'a>(token: &Token<'a>) -> &'a str { &token.text[1..token.text.len() - 1] }
+
+/// Lay out the given single-line statement as generated by spargebra,
+/// replacing IRIs by prefixed names where possible. Returns the statement and
+/// the namespaces that were used.
+fn layout<'a>(
+    algebra: &str,
+    namespaces: &[(&'a str, &'a str)],
+) -> (String, Vec<(&'a str, &'a str)>) {
+    let tokens = scanner::tokens(algebra);
+    let mut output = String::new();
+    let mut used = Vec::new();
+    let mut depth = 0_usize;
+    let mut previous_end = 0_usize;
+    let mut at_line_start = true;
+    let newline = |output: &mut String, at_line_start: &mut bool| {
+        if !*at_line_start {
+            output.push('\n');
+            *at_line_start = true;
+        }
+    };
+    for (index, token) in tokens.iter().enumerate() {
+        let gap = &algebra[previous_end..token.offset];
+        previous_end = token.offset + token.text.len();
+        if gap.contains('\n') {
+            newline(&mut output, &mut at_line_start);
+        }
+        if token.text == "}" {
+            depth = depth.saturating_sub(1);
+            newline(&mut output, &mut at_line_start);
+        }
+        if depth == 0 &&
+            SOLUTION_MODIFIERS
+                .iter()
+                .any(|keyword| token.is_keyword(keyword))
+        {
+            newline(&mut output, &mut at_line_start);
+        }
+        if at_line_start {
+            output.push_str(INDENT.repeat(depth).as_str());
+        } else if !gap.is_empty() {
+            output.push(' ');
+        }
+        at_line_start = false;
+        let is_base = index > 0 && tokens[index - 1].is_keyword("BASE");
+        match compact(token, namespaces).filter(|_| !is_base) {
+            Some((namespace, prefixed_name)) => {
+                used.push(namespace);
+                output.push_str(prefixed_name.as_str());
+            },
+            None => output.push_str(token.text),
+        }
+        let next = tokens.get(index + 1).map(|token| token.text);
+        match token.text {
+            "{" => {
+                depth += 1;
+                newline(&mut output, &mut at_line_start);
+            },
+            "}" if !matches!(next, Some("." | ")" | "," | ";")) &&
+                !next.is_some_and(|next| next.eq_ignore_ascii_case("UNION")) =>
+            {
+                newline(&mut output, &mut at_line_start)
+            },
+            "." if token.kind == TokenKind::Punctuation => newline(&mut output, &mut at_line_start),
+            _ => {},
+        }
+    }
+    newline(&mut output, &mut at_line_start);
+    (output, used)
+}
+
+/// The given token as prefixed name, if it is an IRI in one of the given
+/// namespaces with a local name that needs no escaping.
+fn compact<'a>(
+    token: &Token,
+    namespaces: &[(&'a str, &'a str)],
+) -> Option<((&'a str, &'a str), String)> {
+    if token.kind != TokenKind::Iri {
+        return None;
+    }
+    let iri = iri_of(token);
+    namespaces.iter().find_map(|&(prefix, namespace)| {
+        let local_name = iri.strip_prefix(namespace)?;
+        let is_simple = local_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') &&
+            !local_name.starts_with('-');
+        is_simple.then(|| {
+            (
+                (prefix, namespace),
+                format!("{prefix}{local_name}"),
+            )
+        })
+    })
+}
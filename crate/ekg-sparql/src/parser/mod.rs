@@ -1,9 +1,20 @@
 use {
-    crate::{statement::Statement, SPARQLFlavor::SPARQL11, SPARQLStatementType},
+    crate::{
+        SPARQLFlavor::{self, SPARQL11},
+        SPARQLStatementType,
+        statement::{Statement, scanner},
+    },
     ekg_metadata::Namespace,
-    spargebra::{Query, Update},
+    spargebra::{GraphUpdateOperation, Query, SparqlParser, SparqlSyntaxError, Update},
 };
 
+mod analysis;
+mod bind;
+mod format;
+
+pub(crate) use analysis::query_pattern_mut;
+pub use analysis::StatementAnalysis;
+
 #[cfg(test)]
 mod tests;
 
@@ -38,78 +49,69 @@ impl ParsedStatement {
         statement: &Statement,
         base_ns: Option<&Namespace>,
     ) -> Result<(SPARQLStatementType, Option<Query>, Option<Update>), ekg_error::Error> {
-        let base_iri = base_ns.map(|ns| ns.iri.to_string());
-        // First figure out whether we are dealing with a SPARQL update-statement by
-        // attempting to parse it. If that fails, we'll treat it as a SPARQL
-        // query-statement.
-        match Update::parse(statement.as_str(), base_iri.as_deref()) {
-            Ok(algebra) => {
-                Ok((
-                    SPARQLStatementType::UPDATE(SPARQL11),
-                    None,
-                    Some(algebra),
-                ))
-            },
-            Err(err) => {
-                let err_str = {
-                    use std::error::Error;
-                    format!("{:}", err.source().unwrap())
-                };
-                if err_str.contains("expected one of CREATE, DELETE, INSERT, PREFIX") {
-                    tracing::debug!(
-                        target: ekg_util::log::LOG_TARGET_SPARQL,
-                        "SPARQL statement is not an update-statement, trying now to see if its a \
-                         query-statement:"
-                    );
-                } else {
-                    return Err(ekg_error::Error::SPARQLStatementError {
-                        source:    err,
-                        statement: statement.to_string(),
-                    });
-                }
-                match Query::parse(statement.as_str(), base_iri.as_deref()) {
-                    Ok(query_algebra) => {
-                        // tracing::error!("{}", query_algebra.to_sse());
-                        match query_algebra {
-                            Query::Select { .. } => {
-                                Ok((
-                                    SPARQLStatementType::SELECT(SPARQL11),
-                                    Some(query_algebra),
-                                    None,
-                                ))
-                            },
-                            Query::Ask { .. } => {
-                                Ok((
-                                    SPARQLStatementType::ASK(SPARQL11),
-                                    Some(query_algebra),
-                                    None,
-                                ))
-                            },
-                            Query::Construct { .. } => {
-                                Ok((
-                                    SPARQLStatementType::CONSTRUCT(SPARQL11),
-                                    Some(query_algebra),
-                                    None,
-                                ))
-                            },
-                            Query::Describe { .. } => {
-                                Ok((
-                                    SPARQLStatementType::DESCRIBE(SPARQL11),
-                                    Some(query_algebra),
-                                    None,
-                                ))
-                            },
-                        }
-                    },
-                    Err(err) => {
-                        Err(ekg_error::Error::SPARQLStatementError {
-                            source:    err,
-                            statement: statement.to_string(),
-                        })
-                    },
-                }
+        let parser = Self::parser(base_ns)?;
+        let syntax_error = |source: SparqlSyntaxError| {
+            let (line, column) = syntax_error_position(&source).unzip();
+            ekg_error::Error::SPARQLStatementError {
+                source,
+                statement: statement.to_string(),
+                line,
+                column,
+            }
+        };
+        // Pick the parser based on the first keyword after the prologue, queries
+        // and updates are handled by separate parsers in spargebra.
+        if !is_query(statement.as_str()) {
+            let update = parser
+                .parse_update(statement.as_str())
+                .map_err(syntax_error)?;
+            let statement_type = if is_delete_only(&update) {
+                SPARQLStatementType::DELETE(SPARQL11)
+            } else {
+                SPARQLStatementType::UPDATE(SPARQL11)
+            };
+            return Ok((statement_type, None, Some(update)));
+        }
+        let query = parser
+            .parse_query(statement.as_str())
+            .map_err(syntax_error)?;
+        let flavor = StatementAnalysis::of_query(&query).flavor();
+        let statement_type = match query {
+            Query::Select { .. } => SPARQLStatementType::SELECT(flavor),
+            Query::Ask { .. } => SPARQLStatementType::ASK(flavor),
+            Query::Construct { .. } => SPARQLStatementType::CONSTRUCT(flavor),
+            Query::Describe { .. } => SPARQLStatementType::DESCRIBE(flavor),
+        };
+        Ok((statement_type, Some(query), None))
+    }
+
+    fn parser(base_ns: Option<&Namespace>) -> Result<SparqlParser, ekg_error::Error> {
+        match base_ns {
+            Some(ns) => {
+                SparqlParser::new()
+                    .with_base_iri(ns.iri.to_string())
+                    .map_err(|err| ekg_error::Error::InvalidBaseIri(err.to_string()))
             },
+            None => Ok(SparqlParser::new()),
+        }
+    }
+
+    /// Check that the statement only uses features of the given SPARQL
+    /// flavor, for instance the flavor that a story declares for its SPARQL
+    /// implementation.
+    pub fn check_flavor(&self, declared: SPARQLFlavor) -> Result<(), ekg_error::Error> {
+        if declared == SPARQL11 {
+            return Ok(());
+        }
+        let features = self.analysis().sparql11_features();
+        if features.is_empty() {
+            return Ok(());
         }
+        Err(ekg_error::Error::UnsupportedSPARQLFeatures {
+            flavor:    format!("{declared:?}"),
+            features:  features.join(", "),
+            statement: self.statement.to_string(),
+        })
     }
 
     #[allow(unused)]
@@ -147,3 +149,50 @@ impl ParsedStatement {
         matches!(&self.update_statement, Some(Update { .. }))
     }
 }
+
+/// Whether the given statement is a query-statement rather than an
+/// update-statement, based on the first keyword after the prologue (the
+/// comments and the `BASE`, `PREFIX` and `VERSION` declarations).
+fn is_query(statement: &str) -> bool {
+    let tokens = scanner::tokens(statement);
+    let mut tokens = tokens
+        .iter()
+        .filter(|token| token.kind != scanner::TokenKind::Comment);
+    while let Some(token) = tokens.next() {
+        if token.is_keyword("PREFIX") {
+            tokens.nth(1);
+        } else if token.is_keyword("BASE") || token.is_keyword("VERSION") {
+            tokens.next();
+        } else {
+            return ["SELECT", "ASK", "CONSTRUCT", "DESCRIBE"]
+                .iter()
+                .any(|keyword| token.is_keyword(keyword));
+        }
+    }
+    false
+}
+
+/// Whether the given update-statement only deletes triples.
+fn is_delete_only(update: &Update) -> bool {
+    !update.operations.is_empty() &&
+        update.operations.iter().all(|operation| {
+            match operation {
+                GraphUpdateOperation::DeleteData { .. } => true,
+                GraphUpdateOperation::DeleteInsert { insert, .. } => insert.is_empty(),
+                _ => false,
+            }
+        })
+}
+
+/// The line and column of the given syntax error, as far as spargebra
+/// reports it in its message (`error at 3:14: expected ...`).
+///
+/// spargebra keeps the position of a syntax error private, so it has to be
+/// taken from the message. `test_spargebra_syntax_error_format` fails if a
+/// new version of spargebra formats its messages differently.
+fn syntax_error_position(err: &SparqlSyntaxError) -> Option<(usize, usize)> {
+    let message = err.to_string();
+    let (position, _) = message.strip_prefix("error at ")?.split_once(": ")?;
+    let (line, column) = position.split_once(':')?;
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
@@ -29,3 +29,572 @@ fn test_parse_update_statement() {
     let statement = crate::Statement::new(prefixes, TEST_UPDATE_STATEMENT.into()).unwrap();
     crate::ParsedStatement::parse(&statement, None).expect("TODO: panic message");
 }
+
+fn statement(sparql: &str) -> Result<crate::Statement, ekg_error::Error> {
+    crate::Statement::new(crate::Prefixes::try_default()?, sparql.into())
+}
+
+#[test_log::test]
+fn test_bind_select_statement() -> Result<(), ekg_error::Error> {
+    let statement = statement(indoc::indoc! {r#"
+        # ekg:param ?date xsd:date
+        SELECT DISTINCT ?thing
+        WHERE {
+            ?thing ?p ?date .
+            ?thing ?q ?name .
+        }
+        LIMIT 10
+    "#})?;
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let bound = statement.bind([
+        (
+            "?date",
+            ekg_metadata::Literal::new_date_with_datatype(date, ekg_metadata::DataType::Date)?,
+        ),
+        (
+            "name",
+            ekg_metadata::Literal::new_plain_literal_string("x\" } DROP ALL #")?,
+        ),
+    ])?;
+
+    assert!(bound.as_str().contains(
+        r#"VALUES ( ?date ?name ) { ( "2024-02-29"^^<http://www.w3.org/2001/XMLSchema#date> "x\" } DROP ALL #" )"#
+    ));
+    assert!(bound.as_str().contains("LIMIT 10"));
+    assert!(bound.directives.params.is_empty());
+    let parsed = crate::ParsedStatement::parse(&bound, None)?;
+    assert!(parsed.is_select_statement());
+    Ok(())
+}
+
+#[test_log::test]
+fn test_bind_keeps_directives() -> Result<(), ekg_error::Error> {
+    let statement = statement(indoc::indoc! {r#"
+        # ekg:name: things-by-date
+        # ekg:timeout: 30s
+        # ekg:max-rows: 100
+        # ekg:param ?date xsd:date
+        SELECT ?thing WHERE { ?thing ?p ?date ; ?q ?name }
+    "#})?;
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let bound = statement.bind([(
+        "date",
+        ekg_metadata::Literal::new_date_with_datatype(date, ekg_metadata::DataType::Date)?,
+    )])?;
+
+    assert_eq!(
+        bound.directives.name.as_deref(),
+        Some("things-by-date")
+    );
+    assert_eq!(
+        bound.directives.timeout,
+        Some(std::time::Duration::from_secs(30))
+    );
+    assert_eq!(bound.directives.max_rows, Some(100));
+    assert!(bound.directives.params.is_empty());
+    assert!(bound.as_str().contains("# ekg:max-rows: 100"));
+    assert!(!bound.as_str().contains("ekg:param"));
+
+    // The text of the bound statement still carries the same directives
+    let reparsed = crate::Statement::new(bound.prefixes.clone(), bound.as_str().into())?;
+    assert_eq!(reparsed.directives, bound.directives);
+
+    // and its remaining variables can be bound in turn
+    let bound = bound.bind([(
+        "name",
+        ekg_metadata::Literal::new_plain_literal_string("x")?,
+    )])?;
+    assert_eq!(
+        bound.directives.name.as_deref(),
+        Some("things-by-date")
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_bind_lexical_types() -> Result<(), ekg_error::Error> {
+    use ekg_metadata::DataType;
+
+    let statement = statement("SELECT ?thing WHERE { ?thing ?p ?value }")?;
+    for (data_type, value, expected) in [
+        (
+            DataType::Double,
+            "1.5E0",
+            r#""1.5E0"^^<http://www.w3.org/2001/XMLSchema#double>"#,
+        ),
+        (
+            DataType::Float,
+            "1.5",
+            r#""1.5"^^<http://www.w3.org/2001/XMLSchema#float>"#,
+        ),
+        (
+            DataType::Time,
+            "12:30:00",
+            r#""12:30:00"^^<http://www.w3.org/2001/XMLSchema#time>"#,
+        ),
+        (
+            DataType::YearMonth,
+            "2024-03",
+            r#""2024-03"^^<http://www.w3.org/2001/XMLSchema#gYearMonth>"#,
+        ),
+    ] {
+        let literal = ekg_metadata::Literal::from_type_and_buffer(data_type, value, None)?.unwrap();
+        let bound = statement.bind([("value", literal)])?;
+        assert!(
+            bound.as_str().contains(expected),
+            "{data_type:?}: {bound}"
+        );
+    }
+    let bound = statement.bind([(
+        "value",
+        ekg_metadata::Literal::new_language_tagged_string("chat", "fr")?,
+    )])?;
+    assert!(bound.as_str().contains(r#""chat"@fr"#));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_bind_update_statement() -> Result<(), ekg_error::Error> {
+    let statement = statement(indoc::indoc! {r#"
+        DELETE { ?thing ?p ?old }
+        INSERT { ?thing ?p ?new }
+        WHERE { ?thing ?p ?old }
+    "#})?;
+    let bound = statement.bind([
+        (
+            "thing",
+            ekg_metadata::Term::new_iri_from_str("https://example.com/thing")?,
+        ),
+        (
+            "new",
+            ekg_metadata::Literal::new_signed_integer(42)?.as_term(),
+        ),
+    ])?;
+
+    assert!(bound.as_str().contains("VALUES ( ?new ?thing )"));
+    assert!(bound.as_str().contains("<https://example.com/thing>"));
+    assert!(crate::ParsedStatement::parse(&bound, None)?.is_update_statement());
+    Ok(())
+}
+
+#[test_log::test]
+fn test_bind_errors() -> Result<(), ekg_error::Error> {
+    let statement = statement(indoc::indoc! {r#"
+        # ekg:param ?date xsd:date
+        ASK { ?thing ?p ?date }
+    "#})?;
+    let date = ekg_metadata::Literal::new_date_with_datatype(
+        chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+        ekg_metadata::DataType::Date,
+    )?;
+
+    assert!(matches!(
+        statement.bind([("other", date.clone())]),
+        Err(ekg_error::Error::UnknownSPARQLBinding { variable, .. }) if variable == "other"
+    ));
+    assert!(matches!(
+        statement.bind([("thing", date.clone())]),
+        Err(ekg_error::Error::MissingSPARQLBinding { variable, .. }) if variable == "date"
+    ));
+    assert!(matches!(
+        statement.bind([("date", ekg_metadata::Literal::new_boolean(true)?)]),
+        Err(ekg_error::Error::InvalidSPARQLBinding { variable, .. }) if variable == "date"
+    ));
+    assert!(matches!(
+        statement.bind([
+            ("date", date.as_term()),
+            ("thing", ekg_metadata::Term::new_blank_node("b0")?)
+        ]),
+        Err(ekg_error::Error::InvalidSPARQLBinding { variable, .. }) if variable == "thing"
+    ));
+    let bound = statement.bind([("$date", date)])?;
+    assert!(bound.as_str().contains("VALUES ( ?date )"));
+    Ok(())
+}
+
+fn iri(iri: &str) -> spargebra::term::NamedNode { spargebra::term::NamedNode::new_unchecked(iri) }
+
+fn variable(name: &str) -> spargebra::term::Variable {
+    spargebra::term::Variable::new_unchecked(name)
+}
+
+#[test_log::test]
+fn test_analysis_of_select_statement() -> Result<(), ekg_error::Error> {
+    let statement = statement(indoc::indoc! {r#"
+        PREFIX ex: <https://example.com/>
+        SELECT ?thing (COUNT(?label) AS ?labels)
+        FROM NAMED <https://example.com/graph/a>
+        WHERE {
+            GRAPH <https://example.com/graph/b> {
+                ?thing a ex:Thing ;
+                    ex:label ?label .
+            }
+            ?thing ex:parent+ ?parent .
+            FILTER(?label != "x"^^xsd:token)
+        }
+        GROUP BY ?thing
+    "#})?;
+    let analysis = crate::ParsedStatement::parse(&statement, None)?.analysis();
+
+    assert_eq!(analysis.projected_variables, vec![
+        variable("thing"),
+        variable("labels")
+    ]);
+    assert_eq!(
+        analysis.in_scope_variables,
+        ["label", "parent", "thing"].map(variable).into()
+    );
+    assert_eq!(
+        analysis.named_graphs,
+        [
+            iri("https://example.com/graph/a"),
+            iri("https://example.com/graph/b")
+        ]
+        .into()
+    );
+    assert_eq!(analysis.triple_patterns.len(), 2);
+    assert_eq!(
+        analysis.predicates,
+        [
+            iri("http://www.w3.org/1999/02/22-rdf-syntax-ns#type"),
+            iri("https://example.com/label"),
+            iri("https://example.com/parent"),
+        ]
+        .into()
+    );
+    assert!(analysis.iris.contains(&iri("https://example.com/Thing")));
+    assert!(
+        analysis
+            .iris
+            .contains(&iri("http://www.w3.org/2001/XMLSchema#token"))
+    );
+    assert!(analysis.uses_aggregates);
+    assert!(analysis.uses_property_paths);
+    assert!(!analysis.uses_service);
+
+    let namespaces = analysis.namespaces(&statement.prefixes);
+    let names = namespaces
+        .iter()
+        .map(|namespace| namespace.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["rdf:", "xsd:"]);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_analysis_of_update_statement() -> Result<(), ekg_error::Error> {
+    let statement = statement(indoc::indoc! {r#"
+        DELETE { GRAPH <https://example.com/graph> { ?thing ?p ?old } }
+        WHERE {
+            SERVICE <https://example.com/sparql> { ?thing ?p ?old }
+        } ;
+        DROP SILENT GRAPH <https://example.com/other>
+    "#})?;
+    let analysis = crate::ParsedStatement::parse(&statement, None)?.analysis();
+
+    assert!(analysis.projected_variables.is_empty());
+    assert_eq!(
+        analysis.variables,
+        ["old", "p", "thing"].map(variable).into()
+    );
+    assert_eq!(
+        analysis.named_graphs,
+        [
+            iri("https://example.com/graph"),
+            iri("https://example.com/other")
+        ]
+        .into()
+    );
+    assert!(analysis.iris.contains(&iri("https://example.com/sparql")));
+    assert!(analysis.uses_service);
+    assert!(!analysis.uses_aggregates);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_canonical_statement() -> Result<(), ekg_error::Error> {
+    let compact = statement(indoc::indoc! {r#"
+        # ekg:timeout: 30s
+        # Just a comment
+        PREFIX ex: <https://example.com/>
+        SELECT ?thing ?label WHERE { ?thing a ex:Thing ; rdfs:label ?label .
+          OPTIONAL { ?thing ex:date ?date } FILTER(?date > "2024-01-01"^^xsd:date) }
+        ORDER BY ?label LIMIT 10
+    "#})?;
+    let other = statement(indoc::indoc! {r#"
+        # ekg:timeout: 30s
+        PREFIX ex: <https://example.com/>
+        SELECT ?thing ?label
+        WHERE {
+            ?thing <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://example.com/Thing> .
+            ?thing rdfs:label ?label .
+            OPTIONAL {
+                ?thing ex:date ?date
+            }
+            FILTER (?date > "2024-01-01"^^xsd:date)
+        }
+        ORDER BY ?label
+        LIMIT 10
+    "#})?;
+    // Declares another prefix for the same namespace and uses full IRIs
+    let full = statement(indoc::indoc! {r#"
+        # ekg:timeout: 30s
+        PREFIX foo: <https://example.com/>
+        SELECT ?thing ?label WHERE {
+            ?thing a foo:Thing ; <http://www.w3.org/2000/01/rdf-schema#label> ?label .
+            OPTIONAL { ?thing <https://example.com/date> ?date }
+            FILTER(?date > "2024-01-01"^^<http://www.w3.org/2001/XMLSchema#date>)
+        } ORDER BY ?label LIMIT 10
+    "#})?;
+    let canonical = compact.canonical()?;
+
+    assert_eq!(canonical.as_str(), other.canonical()?.as_str());
+    assert_eq!(canonical.as_str(), full.canonical()?.as_str());
+    // Only the prefixes of the statement are used, not the ones in its text
+    assert_eq!(canonical.as_str(), indoc::indoc! {r#"
+            # ekg:timeout: 30s
+            PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>
+            PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+            PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+
+            SELECT ?thing ?label WHERE {
+                ?thing rdf:type <https://example.com/Thing> .
+                ?thing rdfs:label ?label .
+                OPTIONAL {
+                    ?thing <https://example.com/date> ?date .
+                }
+                FILTER((?date > "2024-01-01"^^xsd:date))
+            }
+            ORDER BY ASC(?label)
+            LIMIT 10
+        "#});
+    assert_eq!(
+        canonical.canonical()?.as_str(),
+        canonical.as_str()
+    );
+    assert_eq!(canonical.directives, compact.directives);
+
+    // or the ones given by the caller
+    let mut prefixes = crate::Prefixes::try_default()?;
+    prefixes.declare_namespace(&ekg_identifier::Namespace::declare_from_str(
+        "ex:",
+        "https://example.com/",
+    )?)?;
+    let canonical = crate::ParsedStatement::parse(&full, None)?.canonical_string(&prefixes);
+    assert!(canonical.starts_with("# ekg:timeout: 30s\nPREFIX ex: <https://example.com/>\n"));
+    assert!(canonical.contains("?thing rdf:type ex:Thing .\n"));
+    assert!(!canonical.contains("foo:"));
+
+    let update = statement(indoc::indoc! {r#"
+        PREFIX ex: <https://example.com/>
+        INSERT DATA { GRAPH ex:graph { ex:a ex:b "c"@en } } ;
+        DELETE { ?s ex:b ?o } WHERE { { ?s ex:b ?o } UNION { ?s ex:d ?o } }
+    "#})?
+    .canonical()?;
+    assert!(update.as_str().contains("} UNION {"));
+    assert!(crate::ParsedStatement::parse(&update, None)?.is_update_statement());
+    Ok(())
+}
+
+#[test_log::test]
+fn test_sparql_flavor() -> Result<(), ekg_error::Error> {
+    use crate::SPARQLFlavor::{SPARQL10, SPARQL11};
+
+    let parse = |sparql: &str| crate::ParsedStatement::parse(&statement(sparql)?, None);
+
+    let plain = parse("SELECT ?s WHERE { ?s a ?type OPTIONAL { ?s rdfs:label ?label } }")?;
+    assert_eq!(plain.statement_type.flavor(), SPARQL10);
+    assert!(plain.analysis().sparql11_features().is_empty());
+    plain.check_flavor(SPARQL10)?;
+
+    let functions = parse(indoc::indoc! {r#"
+        SELECT ?s WHERE {
+            ?s ?p ?o
+            FILTER(isLiteral(?o) && langMatches(lang(?o), "en") && regex(str(?o), "^a"))
+            FILTER(bound(?o) && sameTerm(datatype(?o), xsd:string) && xsd:integer("1") = 1)
+        }
+    "#})?;
+    assert!(functions.analysis().sparql11_features().is_empty());
+    functions.check_flavor(SPARQL10)?;
+
+    for (sparql, feature) in [
+        (
+            "SELECT ?s WHERE { ?s rdfs:subClassOf+ ?o }",
+            "property paths",
+        ),
+        (
+            "SELECT (COUNT(?s) AS ?n) WHERE { ?s ?p ?o }",
+            "aggregates",
+        ),
+        (
+            "SELECT ?s WHERE { { SELECT ?s WHERE { ?s ?p ?o } LIMIT 1 } }",
+            "subqueries",
+        ),
+        (
+            "SELECT ?s ?x WHERE { ?s ?p ?o BIND(1 AS ?x) }",
+            "BIND",
+        ),
+        (
+            "SELECT ?s WHERE { VALUES ?s { <urn:a> } ?s ?p ?o }",
+            "VALUES",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o MINUS { ?s a ?type } }",
+            "MINUS",
+        ),
+        (
+            "ASK { ?s ?p ?o FILTER NOT EXISTS { ?s a ?type } }",
+            "EXISTS",
+        ),
+        (
+            "SELECT ?s WHERE { SERVICE <https://example.com/sparql> { ?s ?p ?o } }",
+            "SERVICE",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(?o IN (1, 2)) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(?o NOT IN (1, 2)) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(STRLEN(CONCAT(?o, \"x\")) > 2) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(IF(BOUND(?o), ?o, 0) > 1) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(COALESCE(?o, 1) > 0) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(CONTAINS(UCASE(STR(?o)), \"X\")) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(?o < NOW()) }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(SHA256(STR(?o)) != \"\") }",
+            "SPARQL 1.1 functions",
+        ),
+        (
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(ROUND(?o) = 1) }",
+            "SPARQL 1.1 functions",
+        ),
+    ] {
+        let parsed = parse(sparql)?;
+        assert_eq!(
+            parsed.statement_type.flavor(),
+            SPARQL11,
+            "{sparql}"
+        );
+        assert_eq!(
+            parsed.analysis().sparql11_features(),
+            vec![feature],
+            "{sparql}"
+        );
+        parsed.check_flavor(SPARQL11)?;
+        assert!(matches!(
+            parsed.check_flavor(SPARQL10),
+            Err(ekg_error::Error::UnsupportedSPARQLFeatures { .. })
+        ));
+    }
+
+    let update = parse("INSERT DATA { <urn:a> a <urn:b> }")?;
+    assert_eq!(update.statement_type.flavor(), SPARQL11);
+    assert_eq!(update.analysis().sparql11_features(), vec![
+        "update"
+    ]);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_statement_type_detection() -> Result<(), ekg_error::Error> {
+    use crate::{SPARQLFlavor::SPARQL11, SPARQLStatementType};
+
+    let parse = |sparql: &str| crate::ParsedStatement::parse(&statement(sparql)?, None);
+
+    let query = parse(indoc::indoc! {r#"
+        # INSERT { ?s ?p ?o } is not what this does
+        BASE <https://example.com/>
+        PREFIX : <https://example.com/>
+        PREFIX delete: <https://example.com/delete/>
+        SELECT ?s WHERE { ?s delete:this "DELETE" }
+    "#})?;
+    assert!(query.is_select_statement());
+    assert!(parse("ask { ?s ?p ?o }")?.is_ask_statement());
+
+    for (sparql, statement_type) in [
+        (
+            "DELETE DATA { <urn:a> a <urn:b> }",
+            SPARQLStatementType::DELETE(SPARQL11),
+        ),
+        (
+            "DELETE WHERE { ?s a <urn:b> }",
+            SPARQLStatementType::DELETE(SPARQL11),
+        ),
+        (
+            "DELETE { ?s a <urn:b> } WHERE { ?s a <urn:b> }",
+            SPARQLStatementType::DELETE(SPARQL11),
+        ),
+        (
+            "DELETE { ?s a <urn:b> } INSERT { ?s a <urn:c> } WHERE { ?s a <urn:b> }",
+            SPARQLStatementType::UPDATE(SPARQL11),
+        ),
+        (
+            "DELETE DATA { <urn:a> a <urn:b> } ; INSERT DATA { <urn:a> a <urn:c> }",
+            SPARQLStatementType::UPDATE(SPARQL11),
+        ),
+        ("CLEAR ALL", SPARQLStatementType::UPDATE(SPARQL11)),
+    ] {
+        let parsed = parse(sparql)?;
+        assert!(parsed.is_update_statement(), "{sparql}");
+        assert_eq!(parsed.statement_type, statement_type, "{sparql}");
+    }
+    Ok(())
+}
+
+/// The position of a syntax error is parsed from the message of spargebra,
+/// make sure that its format is still the one that is expected.
+#[test_log::test]
+fn test_spargebra_syntax_error_format() {
+    let err = spargebra::SparqlParser::new()
+        .parse_query("SELECT ?s\nWHERE {\n    ?s ?p\n}")
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with("error at 4:2: "),
+        "spargebra changed the format of its syntax errors, update syntax_error_position() to \
+         handle \"{message}\""
+    );
+    assert_eq!(super::syntax_error_position(&err), Some((4, 2)));
+}
+
+#[test_log::test]
+fn test_syntax_error_position() -> Result<(), ekg_error::Error> {
+    for (sparql, position) in [
+        ("SELECT ?s\nWHERE {\n    ?s ?p\n}", (4, 2)),
+        ("INSERT DATA {\n  <urn:a> a\n}", (3, 1)),
+    ] {
+        // The position is relative to the statement including its prefixes
+        let statement = statement(sparql)?;
+        let prologue = statement.as_str().lines().count() - sparql.lines().count();
+        match crate::ParsedStatement::parse(&statement, None) {
+            Err(ekg_error::Error::SPARQLStatementError { line, column, .. }) => {
+                assert_eq!(
+                    (line, column),
+                    (Some(prologue + position.0), Some(position.1)),
+                    "{sparql}"
+                );
+            },
+            Err(err) => panic!("unexpected error {err:?}"),
+            Ok(_) => panic!("expected a syntax error in {sparql}"),
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use {
-    crate::prefixes::this::Prefixes,
+    crate::{PrefixesDeclareOutcome, prefixes::this::Prefixes},
     ekg_identifier::{NS_OWL, NS_RDF, NS_RDFS, NS_XSD},
     ekg_metadata::{Class, Namespace, Predicate},
     ekg_util::log::LOG_TARGET_DATABASE,
@@ -28,8 +28,8 @@ impl PrefixesBuilder {
             .declare(NS_XSD.deref())
     }
 
-    pub fn declare_namespaces(mut self, namespaces: &Vec<Namespace>) -> Self {
-        self.prefixes.append(&mut namespaces.clone());
+    pub fn declare_namespaces(mut self, namespaces: &[Namespace]) -> Self {
+        self.prefixes.extend_from_slice(namespaces);
         self
     }
 
@@ -43,10 +43,19 @@ impl PrefixesBuilder {
         self
     }
 
+    /// Build the prefixes, failing with [`ekg_error::Error::InvalidPrefixes`]
+    /// if a prefix is declared for two different IRIs.
     pub fn build(self) -> Result<Prefixes, ekg_error::Error> {
         let mut to_build = Prefixes::empty()?;
         for namespace in self.prefixes.iter() {
-            to_build.declare_namespace(namespace)?;
+            if let PrefixesDeclareOutcome::ConflictingIri(existing) =
+                to_build.declare_namespace(namespace)?
+            {
+                return Err(ekg_error::Error::InvalidPrefixes(format!(
+                    "PREFIX {} is declared as both <{}> and <{}>",
+                    namespace.name, existing.iri, namespace.iri
+                )));
+            }
         }
         Ok(to_build)
     }
@@ -1,3 +1,5 @@
+use ekg_identifier::Namespace;
+
 #[repr(u32)]
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
@@ -10,6 +12,49 @@ pub enum PrefixesDeclareResult {
     PREFIXES_DECLARED_NEW        = 3,
 }
 
+/// The outcome of declaring a namespace in [`crate::Prefixes`]: either one of
+/// the results defined by the RDFox C API or a conflict with an existing
+/// declaration, which RDFox does not report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefixesDeclareOutcome {
+    Declared(PrefixesDeclareResult),
+    /// The prefix is already declared for another IRI, the existing
+    /// declaration (given here) is kept
+    ConflictingIri(Namespace),
+    /// The namespace is declared but its IRI is also declared under another
+    /// prefix (given here)
+    DuplicateIri(Namespace),
+}
+
+impl PrefixesDeclareOutcome {
+    /// Whether the namespace has been declared, possibly next to another
+    /// prefix for the same IRI.
+    pub fn is_declared(&self) -> bool {
+        matches!(
+            self,
+            Self::Declared(PrefixesDeclareResult::PREFIXES_DECLARED_NEW) | Self::DuplicateIri(_)
+        )
+    }
+
+    /// Whether the declaration conflicts with an existing one.
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self,
+            Self::ConflictingIri(_) | Self::DuplicateIri(_)
+        )
+    }
+}
+
+impl From<PrefixesDeclareResult> for PrefixesDeclareOutcome {
+    fn from(value: PrefixesDeclareResult) -> Self { Self::Declared(value) }
+}
+
+impl PartialEq<PrefixesDeclareResult> for PrefixesDeclareOutcome {
+    fn eq(&self, other: &PrefixesDeclareResult) -> bool {
+        matches!(self, Self::Declared(result) if result == other)
+    }
+}
+
 #[cfg(feature = "_rdfox")]
 impl From<rdfox_sys::CPrefixes_DeclareResult> for PrefixesDeclareResult {
     fn from(value: rdfox_sys::CPrefixes_DeclareResult) -> Self {
@@ -0,0 +1,238 @@
+use {
+    crate::{
+        Prefixes,
+        statement::scanner::{self, TokenKind},
+    },
+    ekg_error::Error,
+    ekg_identifier::Namespace,
+    serde_json::{Map, Value},
+};
+
+/// The characters that a JSON-LD term definition has to end with to be
+/// usable as a prefix, see <https://www.w3.org/TR/json-ld11/#dfn-gen-delim>.
+const GEN_DELIMS: [char; 7] = [':', '/', '?', '#', '[', ']', '@'];
+
+impl Prefixes {
+    /// Read the `@prefix` (or `PREFIX`) declarations of the given Turtle
+    /// document, such as an ontology file.
+    ///
+    /// The whole document is parsed, so a document with syntax errors is
+    /// rejected with [`Error::InvalidRDF`].
+    pub fn from_turtle(turtle: &str) -> Result<Self, Error> {
+        let mut parser = oxttl::TurtleParser::new().for_slice(turtle);
+        for triple in parser.by_ref() {
+            triple.map_err(|err| Error::InvalidRDF(err.to_string()))?;
+        }
+        let namespaces = parser
+            .prefixes()
+            .map(|(name, iri)| Namespace::declare_from_str(format!("{name}:").as_str(), iri))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::builder().declare_namespaces(&namespaces).build()
+    }
+
+    /// Read the prefixes of the given JSON-LD `@context`, which may be the
+    /// context object itself or a document with a `@context` member.
+    ///
+    /// Term definitions that cannot be used as a prefix, such as
+    /// `"name": "http://schema.org/name"`, are skipped. Remote contexts
+    /// (given as IRI) are not supported.
+    pub fn from_json_ld_context(json_ld: &str) -> Result<Self, Error> {
+        let value: Value = serde_json::from_str(json_ld)?;
+        let invalid = |message: &str| Error::InvalidPrefixes(format!("JSON-LD context {message}"));
+        let context = match value.get("@context") {
+            Some(context) => context,
+            None => &value,
+        };
+        let contexts = match context {
+            Value::Array(contexts) => contexts.iter().collect::<Vec<_>>(),
+            context => vec![context],
+        };
+        let mut namespaces = Vec::new();
+        for context in contexts {
+            let Value::Object(definitions) = context else {
+                return Err(invalid("is not an object"));
+            };
+            for (term, definition) in definitions {
+                if term.starts_with('@') {
+                    continue;
+                }
+                let iri = match definition {
+                    Value::String(iri) if iri.ends_with(GEN_DELIMS) => iri,
+                    Value::Object(definition) if is_prefix_definition(definition) => {
+                        definition
+                            .get("@id")
+                            .and_then(Value::as_str)
+                            .ok_or_else(|| invalid("has a prefix without @id"))?
+                    },
+                    _ => continue,
+                };
+                namespaces.push(Namespace::declare_from_str(
+                    format!("{term}:").as_str(),
+                    iri,
+                )?);
+            }
+        }
+        Self::builder().declare_namespaces(&namespaces).build()
+    }
+
+    /// Read a simple prefix mapping with one `prefix: iri` pair per line,
+    /// like the files that <https://prefix.cc> offers for download. The colon
+    /// after the prefix is optional (`prefix<TAB>iri` works too) and so are
+    /// angle brackets around the IRI. Empty lines and lines starting with
+    /// `#` are skipped.
+    pub fn from_prefix_mapping(mapping: &str) -> Result<Self, Error> {
+        let mut namespaces = Vec::new();
+        for (index, line) in mapping.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, iri) = line
+                .split_once(|c: char| c == ':' || c.is_whitespace())
+                .map(|(name, iri)| (name.trim(), iri.trim()))
+                .filter(|(name, iri)| !name.contains(char::is_whitespace) && !iri.is_empty())
+                .ok_or_else(|| {
+                    Error::InvalidPrefixes(format!(
+                        "line {} is not a \"prefix: iri\" pair: {line}",
+                        index + 1
+                    ))
+                })?;
+            let iri = iri
+                .strip_prefix('<')
+                .and_then(|iri| iri.strip_suffix('>'))
+                .unwrap_or(iri);
+            namespaces.push(Namespace::declare_from_str(
+                format!("{name}:").as_str(),
+                iri,
+            )?);
+        }
+        Self::builder().declare_namespaces(&namespaces).build()
+    }
+
+    /// Read the `PREFIX` declarations in the prologue of the given SPARQL
+    /// statement, or of a file with just a prologue such as the ones written
+    /// by [`Prefixes::save`]. Comments and `BASE` and `VERSION` declarations
+    /// are skipped, reading stops at the first keyword after the prologue.
+    pub fn from_sparql(sparql: &str) -> Result<Self, Error> {
+        let tokens = scanner::tokens(sparql);
+        let mut tokens = tokens
+            .iter()
+            .filter(|token| token.kind != TokenKind::Comment);
+        let mut namespaces = Vec::new();
+        while let Some(token) = tokens.next() {
+            if token.is_keyword("PREFIX") {
+                let name = tokens.next().and_then(|token| token.prefix());
+                let iri = tokens
+                    .next()
+                    .filter(|token| token.kind == TokenKind::Iri)
+                    .map(|token| &token.text[1..token.text.len() - 1]);
+                let (Some(name), Some(iri)) = (name, iri) else {
+                    return Err(Error::InvalidPrefixes(format!(
+                        "PREFIX at offset {} is not followed by a prefix and an IRI",
+                        token.offset
+                    )));
+                };
+                namespaces.push(Namespace::declare_from_str(name, iri)?);
+            } else if token.is_keyword("BASE") || token.is_keyword("VERSION") {
+                tokens.next();
+            } else {
+                break;
+            }
+        }
+        Self::builder().declare_namespaces(&namespaces).build()
+    }
+
+    /// The prefixes as Turtle `@prefix` declarations, sorted by prefix.
+    pub fn to_turtle(&self) -> String {
+        self.iter()
+            .map(|namespace| format!("{}\n", namespace.as_turtle_prefix()))
+            .collect()
+    }
+
+    /// The prefixes as SPARQL `PREFIX` declarations, sorted by prefix.
+    pub fn to_sparql(&self) -> String {
+        self.iter()
+            .map(|namespace| format!("{}\n", namespace.as_sparql_prefix()))
+            .collect()
+    }
+
+    /// The prefixes as JSON-LD document with just a `@context`.
+    pub fn to_json_ld_context(&self) -> Value {
+        let context = self
+            .iter()
+            .map(|namespace| {
+                (
+                    namespace.name.trim_end_matches(':').to_string(),
+                    Value::String(namespace.iri.to_string()),
+                )
+            })
+            .collect::<Map<_, _>>();
+        serde_json::json!({ "@context": context })
+    }
+
+    /// Read the prefixes from the given file, in the format that matches its
+    /// extension: Turtle for `.ttl`, a JSON-LD context for `.json` and
+    /// `.jsonld`, a SPARQL prologue for `.sparql` and `.rq`, and otherwise a
+    /// prefix mapping (see [`Prefixes::from_prefix_mapping`]).
+    #[cfg(feature = "fs")]
+    pub fn load(path: &std::path::Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        match PrefixesFormat::of(path) {
+            PrefixesFormat::Turtle => Self::from_turtle(content.as_str()),
+            PrefixesFormat::JsonLd => Self::from_json_ld_context(content.as_str()),
+            PrefixesFormat::Sparql => Self::from_sparql(content.as_str()),
+            PrefixesFormat::Mapping => Self::from_prefix_mapping(content.as_str()),
+        }
+    }
+
+    /// Write the prefixes to the given file, in the format that matches its
+    /// extension (see [`Prefixes::load`]).
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: &std::path::Path) -> Result<(), Error> {
+        let content = match PrefixesFormat::of(path) {
+            PrefixesFormat::Turtle => self.to_turtle(),
+            PrefixesFormat::JsonLd => serde_json::to_string_pretty(&self.to_json_ld_context())?,
+            PrefixesFormat::Sparql => self.to_sparql(),
+            PrefixesFormat::Mapping => {
+                self.iter()
+                    .map(|namespace| format!("{} {}\n", namespace.name, namespace.iri))
+                    .collect()
+            },
+        };
+        Ok(std::fs::write(path, content)?)
+    }
+}
+
+/// Whether the given expanded JSON-LD term definition can be used as a
+/// prefix.
+fn is_prefix_definition(definition: &Map<String, Value>) -> bool {
+    match definition.get("@prefix") {
+        Some(prefix) => prefix.as_bool() == Some(true),
+        None => {
+            definition
+                .get("@id")
+                .and_then(Value::as_str)
+                .is_some_and(|iri| iri.ends_with(GEN_DELIMS))
+        },
+    }
+}
+
+#[cfg(feature = "fs")]
+enum PrefixesFormat {
+    Turtle,
+    JsonLd,
+    Sparql,
+    Mapping,
+}
+
+#[cfg(feature = "fs")]
+impl PrefixesFormat {
+    fn of(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ttl") => Self::Turtle,
+            Some("json" | "jsonld") => Self::JsonLd,
+            Some("sparql" | "rq") => Self::Sparql,
+            _ => Self::Mapping,
+        }
+    }
+}
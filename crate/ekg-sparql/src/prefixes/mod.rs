@@ -1,7 +1,12 @@
-pub use {builder::PrefixesBuilder, declare_result::PrefixesDeclareResult, this::Prefixes};
+pub use {
+    builder::PrefixesBuilder,
+    declare_result::{PrefixesDeclareOutcome, PrefixesDeclareResult},
+    this::Prefixes,
+};
 
 mod builder;
 mod declare_result;
+mod formats;
 #[cfg(feature = "_rdfox")]
 mod handle;
 mod tests;
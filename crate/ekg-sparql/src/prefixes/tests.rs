@@ -8,3 +8,305 @@ fn test_default_namespaces() -> Result<(), ekg_error::Error> {
     assert_eq!(prefixes.len(), 4);
     Ok(())
 }
+
+fn names(prefixes: &crate::Prefixes) -> Vec<String> {
+    prefixes
+        .to_sparql()
+        .lines()
+        .map(|line| line.split(' ').nth(1).unwrap_or_default().to_string())
+        .collect()
+}
+
+#[test_log::test]
+fn test_prefixes_from_turtle() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::from_turtle(indoc::indoc! {r#"
+        @prefix ex: <https://example.com/> .
+        PREFIX skos: <http://www.w3.org/2004/02/skos/core#>
+        @prefix : <https://example.com/default/> .
+
+        ex:Thing a skos:Concept ; skos:prefLabel "Thing" .
+    "#})?;
+    assert_eq!(names(&prefixes), vec![":", "ex:", "skos:"]);
+    assert_eq!(prefixes.to_turtle(), indoc::indoc! {r#"
+            @prefix : <https://example.com/default/> .
+            @prefix ex: <https://example.com/> .
+            @prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+        "#});
+    assert_eq!(
+        crate::Prefixes::from_turtle(prefixes.to_turtle().as_str())?,
+        prefixes
+    );
+    assert!(matches!(
+        crate::Prefixes::from_turtle("@prefix ex: <https://example.com/> .\nex:a ex:b"),
+        Err(ekg_error::Error::InvalidRDF(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_prefixes_from_json_ld_context() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::from_json_ld_context(indoc::indoc! {r#"
+        {
+            "@context": [
+                {
+                    "@vocab": "https://example.com/vocab/",
+                    "ex": "https://example.com/",
+                    "name": "http://schema.org/name",
+                    "schema": { "@id": "http://schema.org/", "@prefix": true }
+                },
+                { "dcterms": { "@id": "http://purl.org/dc/terms/" } }
+            ],
+            "name": "not part of the context"
+        }
+    "#})?;
+    assert_eq!(names(&prefixes), vec![
+        "dcterms:", "ex:", "schema:"
+    ]);
+    assert_eq!(
+        prefixes.to_json_ld_context(),
+        serde_json::json!({
+            "@context": {
+                "dcterms": "http://purl.org/dc/terms/",
+                "ex": "https://example.com/",
+                "schema": "http://schema.org/"
+            }
+        })
+    );
+    assert_eq!(
+        crate::Prefixes::from_json_ld_context(prefixes.to_json_ld_context().to_string().as_str())?,
+        prefixes
+    );
+    assert!(matches!(
+        crate::Prefixes::from_json_ld_context(r#"{ "@context": "https://example.com/context" }"#),
+        Err(ekg_error::Error::InvalidPrefixes(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_prefixes_from_prefix_mapping() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::from_prefix_mapping(indoc::indoc! {r#"
+        # From prefix.cc
+        foaf: http://xmlns.com/foaf/0.1/
+        dcat	http://www.w3.org/ns/dcat#
+
+        ex: <https://example.com/>
+    "#})?;
+    assert_eq!(names(&prefixes), vec!["dcat:", "ex:", "foaf:"]);
+    assert_eq!(prefixes.to_sparql(), indoc::indoc! {r#"
+            PREFIX dcat: <http://www.w3.org/ns/dcat#>
+            PREFIX ex: <https://example.com/>
+            PREFIX foaf: <http://xmlns.com/foaf/0.1/>
+        "#});
+    assert!(matches!(
+        crate::Prefixes::from_prefix_mapping("foaf\n"),
+        Err(ekg_error::Error::InvalidPrefixes(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_prefixes_from_sparql() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::from_sparql(indoc::indoc! {r#"
+        # ekg:name: things
+        BASE <https://example.com/base/>
+        PREFIX ex: <https://example.com/>
+        prefix foaf: <http://xmlns.com/foaf/0.1/> # a comment
+        PREFIX : <https://example.com/default#>
+        SELECT * WHERE { ?s ex:p ?o }
+        PREFIX ignored: <https://example.com/ignored/>
+    "#})?;
+    assert_eq!(names(&prefixes), vec![":", "ex:", "foaf:"]);
+    assert_eq!(
+        crate::Prefixes::from_sparql(prefixes.to_sparql().as_str())?,
+        prefixes
+    );
+    assert!(matches!(
+        crate::Prefixes::from_sparql("PREFIX ex: https://example.com/"),
+        Err(ekg_error::Error::InvalidPrefixes(_))
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+#[test_log::test]
+fn test_load_and_save_prefixes() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::builder().default_namespaces().build()?;
+    let directory = std::env::temp_dir().join(format!(
+        "ekg-sparql-prefixes-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&directory)?;
+    for file in [
+        "prefixes.ttl",
+        "context.json",
+        "context.jsonld",
+        "prefixes.sparql",
+        "prefixes.rq",
+        "prefixes.txt",
+    ] {
+        let path = directory.join(file);
+        prefixes.save(&path)?;
+        assert_eq!(crate::Prefixes::load(&path)?, prefixes, "{file}");
+    }
+    assert_eq!(
+        std::fs::read_to_string(directory.join("prefixes.rq"))?,
+        prefixes.to_sparql()
+    );
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test_log::test]
+fn test_compact_and_expand() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::builder()
+        .default_namespaces()
+        .declare(&ekg_metadata::Namespace::declare_from_str(
+            "ex:",
+            "https://example.com/",
+        )?)
+        .declare(&ekg_metadata::Namespace::declare_from_str(
+            "people:",
+            "https://example.com/people/",
+        )?)
+        .build()?;
+    let iri = |iri: &str| iri_string::types::IriReferenceString::try_from(iri).unwrap();
+
+    for (full, prefixed_name) in [
+        (
+            "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+            "rdf:type",
+        ),
+        ("https://example.com/people/alice", "people:alice"),
+        ("https://example.com/people", "ex:people"),
+        ("https://example.com/people/", "people:"),
+    ] {
+        assert_eq!(
+            prefixes.compact(&iri(full)).as_deref(),
+            Some(prefixed_name)
+        );
+        assert_eq!(prefixes.expand(prefixed_name)?, iri(full));
+    }
+    // Local names with a slash would need escaping
+    assert_eq!(
+        prefixes.compact(&iri("https://example.com/people/a/b")),
+        None
+    );
+    assert_eq!(
+        prefixes.compact(&iri("https://example.org/other")),
+        None
+    );
+    assert!(matches!(
+        prefixes.expand("unknown:thing"),
+        Err(ekg_error::Error::UnknownPrefix(_))
+    ));
+    assert!(matches!(
+        prefixes.expand("no-colon"),
+        Err(ekg_error::Error::UnknownPrefix(_))
+    ));
+
+    let term = ekg_metadata::Term::new_iri(&iri("https://example.com/people/alice"))?;
+    assert_eq!(
+        term.display_turtle_with(&prefixes).to_string(),
+        "people:alice"
+    );
+    assert_eq!(
+        term.display_turtle().to_string(),
+        "<https://example.com/people/alice>"
+    );
+    let term = ekg_metadata::Term::new_iri(&iri("https://example.org/other"))?;
+    assert_eq!(
+        term.display_turtle_with(&prefixes).to_string(),
+        "<https://example.org/other>"
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_prefixes_order_and_iterator() -> Result<(), ekg_error::Error> {
+    let ex = ekg_metadata::Namespace::declare_from_str("ex:", "https://example.com/")?;
+    let forward = crate::Prefixes::builder()
+        .default_namespaces()
+        .declare(&ex)
+        .build()?;
+    let backward = crate::Prefixes::builder()
+        .declare(&ex)
+        .declare(&ekg_identifier::NS_XSD)
+        .declare(&ekg_identifier::NS_RDFS)
+        .declare(&ekg_identifier::NS_RDF)
+        .declare(&ekg_identifier::NS_OWL)
+        .build()?;
+    assert_eq!(forward.to_string(), backward.to_string());
+    assert_eq!(
+        forward
+            .iter()
+            .map(|namespace| namespace.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["ex:", "owl:", "rdf:", "rdfs:", "xsd:"]
+    );
+    assert_eq!((&forward).into_iter().count(), 5);
+    assert_eq!(
+        forward.clone().into_iter().last(),
+        Some(ekg_identifier::NS_XSD.clone())
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_prefixes_conflicts() -> Result<(), ekg_error::Error> {
+    use crate::{PrefixesDeclareOutcome, PrefixesDeclareResult::*};
+
+    let mut prefixes = crate::Prefixes::empty()?;
+    let ex = ekg_metadata::Namespace::declare_from_str("ex:", "https://example.com/")?;
+    assert_eq!(
+        prefixes.declare_namespace(&ex)?,
+        PREFIXES_DECLARED_NEW
+    );
+    assert_eq!(
+        prefixes.declare_namespace(&ex)?,
+        PREFIXES_NO_CHANGE
+    );
+
+    let other = ekg_metadata::Namespace::declare_from_str("ex:", "https://example.org/")?;
+    let result = prefixes.declare_namespace(&other)?;
+    assert_eq!(
+        result,
+        PrefixesDeclareOutcome::ConflictingIri(ex.clone())
+    );
+    assert!(result.is_conflict());
+    assert_eq!(prefixes.get_namespace("ex:"), Some(&ex));
+
+    let alias = ekg_metadata::Namespace::declare_from_str("example:", "https://example.com/")?;
+    let result = prefixes.declare_namespace(&alias)?;
+    assert_eq!(
+        result,
+        PrefixesDeclareOutcome::DuplicateIri(ex.clone())
+    );
+    assert!(result.is_declared());
+    assert!(result.is_conflict());
+    assert_eq!(prefixes.len(), 2);
+
+    assert!(matches!(
+        crate::Prefixes::builder()
+            .declare(&ex)
+            .declare(&other)
+            .build(),
+        Err(ekg_error::Error::InvalidPrefixes(_))
+    ));
+    let prefixes = crate::Prefixes::builder()
+        .declare(&ex)
+        .declare(&ex)
+        .declare(&alias)
+        .build()?;
+    assert_eq!(prefixes.len(), 2);
+
+    assert!(matches!(
+        crate::Prefixes::try_from(vec![ex.clone(), other]),
+        Err(ekg_error::Error::InvalidPrefixes(_))
+    ));
+    assert_eq!(
+        crate::Prefixes::try_from(&vec![ex, alias])?,
+        prefixes
+    );
+    Ok(())
+}
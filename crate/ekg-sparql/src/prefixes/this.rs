@@ -7,18 +7,21 @@ use {
     std::{ffi::CString, ops::Deref},
 };
 use {
-    crate::prefixes::{PrefixesBuilder, PrefixesDeclareResult},
-    ekg_identifier::{Namespace, TBoxNamespaceIRI, NS_OWL, NS_RDF, NS_RDFS, NS_XSD},
-    ekg_metadata::{Class, Predicate},
+    crate::prefixes::{PrefixesBuilder, PrefixesDeclareOutcome, PrefixesDeclareResult},
+    ekg_identifier::{NS_OWL, NS_RDF, NS_RDFS, NS_XSD, Namespace, TBoxNamespaceIRI},
+    ekg_metadata::{Class, IriCompactor, Predicate},
     ekg_util::log::LOG_TARGET_DATABASE,
+    iri_string::types::{IriReferenceStr, IriReferenceString},
     std::{
-        collections::HashSet,
+        collections::BTreeMap,
         fmt::{Display, Formatter},
     },
 };
 
 /// A set of namespace prefixes, used to declare consts in SPARQL queries.
-/// The prefixes are stored in a vector of [`Namespace`] structs.
+/// The prefixes are stored as [`Namespace`] structs sorted by prefix, so that
+/// the `PREFIX` declarations of a statement are always written in the same
+/// order.
 /// The `_rdfox` feature uses the `rdfox` library to manage the prefixes and
 /// also uses the Rust vector to store the consts because RDFox does not
 /// have an API that allows the retrieval of all the prefixes from a given
@@ -27,7 +30,7 @@ use {
 pub struct Prefixes {
     #[cfg(feature = "_rdfox")]
     inner:    Arc<Mutex<CPrefixesHandle>>,
-    prefixes: HashSet<Namespace>,
+    prefixes: BTreeMap<String, Namespace>,
 }
 
 unsafe impl Sync for Prefixes {}
@@ -44,46 +47,46 @@ impl PartialEq for Prefixes {
     fn eq(&self, _other: &Self) -> bool { self.prefixes == _other.prefixes }
 }
 
-impl Iterator for Prefixes {
+impl IntoIterator for Prefixes {
+    type IntoIter = std::collections::btree_map::IntoValues<String, Namespace>;
     type Item = Namespace;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.prefixes
-            .iter()
-            .next()
-            .map(|namespace| namespace.clone())
-    }
+    fn into_iter(self) -> Self::IntoIter { self.prefixes.into_values() }
 }
 
-impl ExactSizeIterator for Prefixes {
-    fn len(&self) -> usize { self.prefixes.len() }
+impl<'a> IntoIterator for &'a Prefixes {
+    type IntoIter = std::collections::btree_map::Values<'a, String, Namespace>;
+    type Item = &'a Namespace;
+
+    fn into_iter(self) -> Self::IntoIter { self.prefixes.values() }
 }
 
 /// Show the consts in SPARQL format
 impl Display for Prefixes {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        for namespace in self.prefixes.iter() {
+        for namespace in self.iter() {
             writeln!(f, "{}", namespace.as_sparql_prefix())?;
         }
         Ok(())
     }
 }
 
-impl From<Vec<Namespace>> for Prefixes {
-    fn from(namespaces: Vec<Namespace>) -> Self {
-        Self::builder()
-            .declare_namespaces(&namespaces)
-            .build()
-            .unwrap()
+/// Declare the given namespaces, failing with
+/// [`ekg_error::Error::InvalidPrefixes`] if a prefix is declared for two
+/// different IRIs.
+impl TryFrom<Vec<Namespace>> for Prefixes {
+    type Error = ekg_error::Error;
+
+    fn try_from(namespaces: Vec<Namespace>) -> Result<Self, Self::Error> {
+        Self::builder().declare_namespaces(&namespaces).build()
     }
 }
 
-impl From<&Vec<Namespace>> for Prefixes {
-    fn from(namespaces: &Vec<Namespace>) -> Self {
-        Self::builder()
-            .declare_namespaces(namespaces)
-            .build()
-            .unwrap()
+impl TryFrom<&Vec<Namespace>> for Prefixes {
+    type Error = ekg_error::Error;
+
+    fn try_from(namespaces: &Vec<Namespace>) -> Result<Self, Self::Error> {
+        Self::builder().declare_namespaces(namespaces).build()
     }
 }
 
@@ -95,7 +98,7 @@ impl Prefixes {
         let mut prefixes = Self {
             #[cfg(feature = "_rdfox")]
             inner:                            Arc::new(Mutex::new(CPrefixesHandle::new().unwrap())),
-            prefixes:                         HashSet::new(),
+            prefixes:                         BTreeMap::new(),
         };
 
         #[cfg(feature = "_rdfox")]
@@ -133,54 +136,88 @@ impl Prefixes {
     pub fn declare_namespace(
         &mut self,
         namespace: &Namespace,
-    ) -> Result<PrefixesDeclareResult, ekg_error::Error> {
+    ) -> Result<PrefixesDeclareOutcome, ekg_error::Error> {
         self._declare_namespace(namespace)
     }
 
+    /// Declare the given namespace, unless its prefix is already declared
+    /// for another IRI, which is reported as
+    /// [`PrefixesDeclareOutcome::ConflictingIri`]. An IRI that is
+    /// already declared under another prefix is declared anyway but reported
+    /// as [`PrefixesDeclareOutcome::DuplicateIri`].
     fn _declare_namespace(
         &mut self,
         namespace: &Namespace,
-    ) -> Result<PrefixesDeclareResult, ekg_error::Error> {
-        if !self.prefixes.insert(namespace.clone()) {
-            tracing::trace!("Declaring PREFIX {namespace} (already declared)");
-            return Ok(PrefixesDeclareResult::PREFIXES_NO_CHANGE);
+    ) -> Result<PrefixesDeclareOutcome, ekg_error::Error> {
+        let result = self.classify_namespace(namespace);
+        if result.is_declared() {
+            self.prefixes
+                .insert(namespace.name.clone(), namespace.clone());
+        }
+        Ok(result)
+    }
+
+    /// What declaring the given namespace would do, without declaring it.
+    fn classify_namespace(&self, namespace: &Namespace) -> PrefixesDeclareOutcome {
+        if let Some(existing) = self.prefixes.get(namespace.name.as_str()) {
+            if existing.iri.as_str() == namespace.iri.as_str() {
+                tracing::trace!("Declaring PREFIX {namespace} (already declared)");
+                return PrefixesDeclareResult::PREFIXES_NO_CHANGE.into();
+            }
+            tracing::warn!(
+                target: LOG_TARGET_DATABASE,
+                "Cannot declare PREFIX {namespace}, already declared as {existing}"
+            );
+            return PrefixesDeclareOutcome::ConflictingIri(existing.clone());
+        }
+        if let Some(existing) = self
+            .iter()
+            .find(|existing| existing.iri.as_str() == namespace.iri.as_str())
+        {
+            tracing::warn!(
+                target: LOG_TARGET_DATABASE,
+                "Declaring PREFIX {namespace}, its IRI is also declared as {existing}"
+            );
+            return PrefixesDeclareOutcome::DuplicateIri(existing.clone());
         }
         tracing::trace!(target: LOG_TARGET_DATABASE, "Declaring PREFIX {namespace}");
-        Ok(PrefixesDeclareResult::PREFIXES_DECLARED_NEW)
+        PrefixesDeclareResult::PREFIXES_DECLARED_NEW.into()
     }
 
+    /// Declare the given namespace in RDFox first and then in this set of
+    /// prefixes, so that both stay in sync when RDFox rejects it. Returns the
+    /// same results as without RDFox, such as
+    /// [`PrefixesDeclareOutcome::DuplicateIri`].
     #[cfg(feature = "_rdfox")]
     pub fn declare_namespace(
         &mut self,
         namespace: &Namespace,
-    ) -> Result<PrefixesDeclareResult, ekg_error::Error> {
-        let result = self._declare_namespace(namespace)?;
-        if result != PrefixesDeclareResult::PREFIXES_DECLARED_NEW {
+    ) -> Result<PrefixesDeclareOutcome, ekg_error::Error> {
+        let result = self.classify_namespace(namespace);
+        if !result.is_declared() {
             return Ok(result);
         }
-        let c_name = CString::new(namespace.name.as_str()).unwrap();
-        let c_iri = CString::new(namespace.iri.as_str()).unwrap();
-        let mut result = rdfox_sys::CPrefixes_DeclareResult::PREFIXES_NO_CHANGE;
-        let mut c_prefixes = self.inner.lock().unwrap(); // TODO: error handling
-        rdfox_sys::database_call!(
-            format!(
-                "Registering PREFIX {} <{}>",
-                namespace.name.as_str(),
-                namespace.iri.as_str(),
-            )
-            .as_str(),
-            rdfox_sys::CPrefixes_declarePrefix(
-                c_prefixes.cast_mut(),
-                c_name.as_ptr(),
-                c_iri.as_ptr(),
-                &mut result
-            )
-        )?;
-        assert!(
-            self.get_namespace(&namespace.name).is_some(),
-            "Namespace not found"
-        );
-        match result {
+        let c_name = CString::new(namespace.name.as_str())?;
+        let c_iri = CString::new(namespace.iri.as_str())?;
+        let mut c_result = rdfox_sys::CPrefixes_DeclareResult::PREFIXES_NO_CHANGE;
+        {
+            let mut c_prefixes = self.inner.lock().unwrap(); // TODO: error handling
+            rdfox_sys::database_call!(
+                format!(
+                    "Registering PREFIX {} <{}>",
+                    namespace.name.as_str(),
+                    namespace.iri.as_str(),
+                )
+                .as_str(),
+                rdfox_sys::CPrefixes_declarePrefix(
+                    c_prefixes.cast_mut(),
+                    c_name.as_ptr(),
+                    c_iri.as_ptr(),
+                    &mut c_result
+                )
+            )?;
+        }
+        match c_result {
             rdfox_sys::CPrefixes_DeclareResult::PREFIXES_INVALID_PREFIX_NAME => {
                 tracing::error!(
                     target: LOG_TARGET_DATABASE,
@@ -188,7 +225,7 @@ impl Prefixes {
                     namespace.name.as_str(),
                     namespace.iri.as_str()
                 );
-                Err(ekg_error::Error::InvalidPrefixName)
+                return Err(ekg_error::Error::InvalidPrefixName);
             },
             rdfox_sys::CPrefixes_DeclareResult::PREFIXES_DECLARED_NEW => {
                 tracing::trace!(
@@ -197,31 +234,25 @@ impl Prefixes {
                     namespace.name.as_str(),
                     namespace.iri.as_str()
                 );
-                Ok(result.into())
-            },
-            rdfox_sys::CPrefixes_DeclareResult::PREFIXES_NO_CHANGE => {
-                tracing::error!(
-                    target: LOG_TARGET_DATABASE,
-                    "Registered {namespace} twice"
-                );
-                Ok(result.into())
             },
             _ => {
-                tracing::error!(
+                tracing::warn!(
                     target: LOG_TARGET_DATABASE,
                     "Result of registering prefix {namespace} is {:?}",
-                    result
+                    c_result
                 );
-                Ok(result.into())
             },
         }
+        self.prefixes
+            .insert(namespace.name.clone(), namespace.clone());
+        Ok(result)
     }
 
     pub fn declare(
         &mut self,
         name: &str,
         iri: TBoxNamespaceIRI,
-    ) -> Result<PrefixesDeclareResult, ekg_error::Error> {
+    ) -> Result<PrefixesDeclareOutcome, ekg_error::Error> {
         self.declare_namespace(&Namespace::declare(name, iri)?)
     }
 
@@ -242,13 +273,67 @@ impl Prefixes {
         &self,
         mut f: F,
     ) -> Result<(), E> {
-        for namespace in self.prefixes.iter() {
+        for namespace in self.iter() {
             f(namespace.name.as_str(), namespace)?;
         }
         Ok(())
     }
 
-    pub fn get_namespace(&self, prefix: &str) -> Option<&Namespace> {
-        self.prefixes.iter().find(|ns| ns.name == prefix)
+    /// The namespaces, sorted by prefix.
+    pub fn iter(&self) -> impl Iterator<Item = &Namespace> { self.prefixes.values() }
+
+    pub fn len(&self) -> usize { self.prefixes.len() }
+
+    pub fn is_empty(&self) -> bool { self.prefixes.is_empty() }
+
+    pub fn get_namespace(&self, prefix: &str) -> Option<&Namespace> { self.prefixes.get(prefix) }
+
+    /// The given IRI as prefixed name (a CURIE such as `story:Story`), using
+    /// the longest matching namespace. Returns `None` if the IRI is not in
+    /// any of the namespaces or if its local name would need escaping.
+    pub fn compact(&self, iri: &IriReferenceStr) -> Option<String> {
+        let iri = iri.as_str();
+        self.iter()
+            .filter_map(|namespace| {
+                let local_name = iri.strip_prefix(namespace.iri.as_str())?;
+                is_simple_local_name(local_name).then_some((namespace, local_name))
+            })
+            .max_by(|(a, _), (b, _)| {
+                a.iri
+                    .as_str()
+                    .len()
+                    .cmp(&b.iri.as_str().len())
+                    .then_with(|| b.name.cmp(&a.name))
+            })
+            .map(|(namespace, local_name)| format!("{}{local_name}", namespace.name))
     }
+
+    /// The full IRI of the given prefixed name (a CURIE such as
+    /// `story:Story`).
+    pub fn expand(&self, prefixed_name: &str) -> Result<IriReferenceString, ekg_error::Error> {
+        let unknown = || ekg_error::Error::UnknownPrefix(prefixed_name.to_string());
+        let colon = prefixed_name.find(':').ok_or_else(unknown)?;
+        let (prefix, local_name) = prefixed_name.split_at(colon + 1);
+        let namespace = self.get_namespace(prefix).ok_or_else(unknown)?;
+        Ok(IriReferenceString::try_from(format!(
+            "{}{local_name}",
+            namespace.iri
+        ))?)
+    }
+}
+
+impl IriCompactor for Prefixes {
+    fn compact_iri(&self, iri: &str) -> Option<String> {
+        self.compact(IriReferenceStr::new(iri).ok()?)
+    }
+}
+
+/// Whether the given local name can be used in a prefixed name as it is,
+/// without escaping.
+fn is_simple_local_name(local_name: &str) -> bool {
+    local_name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.') &&
+        !local_name.starts_with(['-', '.']) &&
+        !local_name.ends_with('.')
 }
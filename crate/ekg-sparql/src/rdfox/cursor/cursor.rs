@@ -90,18 +90,38 @@ impl Cursor {
     conn = % self.connection.number,
     )
     )]
-    pub fn consume<T, E>(
+    pub fn consume<T, E>(&mut self, tx: &Arc<Transaction>, max_row: usize, f: T) -> Result<usize, E>
+    where
+        T: FnMut(&CursorRow) -> Result<(), E>,
+        E: From<ekg_error::Error> + Debug,
+    {
+        self.consume_opened(tx, max_row, |_opened| Ok(()), f)
+    }
+
+    /// Variation of [`Cursor::consume`] that calls `on_open` with the opened
+    /// cursor before the first row, also when there are no rows, for
+    /// instance to get the names of the answer variables.
+    pub fn consume_opened<O, T, E>(
         &mut self,
         tx: &Arc<Transaction>,
         max_row: usize,
+        on_open: O,
         mut f: T,
     ) -> Result<usize, E>
     where
+        O: FnOnce(&OpenedCursor) -> Result<(), E>,
         T: FnMut(&CursorRow) -> Result<(), E>,
         E: From<ekg_error::Error> + Debug,
     {
         let sparql_str = self.statement.text.clone();
+        // The `ekg:max-rows` directive of the statement can only lower the maximum
+        let max_row = self
+            .statement
+            .directives
+            .max_rows
+            .map_or(max_row, |directive| directive.min(max_row));
         let (mut opened_cursor, mut multiplicity) = OpenedCursor::new(self, tx.clone())?;
+        on_open(&opened_cursor)?;
         let mut rowid = 0_usize;
         let mut count = 0_usize;
         while multiplicity > 0_usize {
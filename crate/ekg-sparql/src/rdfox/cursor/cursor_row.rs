@@ -1,7 +1,7 @@
 #![cfg(feature = "_rdfox")]
 
 use {
-    crate::rdfox::OpenedCursor,
+    crate::{Solution, rdfox::OpenedCursor},
     ekg_metadata::{DataType, Literal},
     ekg_util::log::LOG_TARGET_DATABASE,
     tracing::event_enabled,
@@ -92,4 +92,15 @@ impl<'a> CursorRow<'a> {
         }
         self.lexical_value_with_id(term_index)
     }
+
+    /// Get all values of the current row as a [`Solution`] with the
+    /// multiplicity of the row.
+    pub fn solution(&self) -> Result<Solution, ekg_error::Error> {
+        Ok(Solution::new(
+            (0..self.opened.arity)
+                .map(|term_index| self.lexical_value(term_index))
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+        .with_multiplicity(*self.multiplicity))
+    }
 }
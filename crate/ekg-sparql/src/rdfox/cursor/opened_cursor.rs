@@ -92,4 +92,11 @@ impl<'a> OpenedCursor<'a> {
         let c_name = unsafe { std::ffi::CStr::from_ptr(c_buf) };
         Ok(c_name.to_str().unwrap().to_owned())
     }
+
+    /// The names of the variables of all columns in the output, in order.
+    pub fn answer_variable_names(&self) -> Result<Vec<String>, ekg_error::Error> {
+        (0..self.arity)
+            .map(|index| self.get_answer_variable_name(index))
+            .collect()
+    }
 }
@@ -4,7 +4,7 @@
 extern crate core;
 
 pub use {
-    class_report::ClassReport,
+    crate::ClassReport,
     connectable_data_store::ConnectableDataStore,
     cursor::{Cursor, CursorRow, OpenedCursor},
     data_store::DataStore,
@@ -19,7 +19,6 @@ pub use {
     transaction::Transaction,
 };
 
-mod class_report;
 mod connectable_data_store;
 mod cursor;
 mod data_store;
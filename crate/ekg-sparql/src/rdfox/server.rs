@@ -55,10 +55,13 @@ impl Server {
                 )?;
             }
             #[cfg(not(feature = "rdfox-7-0a"))]
-            rdfox_sys::database_call!(
-                "Starting a local RDFFox server",
-                rdfox_sys::CServer_startLocalServer(params.inner.cast_const())
-            )?;
+            {
+                let c_params = params.inner.lock().unwrap(); // TODO: handle exception
+                rdfox_sys::database_call!(
+                    "Starting a local RDFFox server",
+                    rdfox_sys::CServer_startLocalServer(c_params.cast_const())
+                )?;
+            }
         } else {
             let params = Parameters::empty()?;
             #[cfg(feature = "rdfox-7-0a")]
@@ -74,10 +77,13 @@ impl Server {
                 )?;
             }
             #[cfg(not(feature = "rdfox-7-0a"))]
-            rdfox_sys::database_call!(
-                "Starting a local RDFFox server with default parameters",
-                rdfox_sys::CServer_startLocalServer(params.inner.cast_const())
-            )?;
+            {
+                let c_params = params.inner.lock().unwrap(); // TODO: handle exception
+                rdfox_sys::database_call!(
+                    "Starting a local RDFFox server with default parameters",
+                    rdfox_sys::CServer_startLocalServer(c_params.cast_const())
+                )?;
+            }
         };
         let server = Server {
             default_role_creds: role_creds,
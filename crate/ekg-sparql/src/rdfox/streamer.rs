@@ -123,7 +123,7 @@ impl<'a, W: 'a + Write> Streamer<'a, W> {
 
     unsafe fn context_as_ref_to_self(context: *mut c_void) -> &'a mut RefToSelf<'a, W> {
         let ref_to_self = context as *mut RefToSelf<'a, W>;
-        &mut *ref_to_self
+        unsafe { &mut *ref_to_self }
     }
 
     extern "C" fn flush_function(context: *mut c_void) -> bool {
@@ -0,0 +1,116 @@
+//! Decoder for the `application/sparql-results+json` format.
+//!
+//! See <https://www.w3.org/TR/sparql11-results-json/>
+use {
+    crate::solution::{Solution, SolutionSet, json_decoder::SparqlResultsJsonDecoder},
+    ekg_error::Error,
+    ekg_metadata::{DataType, Literal},
+    serde_json::Value,
+    std::sync::Arc,
+};
+
+impl SolutionSet {
+    /// Decode the given `application/sparql-results+json` document.
+    pub fn from_sparql_results_json(json: &[u8]) -> Result<Self, Error> {
+        let mut decoder = SparqlResultsJsonDecoder::new();
+        decoder.push(json);
+        let mut solutions = Vec::new();
+        while let Some(solution) = decoder.next_solution()? {
+            solutions.push(solution);
+        }
+        decoder.finish()?;
+        let variables = decoder.variables().unwrap_or_default().to_vec();
+        Ok(SolutionSet::new(variables, solutions))
+    }
+}
+
+/// Decode the `head.vars` array of a SPARQL results document.
+pub(super) fn variables_from_head(head: &Value) -> Result<Vec<String>, Error> {
+    head["vars"]
+        .as_array()
+        .ok_or_else(|| invalid("missing head.vars array"))?
+        .iter()
+        .map(|var| {
+            var.as_str()
+                .map(|var| var.to_string())
+                .ok_or_else(|| invalid("head.vars contains a non-string value"))
+        })
+        .collect()
+}
+
+/// Decode one element of the `results.bindings` array into a [`Solution`]
+/// with its values in the same order as the given variables.
+pub(super) fn solution_from_json(
+    variables: &Arc<[String]>,
+    binding: &Value,
+) -> Result<Solution, Error> {
+    let binding = binding
+        .as_object()
+        .ok_or_else(|| invalid("a binding is not a JSON object"))?;
+    let values = variables
+        .iter()
+        .map(|variable| {
+            binding
+                .get(variable)
+                .map(literal_from_json_binding)
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Solution::new(values).with_variables(variables.clone()))
+}
+
+/// Decode the response of an ASK query.
+pub(crate) fn boolean_from_sparql_results_json(json: &[u8]) -> Result<bool, Error> {
+    serde_json::from_slice::<Value>(json)?["boolean"]
+        .as_bool()
+        .ok_or_else(|| invalid("missing boolean"))
+}
+
+/// Decode an RDF term in its JSON representation, for instance:
+///
+/// ```json
+/// { "type": "literal", "value": "42", "datatype": "http://www.w3.org/2001/XMLSchema#integer" }
+/// ```
+fn literal_from_json_binding(term: &Value) -> Result<Literal, Error> {
+    let value = term["value"]
+        .as_str()
+        .ok_or_else(|| invalid("an RDF term has no value"))?;
+    match term["type"].as_str() {
+        Some("uri") => {
+            Literal::from_iri(&iri_string::types::IriReferenceString::try_from(
+                value,
+            )?)
+        },
+        Some("bnode") => Literal::new_blank_node_with_datatype(value, DataType::BlankNode),
+        // "typed-literal" is not in the spec but still returned by some older endpoints
+        Some("literal") | Some("typed-literal") => {
+            match term["datatype"].as_str() {
+                Some(datatype) => typed_literal(value, datatype),
+                None => {
+                    match term["xml:lang"].as_str() {
+                        Some(language) => Literal::new_language_tagged_string(value, language),
+                        None => Literal::new_plain_literal_string(value),
+                    }
+                },
+            }
+        },
+        Some(other) => {
+            Err(invalid(
+                format!("unknown RDF term type {other}").as_str(),
+            ))
+        },
+        None => Err(invalid("an RDF term has no type")),
+    }
+}
+
+/// Create a literal of the given datatype, which fails with
+/// [`Error::UnknownDataType`] for datatypes that [`Literal`] does not support.
+fn typed_literal(value: &str, datatype: &str) -> Result<Literal, Error> {
+    let datatype = oxrdf::NamedNodeRef::new(datatype)
+        .map_err(|_| invalid(format!("invalid datatype <{datatype}>").as_str()))?;
+    Literal::try_from(oxrdf::LiteralRef::new_typed_literal(
+        value, datatype,
+    ))
+}
+
+pub(super) fn invalid(msg: &str) -> Error { Error::InvalidSPARQLResults(msg.to_string()) }
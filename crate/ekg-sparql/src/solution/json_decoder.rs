@@ -0,0 +1,284 @@
+//! Incremental decoder for the `application/sparql-results+json` format.
+//!
+//! The decoder is fed with the chunks of a response body as they arrive and
+//! hands out every row of `results.bindings` as soon as it is complete, so
+//! that only the current (partial) row has to be kept in memory rather than
+//! the whole document. Rows that precede the `head` are kept until it
+//! arrives, see [`SparqlResultsJsonDecoder::pending`].
+use {
+    crate::solution::{
+        Solution,
+        json::{invalid, solution_from_json, variables_from_head},
+    },
+    ekg_error::Error,
+    serde_json::Value,
+    std::{collections::VecDeque, sync::Arc},
+};
+
+/// Where the decoder currently is in the structure of the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Before the opening `{` of the document
+    Start,
+    /// Inside the top-level object
+    Document,
+    /// Inside the `results` object
+    Results,
+    /// Inside the `results.bindings` array
+    Bindings,
+    /// After the closing `}` of the document
+    End,
+}
+
+/// The outcome of one step of the decoder.
+enum Step {
+    Continue,
+    NeedMoreInput,
+}
+
+#[derive(Debug)]
+pub(crate) struct SparqlResultsJsonDecoder {
+    buffer:      Vec<u8>,
+    position:    usize,
+    phase:       Phase,
+    variables:   Option<Arc<[String]>>,
+    /// Whether the `results.bindings` array has been seen
+    bindings:    bool,
+    /// Bindings that have been decoded before the `head` was seen
+    pending:     VecDeque<Value>,
+    /// Stop decoding once more than this many bindings are pending
+    max_pending: Option<usize>,
+}
+
+impl Default for SparqlResultsJsonDecoder {
+    fn default() -> Self { Self::new() }
+}
+
+impl SparqlResultsJsonDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            buffer:      Vec::new(),
+            position:    0,
+            phase:       Phase::Start,
+            variables:   None,
+            bindings:    false,
+            pending:     VecDeque::new(),
+            max_pending: None,
+        }
+    }
+
+    /// Stop decoding bindings that precede the `head` once more than the
+    /// given number of them are [`pending`](Self::pending), so that the
+    /// caller can enforce its row limit before the whole response has been
+    /// buffered.
+    pub(crate) fn with_max_pending(mut self, max_pending: Option<usize>) -> Self {
+        self.max_pending = max_pending;
+        self
+    }
+
+    /// The variables from `head.vars`, once they have been decoded.
+    pub(crate) fn variables(&self) -> Option<&[String]> { self.variables.as_deref() }
+
+    /// The number of rows that have been decoded but cannot be handed out
+    /// yet because the `head` has not been seen, callers should count them
+    /// against their row limit.
+    pub(crate) fn pending(&self) -> usize {
+        if self.variables.is_some() {
+            0
+        } else {
+            self.pending.len()
+        }
+    }
+
+    /// Append the next chunk of the document.
+    pub(crate) fn push(&mut self, chunk: &[u8]) { self.buffer.extend_from_slice(chunk); }
+
+    /// Return the next complete solution, or `None` if more input is needed.
+    pub(crate) fn next_solution(&mut self) -> Result<Option<Solution>, Error> {
+        loop {
+            if let Some(variables) = &self.variables &&
+                let Some(binding) = self.pending.pop_front()
+            {
+                return solution_from_json(variables, &binding).map(Some);
+            }
+            match self.step()? {
+                Step::Continue => continue,
+                Step::NeedMoreInput => {
+                    self.buffer.drain(..self.position);
+                    self.position = 0;
+                    return Ok(None);
+                },
+            }
+        }
+    }
+
+    /// Check that the whole document has been decoded, call this after the
+    /// last chunk has been pushed and all solutions have been taken.
+    pub(crate) fn finish(&self) -> Result<(), Error> {
+        if self.phase != Phase::End {
+            return Err(invalid("unexpected end of document"));
+        }
+        if self.variables.is_none() {
+            return Err(invalid("missing head.vars array"));
+        }
+        if !self.bindings {
+            return Err(invalid("missing results.bindings array"));
+        }
+        if !self.pending.is_empty() {
+            return Err(invalid("not all solutions have been taken"));
+        }
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<Step, Error> {
+        if let Some(max_pending) = self.max_pending &&
+            self.pending() > max_pending
+        {
+            return Ok(Step::NeedMoreInput);
+        }
+        let Some(position) = skip_whitespace(&self.buffer, self.position) else {
+            return Ok(Step::NeedMoreInput);
+        };
+        self.position = position;
+        let byte = self.buffer[position];
+        match (self.phase, byte) {
+            (Phase::Start, b'{') => self.advance(position + 1, Phase::Document),
+            (Phase::Document | Phase::Results | Phase::Bindings, b',') => {
+                self.advance(position + 1, self.phase)
+            },
+            (Phase::Document, b'}') => self.advance(position + 1, Phase::End),
+            (Phase::Results, b'}') => self.advance(position + 1, Phase::Document),
+            (Phase::Bindings, b']') => self.advance(position + 1, Phase::Results),
+            (Phase::Document | Phase::Results, b'"') => self.member(position),
+            (Phase::Bindings, b'{') => {
+                let Some(end) = value_end(&self.buffer, position) else {
+                    return Ok(Step::NeedMoreInput);
+                };
+                self.pending.push_back(serde_json::from_slice(
+                    &self.buffer[position..end],
+                )?);
+                self.advance(end, Phase::Bindings)
+            },
+            // Whatever follows the document is ignored
+            (Phase::End, _) => {
+                self.position = self.buffer.len();
+                Ok(Step::NeedMoreInput)
+            },
+            (_, byte) => {
+                Err(invalid(
+                    format!("unexpected character '{}'", byte as char).as_str(),
+                ))
+            },
+        }
+    }
+
+    /// Decode the member of an object (the document itself or `results`)
+    /// whose key starts at the given position.
+    fn member(&mut self, position: usize) -> Result<Step, Error> {
+        let Some((key, value_start)) = read_key(&self.buffer, position)? else {
+            return Ok(Step::NeedMoreInput);
+        };
+        match (self.phase, key.as_str()) {
+            (Phase::Document, "results") => self.open(value_start, b'{', Phase::Results),
+            (Phase::Results, "bindings") => {
+                self.bindings = true;
+                self.open(value_start, b'[', Phase::Bindings)
+            },
+            (phase, key) => {
+                let Some(end) = value_end(&self.buffer, value_start) else {
+                    return Ok(Step::NeedMoreInput);
+                };
+                if phase == Phase::Document && key == "head" {
+                    let head = serde_json::from_slice::<Value>(&self.buffer[value_start..end])?;
+                    self.variables = Some(variables_from_head(&head)?.into());
+                }
+                self.advance(end, phase)
+            },
+        }
+    }
+
+    /// Enter the object or array that should start at the given position.
+    fn open(&mut self, position: usize, expected: u8, phase: Phase) -> Result<Step, Error> {
+        if self.buffer[position] != expected {
+            return Err(invalid(
+                format!("expected '{}'", expected as char).as_str(),
+            ));
+        }
+        self.advance(position + 1, phase)
+    }
+
+    fn advance(&mut self, position: usize, phase: Phase) -> Result<Step, Error> {
+        self.position = position;
+        self.phase = phase;
+        Ok(Step::Continue)
+    }
+}
+
+/// Return the position of the first non-whitespace byte at or after the
+/// given position, or `None` if there is none (yet).
+fn skip_whitespace(buffer: &[u8], position: usize) -> Option<usize> {
+    buffer[position..]
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .map(|offset| position + offset)
+}
+
+/// Read the key of an object member that starts at the given position,
+/// returning the key and the position of the start of its value.
+fn read_key(buffer: &[u8], position: usize) -> Result<Option<(String, usize)>, Error> {
+    let Some(end) = value_end(buffer, position) else {
+        return Ok(None);
+    };
+    let key = serde_json::from_slice::<String>(&buffer[position..end])?;
+    let Some(colon) = skip_whitespace(buffer, end) else {
+        return Ok(None);
+    };
+    if buffer[colon] != b':' {
+        return Err(invalid(
+            format!("expected ':' after \"{key}\"").as_str(),
+        ));
+    }
+    Ok(skip_whitespace(buffer, colon + 1).map(|value_start| (key, value_start)))
+}
+
+/// Find the end (exclusive) of the JSON value that starts at the given
+/// position, or `None` if the value is not complete yet.
+///
+/// This only keeps track of nesting and strings, the value itself is
+/// validated by `serde_json` once it is complete.
+fn value_end(buffer: &[u8], position: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, byte) in buffer[position..].iter().enumerate() {
+        let index = position + offset;
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if *byte == b'\\' {
+                escaped = true;
+            } else if *byte == b'"' {
+                in_string = false;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return Some(index),
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            },
+            b',' if depth == 0 => return Some(index),
+            byte if depth == 0 && byte.is_ascii_whitespace() => return Some(index),
+            _ => {},
+        }
+    }
+    None
+}
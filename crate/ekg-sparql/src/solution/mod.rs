@@ -0,0 +1,13 @@
+//! The solutions of a SPARQL SELECT query, i.e. the variable names and the
+//! rows of bindings returned by a SPARQL endpoint.
+pub use {solution_set::SolutionSet, this::Solution};
+
+mod json;
+mod json_decoder;
+mod serialize;
+mod solution_set;
+#[cfg(test)]
+mod tests;
+mod this;
+
+pub(crate) use {json::boolean_from_sparql_results_json, json_decoder::SparqlResultsJsonDecoder};
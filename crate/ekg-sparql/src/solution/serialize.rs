@@ -0,0 +1,248 @@
+//! Serialisation of a [`SolutionSet`] in the SPARQL query results formats:
+//!
+//! - JSON, see <https://www.w3.org/TR/sparql11-results-json/>
+//! - XML, see <https://www.w3.org/TR/rdf-sparql-XMLres/>
+//! - CSV and TSV, see <https://www.w3.org/TR/sparql11-results-csv-tsv/>
+//!
+//! The formats have no notion of multiplicity so a solution is written as
+//! often as its multiplicity.
+use {
+    crate::solution::{Solution, SolutionSet},
+    ekg_error::Error,
+    ekg_metadata::{
+        APPLICATION_SPARQL_RESULTS_JSON,
+        APPLICATION_SPARQL_RESULTS_XML,
+        Literal,
+        TEXT_CSV,
+        TEXT_TSV,
+    },
+    mime::Mime,
+    serde_json::{Map, Value},
+    std::io::Write,
+};
+
+impl SolutionSet {
+    /// Write the solutions to the given writer in the SPARQL results format
+    /// of the given MIME type: `application/sparql-results+json`,
+    /// `application/sparql-results+xml`, `text/csv` or
+    /// `text/tab-separated-values`.
+    pub fn write<W: Write>(&self, writer: W, mime_type: &Mime) -> Result<(), Error> {
+        let mime_type = mime_type.essence_str();
+        if mime_type == APPLICATION_SPARQL_RESULTS_JSON.essence_str() {
+            self.write_json(writer)
+        } else if mime_type == APPLICATION_SPARQL_RESULTS_XML.essence_str() {
+            self.write_xml(writer)
+        } else if mime_type == TEXT_CSV.essence_str() {
+            self.write_csv(writer)
+        } else if mime_type == TEXT_TSV.essence_str() {
+            self.write_tsv(writer)
+        } else {
+            Err(Error::InvalidSPARQLResults(format!(
+                "unsupported content type {mime_type}"
+            )))
+        }
+    }
+
+    /// The solutions, each repeated as often as its multiplicity
+    fn answers(&self) -> impl Iterator<Item = &Solution> {
+        self.iter()
+            .flat_map(|solution| std::iter::repeat_n(solution, solution.multiplicity()))
+    }
+
+    fn write_json<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let bindings = self
+            .answers()
+            .map(|solution| {
+                let mut binding = Map::new();
+                for (variable, value) in self.variables().iter().zip(solution.values()) {
+                    if let Some(value) = value {
+                        binding.insert(variable.clone(), json_term(value)?);
+                    }
+                }
+                Ok(Value::Object(binding))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let json = serde_json::json!({
+            "head": { "vars": self.variables() },
+            "results": { "bindings": bindings },
+        });
+        Ok(serde_json::to_writer(writer, &json)?)
+    }
+
+    fn write_xml<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        writeln!(writer, r#"<?xml version="1.0"?>"#)?;
+        writeln!(
+            writer,
+            r#"<sparql xmlns="http://www.w3.org/2005/sparql-results#">"#
+        )?;
+        writeln!(writer, "  <head>")?;
+        for variable in self.variables() {
+            writeln!(
+                writer,
+                r#"    <variable name="{}"/>"#,
+                xml_escape(variable)
+            )?;
+        }
+        writeln!(writer, "  </head>")?;
+        writeln!(writer, "  <results>")?;
+        for solution in self.answers() {
+            writeln!(writer, "    <result>")?;
+            for (variable, value) in self.variables().iter().zip(solution.values()) {
+                if let Some(value) = value {
+                    writeln!(
+                        writer,
+                        r#"      <binding name="{}">{}</binding>"#,
+                        xml_escape(variable),
+                        xml_term(value)?
+                    )?;
+                }
+            }
+            writeln!(writer, "    </result>")?;
+        }
+        writeln!(writer, "  </results>")?;
+        writeln!(writer, "</sparql>")?;
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let header = self
+            .variables()
+            .iter()
+            .map(|variable| csv_escape(variable))
+            .collect::<Vec<_>>();
+        write!(writer, "{}\r\n", header.join(","))?;
+        for solution in self.answers() {
+            let row = solution
+                .values()
+                .iter()
+                .map(|value| {
+                    value
+                        .as_ref()
+                        .map(|value| Ok(csv_escape(&csv_value(value)?)))
+                        .unwrap_or_else(|| Ok(String::new()))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            write!(writer, "{}\r\n", row.join(","))?;
+        }
+        Ok(())
+    }
+
+    fn write_tsv<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let header = self
+            .variables()
+            .iter()
+            .map(|variable| format!("?{variable}"))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", header.join("\t"))?;
+        for solution in self.answers() {
+            let row = solution
+                .values()
+                .iter()
+                .map(|value| {
+                    value
+                        .as_ref()
+                        .map(|value| Ok(rdf_term(value)?.to_string()))
+                        .unwrap_or_else(|| Ok(String::new()))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            writeln!(writer, "{}", row.join("\t"))?;
+        }
+        Ok(())
+    }
+}
+
+fn rdf_term(value: &Literal) -> Result<oxrdf::Term, Error> {
+    oxrdf::Term::try_from(&value.as_term())
+}
+
+fn json_term(value: &Literal) -> Result<Value, Error> {
+    Ok(match rdf_term(value)? {
+        oxrdf::Term::NamedNode(node) => {
+            serde_json::json!({ "type": "uri", "value": node.as_str() })
+        },
+        oxrdf::Term::BlankNode(node) => {
+            serde_json::json!({ "type": "bnode", "value": node.as_str() })
+        },
+        oxrdf::Term::Literal(literal) => {
+            let mut term = Map::new();
+            term.insert("type".to_string(), "literal".into());
+            term.insert("value".to_string(), literal.value().into());
+            if let Some(language) = literal.language() {
+                term.insert("xml:lang".to_string(), language.into());
+            } else if literal.datatype() != oxrdf::vocab::xsd::STRING {
+                term.insert(
+                    "datatype".to_string(),
+                    literal.datatype().as_str().into(),
+                );
+            }
+            Value::Object(term)
+        },
+        #[allow(unreachable_patterns)]
+        other => return Err(unsupported_term(&other)),
+    })
+}
+
+fn xml_term(value: &Literal) -> Result<String, Error> {
+    Ok(match rdf_term(value)? {
+        oxrdf::Term::NamedNode(node) => format!("<uri>{}</uri>", xml_escape(node.as_str())),
+        oxrdf::Term::BlankNode(node) => format!("<bnode>{}</bnode>", xml_escape(node.as_str())),
+        oxrdf::Term::Literal(literal) => {
+            let value = xml_escape(literal.value());
+            if let Some(language) = literal.language() {
+                format!(
+                    r#"<literal xml:lang="{}">{value}</literal>"#,
+                    xml_escape(language)
+                )
+            } else if literal.datatype() == oxrdf::vocab::xsd::STRING {
+                format!("<literal>{value}</literal>")
+            } else {
+                format!(
+                    r#"<literal datatype="{}">{value}</literal>"#,
+                    xml_escape(literal.datatype().as_str())
+                )
+            }
+        },
+        #[allow(unreachable_patterns)]
+        other => return Err(unsupported_term(&other)),
+    })
+}
+
+/// The CSV format only has the lexical forms of literals, and blank nodes
+/// as `_:label`
+fn csv_value(value: &Literal) -> Result<String, Error> {
+    Ok(match rdf_term(value)? {
+        oxrdf::Term::NamedNode(node) => node.into_string(),
+        oxrdf::Term::BlankNode(node) => format!("_:{}", node.as_str()),
+        oxrdf::Term::Literal(literal) => literal.value().to_string(),
+        #[allow(unreachable_patterns)]
+        other => return Err(unsupported_term(&other)),
+    })
+}
+
+fn unsupported_term(term: &oxrdf::Term) -> Error {
+    Error::InvalidSPARQLResults(format!("unsupported RDF term {term}"))
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote the given field if it contains a comma, quote or line break
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,59 @@
+use {crate::solution::Solution, std::sync::Arc};
+
+/// The result of a SPARQL SELECT query: the projected variable names and the
+/// [`Solution`]s, one per returned row.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SolutionSet {
+    variables: Arc<[String]>,
+    solutions: Vec<Solution>,
+}
+
+impl SolutionSet {
+    /// Create a solution set, the given solutions get the given variables so
+    /// that their values can be looked up by name.
+    pub fn new(variables: Vec<String>, solutions: Vec<Solution>) -> Self {
+        let variables: Arc<[String]> = variables.into();
+        let solutions = solutions
+            .into_iter()
+            .map(|solution| solution.with_variables(variables.clone()))
+            .collect();
+        Self { variables, solutions }
+    }
+
+    /// The names of the projected variables (without the leading `?`)
+    pub fn variables(&self) -> &[String] { &self.variables }
+
+    /// Return the column index of the given variable (without the leading `?`)
+    pub fn variable_index(&self, variable: &str) -> Option<usize> {
+        self.variables.iter().position(|name| name == variable)
+    }
+
+    pub fn solutions(&self) -> &[Solution] { self.solutions.as_slice() }
+
+    /// The number of solutions (rows)
+    pub fn len(&self) -> usize { self.solutions.len() }
+
+    pub fn is_empty(&self) -> bool { self.solutions.is_empty() }
+
+    /// The number of answers, i.e. the sum of the multiplicities of the
+    /// solutions
+    pub fn total_multiplicity(&self) -> usize {
+        self.solutions.iter().map(Solution::multiplicity).sum()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Solution> { self.solutions.iter() }
+}
+
+impl IntoIterator for SolutionSet {
+    type IntoIter = std::vec::IntoIter<Solution>;
+    type Item = Solution;
+
+    fn into_iter(self) -> Self::IntoIter { self.solutions.into_iter() }
+}
+
+impl<'a> IntoIterator for &'a SolutionSet {
+    type IntoIter = std::slice::Iter<'a, Solution>;
+    type Item = &'a Solution;
+
+    fn into_iter(self) -> Self::IntoIter { self.solutions.iter() }
+}
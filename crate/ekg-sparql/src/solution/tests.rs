@@ -0,0 +1,281 @@
+#![cfg(all(test, not(target_family = "wasm")))]
+
+use {
+    crate::{Solution, SolutionSet, solution::SparqlResultsJsonDecoder},
+    ekg_metadata::{
+        APPLICATION_SPARQL_RESULTS_JSON,
+        APPLICATION_SPARQL_RESULTS_XML,
+        Literal,
+        TEXT_CSV,
+        TEXT_TSV,
+    },
+};
+
+/// Two solutions, the second one occurring twice and with `?name` unbound
+fn solution_set() -> Result<SolutionSet, ekg_error::Error> {
+    Ok(SolutionSet::new(
+        vec!["person".to_string(), "name".to_string(), "age".to_string()],
+        vec![
+            Solution::new(vec![
+                Some(Literal::new_iri_reference_from_str(
+                    "https://example.com/alice",
+                )?),
+                Some(Literal::new_language_tagged_string(
+                    "Alice, \"Al\"",
+                    "en",
+                )?),
+                Some(Literal::new_signed_integer(42)?),
+            ]),
+            Solution::new(vec![
+                Some(Literal::new_iri_reference_from_str(
+                    "https://example.com/bob",
+                )?),
+                None,
+                Some(Literal::new_unsigned_integer(17)?),
+            ])
+            .with_multiplicity(2),
+        ],
+    ))
+}
+
+fn write(solutions: &SolutionSet, mime_type: &mime::Mime) -> Result<String, ekg_error::Error> {
+    let mut buffer = Vec::new();
+    solutions.write(&mut buffer, mime_type)?;
+    Ok(String::from_utf8(buffer).unwrap())
+}
+
+#[test_log::test]
+fn test_from_sparql_results_json() -> Result<(), ekg_error::Error> {
+    let json = r#"{
+        "head": { "vars": [ "x", "when", "price" ] },
+        "results": { "bindings": [ {
+            "x": { "type": "literal", "value": "chat", "xml:lang": "fr" },
+            "when": {
+                "type": "literal",
+                "value": "2024-03-01",
+                "datatype": "http://www.w3.org/2001/XMLSchema#date"
+            },
+            "price": {
+                "type": "literal",
+                "value": "1.5E0",
+                "datatype": "http://www.w3.org/2001/XMLSchema#double"
+            }
+        } ] }
+    }"#;
+    let solutions = SolutionSet::from_sparql_results_json(json.as_bytes())?;
+
+    assert_eq!(solutions.variable_index("price"), Some(2));
+    let solution = &solutions.solutions()[0];
+    assert_eq!(
+        solution.value(0).and_then(|x| x.as_str()),
+        Some("chat")
+    );
+    assert_eq!(
+        solution.value(0).and_then(|x| x.language()),
+        Some("fr")
+    );
+    assert_eq!(
+        solution
+            .value(1)
+            .and_then(|when| when.as_date())
+            .map(|date| date.to_string()),
+        Some("2024-03-01".to_string())
+    );
+    // xsd:double values are kept in their lexical form
+    assert_eq!(
+        solution.value(2).map(|x| x.data_type),
+        Some(ekg_metadata::DataType::Double)
+    );
+    assert_eq!(
+        solution.value(2).and_then(|x| x.as_str()),
+        Some("1.5E0")
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_from_invalid_sparql_results_json() {
+    let result = SolutionSet::from_sparql_results_json(br#"{ "head": { "vars": [] } }"#);
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::InvalidSPARQLResults(_))
+    ));
+
+    let result = SolutionSet::from_sparql_results_json(
+        br#"{
+          "head": { "vars": [ "s" ] },
+          "results": { "bindings": [
+            { "s": { "type": "literal", "value": "POINT(1 2)", "datatype": "http://www.opengis.net/ont/geosparql#wktLiteral" } }
+          ] }
+        }"#,
+    );
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnknownDataType { .. })
+    ));
+}
+
+#[test_log::test]
+fn test_sparql_results_json_decoder_byte_by_byte() -> Result<(), ekg_error::Error> {
+    // The "head" comes after the "results" and there's some noise in between
+    let json = r#"{
+        "link": [ "https://example.com/about" ],
+        "results": { "distinct": false, "bindings": [
+            { "s": { "type": "literal", "value": "a \"quoted\" {value}, with [brackets]" } },
+            { },
+            { "s": { "type": "literal", "value": "2", "datatype": "http://www.w3.org/2001/XMLSchema#integer" } }
+        ] },
+        "head": { "vars": [ "s" ] }
+    }"#;
+    let mut decoder = SparqlResultsJsonDecoder::new();
+    let mut solutions = Vec::new();
+    for byte in json.as_bytes() {
+        decoder.push(std::slice::from_ref(byte));
+        while let Some(solution) = decoder.next_solution()? {
+            solutions.push(solution);
+        }
+    }
+    decoder.finish()?;
+
+    assert_eq!(
+        decoder.variables(),
+        Some(["s".to_string()].as_slice())
+    );
+    assert_eq!(solutions.len(), 3);
+    assert_eq!(
+        solutions[0].value(0).and_then(|s| s.as_str()),
+        Some(r#"a "quoted" {value}, with [brackets]"#)
+    );
+    assert!(solutions[1].value(0).is_none());
+    assert_eq!(
+        solutions[2].value(0).and_then(|s| s.as_signed_long()),
+        Some(2)
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_sparql_results_json_decoder_truncated() -> Result<(), ekg_error::Error> {
+    let mut decoder = SparqlResultsJsonDecoder::new();
+    decoder.push(br#"{ "head": { "vars": [ "s" ] }, "results": { "bindings": [ { "s": "#);
+    assert!(decoder.next_solution()?.is_none());
+    assert!(matches!(
+        decoder.finish(),
+        Err(ekg_error::Error::InvalidSPARQLResults(_))
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_solution_lookup_by_name() -> Result<(), ekg_error::Error> {
+    let solutions = solution_set()?;
+    assert_eq!(solutions.len(), 2);
+    assert_eq!(solutions.total_multiplicity(), 3);
+
+    let alice = &solutions.solutions()[0];
+    assert_eq!(alice.variables(), ["person", "name", "age"]);
+    assert_eq!(alice.multiplicity(), 1);
+    assert_eq!(
+        alice.get_iri("?person").map(|iri| iri.to_string()),
+        Some("https://example.com/alice".to_string())
+    );
+    assert_eq!(alice.get_str("name"), Some("Alice, \"Al\""));
+    assert_eq!(alice.get_i64("$age"), Some(42));
+    assert!(alice.get("unknown").is_none());
+    assert!(alice.get_date_time("age").is_none());
+
+    let bob = &solutions.solutions()[1];
+    assert_eq!(bob.multiplicity(), 2);
+    assert!(bob.get("name").is_none());
+    assert_eq!(bob.get_i64("age"), Some(17));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_solution_get_date_time() -> Result<(), ekg_error::Error> {
+    let solutions = SolutionSet::from_sparql_results_json(
+        br#"{
+            "head": { "vars": [ "when" ] },
+            "results": { "bindings": [ { "when": {
+                "type": "literal",
+                "value": "2024-03-01T12:00:00Z",
+                "datatype": "http://www.w3.org/2001/XMLSchema#dateTime"
+            } } ] }
+        }"#,
+    )?;
+    assert_eq!(
+        solutions.solutions()[0]
+            .get_date_time("when")
+            .map(|when| when.to_rfc3339()),
+        Some("2024-03-01T12:00:00+00:00".to_string())
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_write_sparql_results_json() -> Result<(), ekg_error::Error> {
+    let json = write(&solution_set()?, &APPLICATION_SPARQL_RESULTS_JSON)?;
+    let solutions = SolutionSet::from_sparql_results_json(json.as_bytes())?;
+    assert_eq!(solutions.variables(), ["person", "name", "age"]);
+    assert_eq!(solutions.len(), 3);
+    let alice = &solutions.solutions()[0];
+    assert_eq!(
+        alice.get("name").and_then(|name| name.language()),
+        Some("en")
+    );
+    assert_eq!(alice.get_i64("age"), Some(42));
+    assert!(solutions.solutions()[2].get("name").is_none());
+    Ok(())
+}
+
+#[test_log::test]
+fn test_write_sparql_results_xml() -> Result<(), ekg_error::Error> {
+    let xml = write(&solution_set()?, &APPLICATION_SPARQL_RESULTS_XML)?;
+    assert!(xml.contains(r#"<variable name="person"/>"#));
+    assert!(
+        xml.contains(r#"<binding name="person"><uri>https://example.com/alice</uri></binding>"#)
+    );
+    assert!(xml.contains(
+        r#"<binding name="name"><literal xml:lang="en">Alice, &quot;Al&quot;</literal></binding>"#
+    ));
+    assert!(xml.contains(
+        r#"<literal datatype="http://www.w3.org/2001/XMLSchema#positiveInteger">42</literal>"#
+    ));
+    assert_eq!(xml.matches("<result>").count(), 3);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_write_sparql_results_csv_and_tsv() -> Result<(), ekg_error::Error> {
+    let solutions = solution_set()?;
+    assert_eq!(
+        write(&solutions, &TEXT_CSV)?,
+        "person,name,age\r\n\
+         https://example.com/alice,\"Alice, \"\"Al\"\"\",42\r\n\
+         https://example.com/bob,,17\r\n\
+         https://example.com/bob,,17\r\n"
+    );
+    let tsv = write(&solutions, &TEXT_TSV)?;
+    let lines = tsv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "?person\t?name\t?age");
+    assert_eq!(
+        lines[1],
+        "<https://example.com/alice>\t\"Alice, \\\"Al\\\"\"@en\t\
+         \"42\"^^<http://www.w3.org/2001/XMLSchema#positiveInteger>"
+    );
+    assert_eq!(
+        lines[3],
+        "<https://example.com/bob>\t\t\"17\"^^<http://www.w3.org/2001/XMLSchema#positiveInteger>"
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_write_unsupported_content_type() -> Result<(), ekg_error::Error> {
+    assert!(matches!(
+        solution_set()?.write(Vec::new(), &mime::TEXT_PLAIN),
+        Err(ekg_error::Error::InvalidSPARQLResults(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use {
+    ekg_metadata::{Literal, Term},
+    std::sync::Arc,
+};
+
+/// A `Solution` is one row of a [`SolutionSet`](crate::SolutionSet), i.e. a
+/// set of bindings for the variables of a SPARQL SELECT query.
+///
+/// The values are in the same order as the variables of the solution set,
+/// unbound variables are represented as `None`. Solutions that are part of
+/// a solution set (or are streamed from a SPARQL endpoint) also know the
+/// names of the variables so that values can be looked up by name.
+///
+/// The multiplicity is the number of times that the solution occurs in the
+/// result, RDFox returns duplicate solutions only once. It is 1 for the
+/// solutions returned by SPARQL endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    variables:    Arc<[String]>,
+    values:       Vec<Option<Literal>>,
+    multiplicity: usize,
+}
+
+impl Default for Solution {
+    fn default() -> Self { Self::new(Vec::new()) }
+}
+
+impl Solution {
+    pub fn new(values: Vec<Option<Literal>>) -> Self {
+        Self { variables: Arc::from([]), values, multiplicity: 1 }
+    }
+
+    /// Set the number of times that this solution occurs in the result
+    pub fn with_multiplicity(mut self, multiplicity: usize) -> Self {
+        self.multiplicity = multiplicity;
+        self
+    }
+
+    /// Set the names of the variables, in the order of the values
+    pub(crate) fn with_variables(mut self, variables: Arc<[String]>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// The number of columns in this solution
+    pub fn len(&self) -> usize { self.values.len() }
+
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+
+    /// The number of times that this solution occurs in the result
+    pub fn multiplicity(&self) -> usize { self.multiplicity }
+
+    /// The names of the variables (without the leading `?`), empty if the
+    /// solution is not part of a solution set
+    pub fn variables(&self) -> &[String] { &self.variables }
+
+    /// Get the value bound to the variable with the given index, `None` if
+    /// the variable is unbound.
+    pub fn value(&self, index: usize) -> Option<&Literal> {
+        self.values.get(index).and_then(|value| value.as_ref())
+    }
+
+    /// Get the value bound to the variable with the given index as a [`Term`]
+    pub fn term(&self, index: usize) -> Option<Term> { self.value(index).map(Literal::as_term) }
+
+    pub fn values(&self) -> &[Option<Literal>] { self.values.as_slice() }
+
+    /// Get the value bound to the variable with the given name (with or
+    /// without the leading `?` or `$`), `None` if the variable is unbound or
+    /// unknown.
+    pub fn get(&self, variable: &str) -> Option<&Literal> {
+        let variable = variable.strip_prefix(['?', '$']).unwrap_or(variable);
+        self.variables
+            .iter()
+            .position(|name| name == variable)
+            .and_then(|index| self.value(index))
+    }
+
+    /// Get the IRI bound to the given variable
+    pub fn get_iri(&self, variable: &str) -> Option<iri_string::types::IriReferenceString> {
+        self.get(variable).and_then(Literal::as_iri)
+    }
+
+    /// Get the string (or other value that is kept as a string, such as an
+    /// IRI or decimal) bound to the given variable
+    pub fn get_str(&self, variable: &str) -> Option<&str> {
+        self.get(variable).and_then(Literal::as_str)
+    }
+
+    /// Get the integer bound to the given variable, `None` if it is not an
+    /// integer or does not fit in an `i64`
+    pub fn get_i64(&self, variable: &str) -> Option<i64> {
+        let literal = self.get(variable)?;
+        literal.as_signed_long().or_else(|| {
+            literal
+                .as_unsigned_long()
+                .and_then(|value| value.try_into().ok())
+        })
+    }
+
+    /// Get the `xsd:dateTime` bound to the given variable
+    pub fn get_date_time(&self, variable: &str) -> Option<&chrono::DateTime<chrono::Utc>> {
+        self.get(variable).and_then(Literal::as_date_time)
+    }
+}
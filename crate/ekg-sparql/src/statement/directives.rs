@@ -0,0 +1,215 @@
+use {
+    crate::{FactDomain, Prefixes},
+    ekg_error::Error,
+    ekg_identifier::NS_XSD,
+    ekg_metadata::DataType,
+    ekg_util::log::LOG_TARGET_SPARQL,
+    std::{ops::Deref, time::Duration},
+};
+
+/// The prefix of the directives in the comments of a SPARQL statement.
+const DIRECTIVE_PREFIX: &str = "ekg:";
+
+/// A variable of a SPARQL statement that is declared with an
+/// `# ekg:param ?name datatype` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterDirective {
+    /// The name of the variable, without the leading `?` or `$`
+    pub name:      String,
+    /// The datatype of the values of the variable, if it was given
+    pub data_type: Option<DataType>,
+}
+
+/// The directives found in the comment lines of a SPARQL statement, such as:
+///
+/// ```sparql
+/// # ekg:name: people-by-email
+/// # ekg:timeout: 30s
+/// # ekg:max-rows: 1000
+/// # ekg:fact-domain: explicit
+/// # ekg:param ?date xsd:date
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementDirectives {
+    /// The name under which the statement can be found in a
+    /// `StatementLibrary`, next to its path
+    pub name:        Option<String>,
+    /// The maximum time to wait for the response (`ms`, `s`, `m` or `h`, or
+    /// seconds without a unit)
+    pub timeout:     Option<Duration>,
+    /// The maximum number of solutions that the statement may return
+    pub max_rows:    Option<usize>,
+    /// Whether to query explicit (asserted), derived (inferred) or all facts
+    pub fact_domain: Option<FactDomain>,
+    /// The declared variables, in the order of their declaration
+    pub params:      Vec<ParameterDirective>,
+}
+
+impl StatementDirectives {
+    /// Parse the directives in the comment lines of the given statement,
+    /// using the given prefixes for datatypes such as `xsd:date`.
+    pub fn parse(statement: &str, prefixes: &Prefixes) -> Result<Self, Error> {
+        let mut directives = Self::default();
+        for (index, line) in statement.lines().enumerate() {
+            let Some(directive) = directive_of_line(line) else {
+                continue;
+            };
+            directives
+                .parse_directive(directive, prefixes)
+                .map_err(|message| Error::InvalidStatementDirective { line: index + 1, message })?;
+        }
+        Ok(directives)
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&ParameterDirective> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Parse one directive, everything after `ekg:`, the key is followed by
+    /// an optional colon. Unknown keys are ignored with a warning so that
+    /// comments such as `# ekg:todo ...` do not invalidate the statement.
+    fn parse_directive(&mut self, directive: &str, prefixes: &Prefixes) -> Result<(), String> {
+        let (key, value) = directive
+            .split_once(|c: char| c == ':' || c.is_whitespace())
+            .unwrap_or((directive, ""));
+        let value = value.trim();
+        match key {
+            "name" => {
+                if value.is_empty() || value.contains(char::is_whitespace) {
+                    return Err(format!("invalid name \"{value}\""));
+                }
+                set_once(&mut self.name, key, value.to_string())
+            },
+            "timeout" => set_once(&mut self.timeout, key, parse_duration(value)?),
+            "max-rows" => {
+                let max_rows = value
+                    .parse()
+                    .map_err(|_| format!("invalid number of rows \"{value}\""))?;
+                set_once(&mut self.max_rows, key, max_rows)
+            },
+            "fact-domain" => {
+                set_once(
+                    &mut self.fact_domain,
+                    key,
+                    parse_fact_domain(value)?,
+                )
+            },
+            "param" => {
+                let param = parse_parameter(value, prefixes)?;
+                if self.parameter(param.name.as_str()).is_some() {
+                    return Err(format!("duplicate param ?{}", param.name));
+                }
+                self.params.push(param);
+                Ok(())
+            },
+            _ => {
+                tracing::warn!(
+                    target: LOG_TARGET_SPARQL,
+                    "Ignoring unknown directive {DIRECTIVE_PREFIX}{key}"
+                );
+                Ok(())
+            },
+        }
+    }
+}
+
+/// The directive in the given line, everything after `# ekg:`, if the line
+/// is a directive comment.
+pub(crate) fn directive_of_line(line: &str) -> Option<&str> {
+    line.trim_start()
+        .strip_prefix('#')?
+        .trim_start()
+        .strip_prefix(DIRECTIVE_PREFIX)
+}
+
+/// The name of the variable declared by the given `param` directive, without
+/// its leading `?` or `$`.
+pub(crate) fn parameter_name_of_directive(directive: &str) -> Option<&str> {
+    let (key, value) = directive.split_once(|c: char| c == ':' || c.is_whitespace())?;
+    if key != "param" {
+        return None;
+    }
+    let name = value.split_whitespace().next()?;
+    name.strip_prefix(['?', '$'])
+}
+
+fn set_once<T>(field: &mut Option<T>, key: &str, value: T) -> Result<(), String> {
+    if field.is_some() {
+        return Err(format!(
+            "duplicate directive {DIRECTIVE_PREFIX}{key}"
+        ));
+    }
+    *field = Some(value);
+    Ok(())
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration \"{value}\", use for instance 500ms, 30s or 5m");
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let seconds = match unit.trim() {
+        "ms" => return Ok(Duration::from_millis(number)),
+        "" | "s" => Some(number),
+        "m" => number.checked_mul(60),
+        "h" => number.checked_mul(3600),
+        _ => None,
+    };
+    seconds.map(Duration::from_secs).ok_or_else(invalid)
+}
+
+fn parse_fact_domain(value: &str) -> Result<FactDomain, String> {
+    match value {
+        "explicit" | "asserted" => Ok(FactDomain::ASSERTED),
+        "derived" | "inferred" => Ok(FactDomain::INFERRED),
+        "all" => Ok(FactDomain::ALL),
+        _ => {
+            Err(format!(
+                "invalid fact domain \"{value}\", use explicit, derived or all"
+            ))
+        },
+    }
+}
+
+/// Parse `?name` optionally followed by a datatype, either a prefixed name
+/// such as `xsd:date` or a full IRI between angle brackets.
+fn parse_parameter(value: &str, prefixes: &Prefixes) -> Result<ParameterDirective, String> {
+    let mut parts = value.split_whitespace();
+    let name = parts
+        .next()
+        .and_then(|variable| variable.strip_prefix(['?', '$']))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| format!("expected a variable such as ?name instead of \"{value}\""))?;
+    let data_type = parts
+        .next()
+        .map(|data_type| parse_data_type(data_type, prefixes))
+        .transpose()?;
+    if let Some(unexpected) = parts.next() {
+        return Err(format!(
+            "unexpected \"{unexpected}\" after the datatype"
+        ));
+    }
+    Ok(ParameterDirective { name: name.to_string(), data_type })
+}
+
+fn parse_data_type(data_type: &str, prefixes: &Prefixes) -> Result<DataType, String> {
+    let iri = if let Some(iri) = data_type
+        .strip_prefix('<')
+        .and_then(|iri| iri.strip_suffix('>'))
+    {
+        iri.to_string()
+    } else {
+        let Some((prefix, local_name)) = data_type.split_once(':') else {
+            return Err(format!("invalid datatype \"{data_type}\""));
+        };
+        let prefix = format!("{prefix}:");
+        let namespace = prefixes
+            .get_namespace(prefix.as_str())
+            .or_else(|| (prefix == NS_XSD.name).then(|| NS_XSD.deref()))
+            .ok_or_else(|| format!("unknown prefix {prefix} in datatype {data_type}"))?;
+        format!("{}{local_name}", namespace.iri.as_str())
+    };
+    DataType::from_xsd_iri(iri.as_str()).map_err(|error| error.to_string())
+}
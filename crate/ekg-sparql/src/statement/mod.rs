@@ -1,9 +1,12 @@
 //! Represent a SPARQL Statement
 pub use {
+    directives::{ParameterDirective, StatementDirectives},
     statement_type::SPARQLStatementType,
     this::{Statement, RDFOX_QUERY_VALIDATION, RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT},
     utils::no_comments,
 };
+pub(crate) mod directives;
+pub(crate) mod scanner;
 mod statement_type;
 #[cfg(test)]
 mod tests;
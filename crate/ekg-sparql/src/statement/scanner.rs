@@ -0,0 +1,248 @@
+//! A simple lexical scanner for SPARQL statements, good enough to find
+//! keywords and prefixed names without parsing the statement, so also in
+//! statements that cannot be parsed (yet), for instance because of an
+//! undeclared prefix.
+
+/// The kind of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    /// A keyword, function name or boolean such as `SELECT`, `a` or `true`
+    Word,
+    /// A prefixed name such as `rdf:type` or `:local`
+    PrefixedName,
+    /// A blank node label such as `_:b0`
+    BlankNode,
+    /// An IRI between angle brackets
+    Iri,
+    /// A variable such as `?name` or `$name`
+    Variable,
+    /// A string literal in single, double or triple quotes
+    String,
+    /// A language tag such as `@en`
+    LangTag,
+    /// A numeric literal
+    Number,
+    /// A `#` comment up to the end of the line
+    Comment,
+    /// Any other character such as `{`, `.` or `<`
+    Punctuation,
+}
+
+/// A token of a SPARQL statement with its byte offset and its 1-based line
+/// and column (in characters) in the statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Token<'a> {
+    pub kind:   TokenKind,
+    pub text:   &'a str,
+    pub offset: usize,
+    pub line:   usize,
+    pub column: usize,
+}
+
+impl<'a> Token<'a> {
+    /// The prefix of a prefixed name, including its colon.
+    pub fn prefix(&self) -> Option<&'a str> {
+        if self.kind != TokenKind::PrefixedName {
+            return None;
+        }
+        self.text.find(':').map(|colon| &self.text[..=colon])
+    }
+
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+/// Split the given statement into tokens, skipping whitespace.
+pub(crate) fn tokens(statement: &str) -> Vec<Token<'_>> {
+    let mut scanner = Scanner { statement, position: 0, line: 1, column: 1 };
+    let mut tokens = Vec::new();
+    while let Some(token) = scanner.next_token() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+struct Scanner<'a> {
+    statement: &'a str,
+    /// The byte offset of the next character
+    position:  usize,
+    line:      usize,
+    column:    usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        while self.peek()?.is_whitespace() {
+            self.bump();
+        }
+        let (start, line, column) = (self.position, self.line, self.column);
+        let kind = self.scan_token();
+        Some(Token {
+            kind,
+            text: &self.statement[start..self.position],
+            offset: start,
+            line,
+            column,
+        })
+    }
+
+    fn scan_token(&mut self) -> TokenKind {
+        let c = self.bump().unwrap_or_default();
+        match c {
+            '#' => {
+                self.bump_while(|c| c != '\n');
+                TokenKind::Comment
+            },
+            '"' | '\'' => {
+                self.scan_string(c);
+                TokenKind::String
+            },
+            '<' if self.is_iri() => {
+                self.bump_while(|c| c != '>');
+                self.bump();
+                TokenKind::Iri
+            },
+            '?' | '$' if self.peek().is_some_and(is_variable_char) => {
+                self.bump_while(is_variable_char);
+                TokenKind::Variable
+            },
+            '@' if self.peek().is_some_and(|c| c.is_ascii_alphabetic()) => {
+                self.bump_while(|c| c.is_ascii_alphanumeric() || c == '-');
+                TokenKind::LangTag
+            },
+            '_' if self.peek() == Some(':') => {
+                self.bump();
+                self.scan_local_name();
+                TokenKind::BlankNode
+            },
+            ':' => {
+                self.scan_local_name();
+                TokenKind::PrefixedName
+            },
+            c if c.is_alphabetic() => {
+                self.bump_while(|c| is_name_char(c) || c == '.');
+                self.unbump_trailing_dots();
+                if self.peek() == Some(':') {
+                    self.bump();
+                    self.scan_local_name();
+                    TokenKind::PrefixedName
+                } else {
+                    TokenKind::Word
+                }
+            },
+            c if c.is_ascii_digit() => {
+                self.scan_number();
+                TokenKind::Number
+            },
+            _ => TokenKind::Punctuation,
+        }
+    }
+
+    /// Whether the `<` that was just consumed starts an IRI rather than
+    /// being a comparison operator: IRIs cannot contain whitespace.
+    fn is_iri(&self) -> bool {
+        for c in self.statement[self.position..].chars() {
+            match c {
+                '>' => return true,
+                '<' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => return false,
+                c if c.is_whitespace() => return false,
+                _ => {},
+            }
+        }
+        false
+    }
+
+    fn scan_string(&mut self, quote: char) {
+        let doubled = format!("{quote}{quote}");
+        let triple = self.statement[self.position..].starts_with(&doubled) &&
+            self.statement[self.position + 2..].starts_with(|c| c != quote);
+        if triple {
+            self.bump();
+            self.bump();
+        }
+        while let Some(c) = self.bump() {
+            match c {
+                '\\' => {
+                    self.bump();
+                },
+                c if c == quote => {
+                    if !triple {
+                        return;
+                    }
+                    if self.statement[self.position..].starts_with(&doubled) {
+                        self.bump();
+                        self.bump();
+                        return;
+                    }
+                },
+                '\n' if !triple => return,
+                _ => {},
+            }
+        }
+    }
+
+    fn scan_local_name(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                '\\' => {
+                    self.bump();
+                    self.bump();
+                },
+                c if is_name_char(c) || c == '.' || c == ':' || c == '%' => {
+                    self.bump();
+                },
+                _ => break,
+            }
+        }
+        self.unbump_trailing_dots();
+    }
+
+    fn scan_number(&mut self) {
+        self.bump_while(|c| c.is_ascii_digit());
+        let rest = &self.statement[self.position..];
+        if rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            self.bump();
+            self.bump_while(|c| c.is_ascii_digit());
+        }
+        if self.peek().is_some_and(|c| c == 'e' || c == 'E') {
+            self.bump();
+            if self.peek().is_some_and(|c| c == '+' || c == '-') {
+                self.bump();
+            }
+            self.bump_while(|c| c.is_ascii_digit());
+        }
+    }
+
+    /// A name cannot end with a dot, which ends the triple instead.
+    fn unbump_trailing_dots(&mut self) {
+        while self.statement[..self.position].ends_with('.') {
+            self.position -= 1;
+            self.column -= 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> { self.statement[self.position..].chars().next() }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn bump_while(&mut self, f: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&f) {
+            self.bump();
+        }
+    }
+}
+
+fn is_variable_char(c: char) -> bool { c.is_alphanumeric() || c == '_' }
+
+fn is_name_char(c: char) -> bool { is_variable_char(c) || c == '-' }
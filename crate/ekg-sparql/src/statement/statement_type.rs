@@ -48,6 +48,17 @@ impl SPARQLStatementType {
         Self::from_iri(literal.as_iri_ref(), flavor)
     }
 
+    pub fn flavor(&self) -> SPARQLFlavor {
+        match self {
+            Self::SELECT(flavor) |
+            Self::ASK(flavor) |
+            Self::CONSTRUCT(flavor) |
+            Self::DESCRIBE(flavor) |
+            Self::UPDATE(flavor) |
+            Self::DELETE(flavor) => *flavor,
+        }
+    }
+
     pub fn is_query_statement(&self) -> bool {
         matches!(
             self,
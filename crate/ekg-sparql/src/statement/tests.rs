@@ -66,3 +66,133 @@ fn test_embedded_param() {
         Some(&crate::RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT)
     );
 }
+
+#[test_log::test]
+fn test_request_parameters() -> Result<(), ekg_error::Error> {
+    let statement = crate::Statement::new(
+        crate::Prefixes::builder().build()?,
+        "# rdfox-query-validation: standard-compliant\nASK { ?s ?p ?o }".into(),
+    )?;
+
+    assert_eq!(statement.request_parameters(), vec![(
+        "query-validation",
+        "standard-compliant"
+    )]);
+    let parameters = statement.complete_parameters(crate::Parameters::empty()?)?;
+    assert_eq!(
+        parameters.get_string("query-validation", "")?,
+        "standard-compliant"
+    );
+    Ok(())
+}
+
+#[test_log::test]
+fn test_directives() -> Result<(), ekg_error::Error> {
+    let sparql = indoc::formatdoc! {r##"
+            # ekg:name: things-by-date
+            # ekg:timeout: 30s
+            # ekg:max-rows: 1000
+            #   ekg:fact-domain: explicit
+            # ekg:param ?date xsd:date
+            # ekg:param $thing
+            # ekg:todo unknown directives such as this one are ignored
+            # ekg: is not a directive without a key, but this is just a comment
+            SELECT ?thing WHERE {{ ?thing ?p ?date }}
+            "##
+    };
+    let statement = crate::Statement::new(
+        crate::Prefixes::builder().build()?,
+        sparql.as_str().into(),
+    )?;
+    let directives = &statement.directives;
+    assert_eq!(directives.name.as_deref(), Some("things-by-date"));
+    assert_eq!(
+        directives.timeout,
+        Some(std::time::Duration::from_secs(30))
+    );
+    assert_eq!(directives.max_rows, Some(1000));
+    assert_eq!(
+        directives.fact_domain,
+        Some(crate::FactDomain::ASSERTED)
+    );
+    assert_eq!(directives.params, vec![
+        crate::ParameterDirective {
+            name:      "date".to_string(),
+            data_type: Some(ekg_metadata::DataType::Date),
+        },
+        crate::ParameterDirective { name: "thing".to_string(), data_type: None },
+    ]);
+    assert_eq!(statement.request_parameters(), vec![(
+        "fact-domain",
+        "explicit"
+    )]);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_invalid_directives() -> Result<(), ekg_error::Error> {
+    for (sparql, expected_line) in [
+        ("ASK {}\n# ekg:timeout: soon", 2),
+        ("# ekg:timeout: 9999999999999999999h\nASK {}", 1),
+        ("# ekg:max-rows: many\nASK {}", 1),
+        ("\n\n# ekg:fact-domain: some", 3),
+        ("# ekg:param date xsd:date\nASK {}", 1),
+        ("# ekg:param ?date foo:date\nASK {}", 1),
+        ("# ekg:param ?date xsd:noSuchType\nASK {}", 1),
+        ("# ekg:param ?date\n# ekg:param ?date\nASK {}", 2),
+        ("# ekg:timeout: 1s\n# ekg:timeout: 2s\nASK {}", 2),
+        ("# ekg:name: two words\nASK {}", 1),
+    ] {
+        let result = crate::Statement::new(crate::Prefixes::builder().build()?, sparql.into());
+        match result {
+            Err(ekg_error::Error::InvalidStatementDirective { line, message }) => {
+                assert_eq!(line, expected_line, "{sparql}: {message}");
+            },
+            other => panic!("expected an invalid directive in {sparql}, got {other:?}"),
+        }
+    }
+    Ok(())
+}
+
+#[test_log::test]
+fn test_inferred_prefixes() -> Result<(), ekg_error::Error> {
+    let prefixes = crate::Prefixes::builder()
+        .default_namespaces()
+        .declare(&ekg_metadata::Namespace::declare_from_str(
+            "ex:",
+            "https://example.com/",
+        )?)
+        .build()?;
+    let statement = crate::Statement::new_with_inferred_prefixes(
+        &prefixes,
+        indoc::indoc! {r#"
+            # owl:Thing in a comment is not used
+            PREFIX local: <https://example.com/local/>
+            SELECT ?thing WHERE {
+                ?thing a ex:Thing ;
+                    story:name "rdfs:label"@en ;
+                    local:thing <https://example.com/owl:Thing> .
+            }
+        "#}
+        .into(),
+    )?;
+    let text = statement.as_str();
+
+    assert!(text.contains("PREFIX ex: <https://example.com/>"));
+    assert!(text.contains("PREFIX story: <https://ekgf.org/ontology/story#>"));
+    assert!(!text.contains("PREFIX owl:"));
+    assert!(!text.contains("PREFIX rdfs:"));
+    assert_eq!(text.matches("PREFIX local:").count(), 1);
+    crate::ParsedStatement::parse(&statement, None)?;
+
+    let error = crate::Statement::new_with_inferred_prefixes(
+        &prefixes,
+        "SELECT * WHERE {\n  ?s exx:p ?o .\n}".into(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        ekg_error::Error::UndeclaredPrefix { prefix, line: 2, column: 6, .. } if prefix == "exx:"
+    ));
+    Ok(())
+}
@@ -4,9 +4,10 @@ use {
     std::ffi::CString,
 };
 use {
-    crate::{Parameters, Prefixes},
+    super::scanner,
+    crate::{Parameters, Prefixes, StatementDirectives},
     ekg_error::Error,
-    ekg_metadata::DEFAULT_GRAPH_RDFOX,
+    ekg_metadata::{DEFAULT_GRAPH_RDFOX, Namespace},
     indoc::formatdoc,
     std::{
         borrow::Cow,
@@ -27,6 +28,8 @@ pub struct Statement {
     /// Parameters to be added to the SPARQL HTTP request that were recognized
     /// in the comments of the SPARQL statement.
     pub params:          HashMap<&'static str, &'static str>,
+    /// The `# ekg:` directives in the comments of the SPARQL statement.
+    pub directives:      StatementDirectives,
 }
 
 impl Display for Statement {
@@ -50,12 +53,59 @@ impl Statement {
         params: HashMap<&'static str, &'static str>,
     ) -> Result<Self, ekg_error::Error> {
         Ok(Self {
-            prefixes: prefixes.clone(),
-            text:     format!("{}\n{}", &prefixes.to_string(), statement.trim()),
-            params:   Self::scan_for_params(statement.as_ref(), params)?,
+            prefixes:   prefixes.clone(),
+            text:       format!("{}\n{}", &prefixes.to_string(), statement.trim()),
+            params:     Self::scan_for_params(statement.as_ref(), params)?,
+            directives: StatementDirectives::parse(statement.as_ref(), &prefixes)?,
         })
     }
 
+    /// Create a statement that only declares the prefixes that its body uses:
+    /// the ones of the given prefixes and, for prefixes that are not among
+    /// them, the well-known namespaces such as `rdf:` or `story:` (see
+    /// [`ekg_identifier::well_known_namespace`]). Prefixes that are declared
+    /// in the body itself are left alone.
+    ///
+    /// Returns [`Error::UndeclaredPrefix`] with the position of the first
+    /// prefixed name whose prefix is not declared anywhere.
+    pub fn new_with_inferred_prefixes(
+        prefixes: &Prefixes,
+        statement: Cow<str>,
+    ) -> Result<Self, ekg_error::Error> {
+        let tokens = scanner::tokens(statement.as_ref());
+        let declared_in_body = tokens
+            .windows(2)
+            .filter(|pair| pair[0].is_keyword("PREFIX"))
+            .filter_map(|pair| pair[1].prefix())
+            .collect::<Vec<_>>();
+        let mut used = Vec::<Namespace>::new();
+        for (index, token) in tokens.iter().enumerate() {
+            let Some(prefix) = token.prefix() else {
+                continue;
+            };
+            let is_declaration = index > 0 && tokens[index - 1].is_keyword("PREFIX");
+            if is_declaration ||
+                declared_in_body.contains(&prefix) ||
+                used.iter().any(|namespace| namespace.name == prefix)
+            {
+                continue;
+            }
+            let namespace = prefixes
+                .get_namespace(prefix)
+                .or_else(|| ekg_identifier::well_known_namespace(prefix))
+                .ok_or_else(|| {
+                    Error::UndeclaredPrefix {
+                        prefix:    prefix.to_string(),
+                        line:      token.line,
+                        column:    token.column,
+                        statement: statement.to_string(),
+                    }
+                })?;
+            used.push(namespace.clone());
+        }
+        Self::new(Prefixes::builder().declare_namespaces(&used).build()?, statement)
+    }
+
     /// Scan the comment lines in the given SPARQL statement for special
     /// key/value pairs that we should add to the SPARQL HTTP request such
     /// as:
@@ -66,31 +116,24 @@ impl Statement {
         mut params: HashMap<&'static str, &'static str>,
     ) -> Result<HashMap<&'static str, &'static str>, ekg_error::Error> {
         for line in statement.lines().filter(|l| l.starts_with("#")) {
-            if let Some(stripped_line) = line.strip_prefix("# ") {
-                if let Some((key, value)) = Self::scan_for_param(stripped_line) {
-                    params.insert(key, value);
-                }
+            if let Some(stripped_line) = line.strip_prefix("# ")
+                && let Some((key, value)) = Self::scan_for_param(stripped_line)
+            {
+                params.insert(key, value);
             }
         }
         Ok(params)
     }
 
-    fn scan_for_param(line: &str) -> Option<(&'static str, &'static str)> {
+    pub(crate) fn scan_for_param(line: &str) -> Option<(&'static str, &'static str)> {
         let mut parts = line.splitn(2, ':');
-        if let Some(key) = parts.next() {
-            match key {
-                RDFOX_QUERY_VALIDATION => {
-                    if let Some(RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT) =
-                        parts.next().map(|s| s.trim())
-                    {
-                        return Some((
-                            RDFOX_QUERY_VALIDATION,
-                            RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT,
-                        ));
-                    }
-                },
-                _ => {},
-            }
+        if let Some(RDFOX_QUERY_VALIDATION) = parts.next()
+            && let Some(RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT) = parts.next().map(|s| s.trim())
+        {
+            return Some((
+                RDFOX_QUERY_VALIDATION,
+                RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT,
+            ));
         }
         None
     }
@@ -132,21 +175,33 @@ impl Statement {
         Parameters::builder().fact_domain_all().build()
     }
 
+    /// The parameters that are found in the comments of the SPARQL statement,
+    /// including the `ekg:fact-domain` directive, named as RDFox expects them
+    /// (i.e. without the `rdfox-` prefix) and sorted by name.
+    pub fn request_parameters(&self) -> Vec<(&'static str, &'static str)> {
+        let mut parameters = self
+            .params
+            .iter()
+            .map(|(&key, &value)| (key.strip_prefix("rdfox-").unwrap_or(key), value))
+            .collect::<Vec<_>>();
+        if let Some(fact_domain) = &self.directives.fact_domain {
+            parameters.push(("fact-domain", fact_domain.as_str()));
+        }
+        parameters.sort();
+        parameters
+    }
+
     /// Complete the given parameters with any parameters that are found in the
     /// comments of the SPARQL statement.
     pub fn complete_parameters(&self, parameters: Parameters) -> Result<Parameters, Error> {
-        let fixed_params = if let Some(&RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT) =
-            self.params.get(RDFOX_QUERY_VALIDATION)
-        {
-            let mut tmp_params = parameters.clone();
-            tmp_params.set_string(
-                RDFOX_QUERY_VALIDATION.strip_prefix("rdfox-").unwrap(),
-                RDFOX_QUERY_VALIDATION_STANDARD_COMPLIANT,
-            )?;
-            tmp_params
-        } else {
-            parameters
-        };
+        let request_parameters = self.request_parameters();
+        if request_parameters.is_empty() {
+            return Ok(parameters);
+        }
+        let mut fixed_params = parameters.clone();
+        for (key, value) in request_parameters {
+            fixed_params.set_string(key, value)?;
+        }
         Ok(fixed_params)
     }
 
@@ -0,0 +1,26 @@
+use crate::{
+    Quad,
+    SPARQLClient,
+    SolutionSet,
+    statement::Statement,
+    store::{StoreFuture, TripleStore},
+};
+
+/// Evaluates statements at the remote SPARQL endpoint.
+impl TripleStore for SPARQLClient {
+    fn select<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, SolutionSet> {
+        Box::pin(self.query_select(statement))
+    }
+
+    fn ask<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, bool> {
+        Box::pin(self.query_ask(statement))
+    }
+
+    fn construct<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, Vec<Quad>> {
+        Box::pin(self.query_construct(statement))
+    }
+
+    fn update<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, ()> {
+        Box::pin(self.execute(statement))
+    }
+}
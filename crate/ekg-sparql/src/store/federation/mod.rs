@@ -0,0 +1,6 @@
+pub use this::Federation;
+
+mod service;
+#[cfg(test)]
+mod tests;
+mod this;
@@ -0,0 +1,395 @@
+//! Evaluation of SPARQL `SERVICE` clauses by materialisation: the pattern of
+//! each clause is sent to its endpoint as a `SELECT *` query and the clause
+//! is replaced with a `VALUES` block that holds the solutions, so that the
+//! rest of the statement can be evaluated by a store that does not support
+//! `SERVICE` itself.
+//!
+//! A clause that is joined with the pattern before it, such as
+//! `?s ex:p ?o SERVICE <...> { ?o ex:q ?x }` or an `OPTIONAL { SERVICE ... }`,
+//! is evaluated as a bind join: the distinct bindings of the variables that
+//! the clause shares with that pattern are evaluated in the local store
+//! first and sent along with the query as a `VALUES` block.
+use {
+    crate::{Prefixes, SolutionSet, Statement, parser::query_pattern_mut},
+    ekg_error::Error,
+    ekg_metadata::Literal,
+    spargebra::{
+        GraphUpdateOperation,
+        Query,
+        Update,
+        algebra::{AggregateExpression, Expression, GraphPattern, OrderExpression, QueryDataset},
+        term::{GroundTerm, NamedNodePattern, Variable},
+    },
+};
+
+/// A `SERVICE` clause of a statement.
+pub(super) struct ServiceCall {
+    /// The IRI of the endpoint
+    pub endpoint: String,
+    /// The pattern of the clause as a `SELECT *` query
+    pub query:    Statement,
+    pub silent:   bool,
+    /// The query for the distinct bindings, in the local store, of the
+    /// variables that the clause shares with the pattern it is joined with
+    pub bindings: Option<Statement>,
+    pattern:      GraphPattern,
+}
+
+impl ServiceCall {
+    fn of(
+        pattern: &GraphPattern,
+        context: Option<&GraphPattern>,
+        dataset: Option<&QueryDataset>,
+        statement: &Statement,
+    ) -> Result<Self, Error> {
+        let GraphPattern::Service { name, inner, silent } = pattern else {
+            unreachable!("only called for SERVICE patterns")
+        };
+        let NamedNodePattern::NamedNode(endpoint) = name else {
+            return Err(Error::UnsupportedByStore {
+                store:     "Federation".to_string(),
+                feature:   format!("SERVICE with variable endpoint {name}"),
+                statement: statement.to_string(),
+            });
+        };
+        let bindings = match context {
+            Some(context) => {
+                let mut variables = Vec::new();
+                inner.on_in_scope_variable(|variable| {
+                    if !variables.contains(variable) {
+                        variables.push(variable.clone());
+                    }
+                });
+                let mut shared = Vec::new();
+                context.on_in_scope_variable(|variable| {
+                    if variables.contains(variable) && !shared.contains(variable) {
+                        shared.push(variable.clone());
+                    }
+                });
+                if shared.is_empty() {
+                    None
+                } else {
+                    Some(select_statement(
+                        GraphPattern::Distinct {
+                            inner: Box::new(GraphPattern::Project {
+                                inner:     Box::new(context.clone()),
+                                variables: shared,
+                            }),
+                        },
+                        dataset.cloned(),
+                    )?)
+                }
+            },
+            None => None,
+        };
+        Ok(Self {
+            endpoint: endpoint.as_str().to_string(),
+            query: select_statement(inner.as_ref().clone(), None)?,
+            silent: *silent,
+            bindings,
+            pattern: inner.as_ref().clone(),
+        })
+    }
+
+    /// The pattern of the clause as a `SELECT *` query that only returns the
+    /// solutions that are compatible with one of the given bindings, see
+    /// [`ServiceCall::bindings`]. Blank nodes in the bindings cannot be sent
+    /// to the endpoint so they are left unbound, which only makes the
+    /// endpoint return more solutions than needed.
+    pub fn query_with(&self, bindings: &SolutionSet) -> Result<Statement, Error> {
+        let variables = variables(self, bindings)?;
+        let bindings = bindings
+            .iter()
+            .map(|solution| {
+                solution
+                    .values()
+                    .iter()
+                    .map(|value| Ok(value.as_ref().map(ground_term).transpose()?.flatten()))
+                    .collect::<Result<Vec<_>, Error>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        select_statement(
+            GraphPattern::Join {
+                left:  Box::new(GraphPattern::Values { variables, bindings }),
+                right: Box::new(self.pattern.clone()),
+            },
+            None,
+        )
+    }
+}
+
+fn select_statement(
+    pattern: GraphPattern,
+    dataset: Option<QueryDataset>,
+) -> Result<Statement, Error> {
+    let query = Query::Select { dataset, pattern, base_iri: None };
+    Statement::new(
+        Prefixes::builder().build()?,
+        query.to_string().into(),
+    )
+}
+
+/// The graph patterns of the given query or of the `DELETE`/`INSERT`
+/// operations of the given update, the ones that can contain `SERVICE`
+/// clauses, each with the dataset that it is evaluated against.
+pub(super) fn statement_patterns<'a>(
+    query: &'a mut Option<Query>,
+    update: &'a mut Option<Update>,
+) -> Vec<(Option<QueryDataset>, &'a mut GraphPattern)> {
+    let mut patterns = Vec::new();
+    if let Some(query) = query {
+        patterns.push((query.dataset().cloned(), query_pattern_mut(query)));
+    }
+    if let Some(update) = update {
+        for operation in update.operations.iter_mut() {
+            if let GraphUpdateOperation::DeleteInsert { pattern, using, .. } = operation {
+                patterns.push((using.clone(), pattern.as_mut()));
+            }
+        }
+    }
+    patterns
+}
+
+/// The first `SERVICE` clause of the given patterns, the one to evaluate
+/// next since the clauses that it may be joined with come before it.
+pub(super) fn first_service_call(
+    patterns: &mut [(Option<QueryDataset>, &mut GraphPattern)],
+    statement: &Statement,
+) -> Result<Option<ServiceCall>, Error> {
+    for (dataset, pattern) in patterns.iter_mut() {
+        let mut call = None;
+        for_each_service(pattern, None, true, &mut |service, context| {
+            if call.is_none() {
+                call = Some(ServiceCall::of(
+                    service,
+                    context,
+                    dataset.as_ref(),
+                    statement,
+                )?);
+            }
+            Ok(())
+        })?;
+        if call.is_some() {
+            return Ok(call);
+        }
+    }
+    Ok(None)
+}
+
+/// Replace the first `SERVICE` clause of the given patterns, see
+/// [`first_service_call`], with the given pattern.
+pub(super) fn replace_first_service(
+    patterns: &mut [(Option<QueryDataset>, &mut GraphPattern)],
+    replacement: GraphPattern,
+) -> Result<(), Error> {
+    let mut replacement = Some(replacement);
+    for (_, pattern) in patterns.iter_mut() {
+        for_each_service(pattern, None, true, &mut |service, _| {
+            if let Some(replacement) = replacement.take() {
+                *service = replacement;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// A `VALUES` block with the given solutions, each repeated as often as its
+/// multiplicity.
+///
+/// Blank nodes cannot occur in a `VALUES` block, and would not join with
+/// the blank nodes of another store anyway, so they are rejected.
+pub(super) fn values(call: &ServiceCall, solutions: &SolutionSet) -> Result<GraphPattern, Error> {
+    let variables = variables(call, solutions)?;
+    let mut bindings = Vec::with_capacity(solutions.total_multiplicity());
+    for solution in solutions.iter() {
+        let row = solution
+            .values()
+            .iter()
+            .map(|value| {
+                let Some(value) = value else {
+                    return Ok(None);
+                };
+                match ground_term(value)? {
+                    Some(term) => Ok(Some(term)),
+                    None => {
+                        Err(Error::UnsupportedByStore {
+                            store:     "Federation".to_string(),
+                            feature:   format!("blank node {value} in the solutions of SERVICE"),
+                            statement: format!("SERVICE <{}> {{ {} }}", call.endpoint, call.query),
+                        })
+                    },
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        bindings.extend(std::iter::repeat_n(row, solution.multiplicity()));
+    }
+    Ok(GraphPattern::Values { variables, bindings })
+}
+
+/// The result of a `SERVICE SILENT` clause whose endpoint failed: a single
+/// solution without any bindings.
+pub(super) fn empty_solution() -> GraphPattern {
+    GraphPattern::Values { variables: Vec::new(), bindings: vec![Vec::new()] }
+}
+
+/// The result of a `SERVICE` clause that is joined with a pattern without
+/// solutions: no solutions at all.
+pub(super) fn no_solutions() -> GraphPattern {
+    GraphPattern::Values { variables: Vec::new(), bindings: Vec::new() }
+}
+
+fn variables(call: &ServiceCall, solutions: &SolutionSet) -> Result<Vec<Variable>, Error> {
+    solutions
+        .variables()
+        .iter()
+        .map(|variable| {
+            Variable::new(variable.as_str()).map_err(|err| {
+                Error::InvalidSPARQLResults(format!(
+                    "invalid variable {variable} from <{}>: {err}",
+                    call.endpoint
+                ))
+            })
+        })
+        .collect()
+}
+
+/// The given value as a term of a `VALUES` block, or `None` for a blank node.
+fn ground_term(value: &Literal) -> Result<Option<GroundTerm>, Error> {
+    Ok(match oxrdf::Term::try_from(&value.as_term())? {
+        oxrdf::Term::NamedNode(node) => Some(GroundTerm::NamedNode(node)),
+        oxrdf::Term::Literal(literal) => Some(GroundTerm::Literal(literal)),
+        _ => None,
+    })
+}
+
+/// Call the given function for each `SERVICE` clause in the given pattern,
+/// including the ones in `EXISTS` and `NOT EXISTS` expressions, but not for
+/// the ones nested in another `SERVICE` clause which are left to the
+/// endpoint of the outer one.
+///
+/// The function is also given the pattern that the clause is joined with,
+/// if it is the right-hand side of a join and `bind_join` holds. That is not
+/// the case within a `GRAPH` clause, whose patterns would have to be
+/// evaluated against the named graph.
+fn for_each_service<F>(
+    pattern: &mut GraphPattern,
+    context: Option<&GraphPattern>,
+    bind_join: bool,
+    f: &mut F,
+) -> Result<(), Error>
+where
+    F: FnMut(&mut GraphPattern, Option<&GraphPattern>) -> Result<(), Error>,
+{
+    match pattern {
+        GraphPattern::Service { .. } => f(pattern, context),
+        GraphPattern::Bgp { .. } | GraphPattern::Path { .. } | GraphPattern::Values { .. } => {
+            Ok(())
+        },
+        GraphPattern::Join { left, right } => {
+            for_each_service(left, None, bind_join, f)?;
+            for_each_service(right, bind_join.then_some(&**left), bind_join, f)
+        },
+        GraphPattern::Union { left, right } | GraphPattern::Minus { left, right } => {
+            for_each_service(left, None, bind_join, f)?;
+            for_each_service(right, None, bind_join, f)
+        },
+        GraphPattern::LeftJoin { left, right, expression } => {
+            for_each_service(left, None, bind_join, f)?;
+            for_each_service(right, bind_join.then_some(&**left), bind_join, f)?;
+            if let Some(expression) = expression {
+                for_each_service_in_expression(expression, bind_join, f)?;
+            }
+            Ok(())
+        },
+        GraphPattern::Filter { expr, inner } => {
+            for_each_service_in_expression(expr, bind_join, f)?;
+            for_each_service(inner, None, bind_join, f)
+        },
+        GraphPattern::Extend { inner, expression, .. } => {
+            for_each_service(inner, None, bind_join, f)?;
+            for_each_service_in_expression(expression, bind_join, f)
+        },
+        GraphPattern::OrderBy { inner, expression } => {
+            for_each_service(inner, None, bind_join, f)?;
+            for order in expression {
+                match order {
+                    OrderExpression::Asc(expression) | OrderExpression::Desc(expression) => {
+                        for_each_service_in_expression(expression, bind_join, f)?
+                    },
+                }
+            }
+            Ok(())
+        },
+        GraphPattern::Group { inner, aggregates, .. } => {
+            for_each_service(inner, None, bind_join, f)?;
+            for (_, aggregate) in aggregates {
+                if let AggregateExpression::FunctionCall { expr, .. } = aggregate {
+                    for_each_service_in_expression(expr, bind_join, f)?;
+                }
+            }
+            Ok(())
+        },
+        GraphPattern::Graph { inner, .. } => for_each_service(inner, None, false, f),
+        GraphPattern::Project { inner, .. } |
+        GraphPattern::Distinct { inner } |
+        GraphPattern::Reduced { inner } |
+        GraphPattern::Slice { inner, .. } => for_each_service(inner, None, bind_join, f),
+        // Such as lateral joins when the "sep-0006" feature of spargebra is enabled
+        #[allow(unreachable_patterns)]
+        _ => Ok(()),
+    }
+}
+
+fn for_each_service_in_expression<F>(
+    expression: &mut Expression,
+    bind_join: bool,
+    f: &mut F,
+) -> Result<(), Error>
+where
+    F: FnMut(&mut GraphPattern, Option<&GraphPattern>) -> Result<(), Error>,
+{
+    match expression {
+        Expression::Exists(pattern) => for_each_service(pattern, None, bind_join, f),
+        Expression::Or(left, right) |
+        Expression::And(left, right) |
+        Expression::Equal(left, right) |
+        Expression::SameTerm(left, right) |
+        Expression::Greater(left, right) |
+        Expression::GreaterOrEqual(left, right) |
+        Expression::Less(left, right) |
+        Expression::LessOrEqual(left, right) |
+        Expression::Add(left, right) |
+        Expression::Subtract(left, right) |
+        Expression::Multiply(left, right) |
+        Expression::Divide(left, right) => {
+            for_each_service_in_expression(left, bind_join, f)?;
+            for_each_service_in_expression(right, bind_join, f)
+        },
+        Expression::UnaryPlus(expression) |
+        Expression::UnaryMinus(expression) |
+        Expression::Not(expression) => for_each_service_in_expression(expression, bind_join, f),
+        Expression::If(condition, then, otherwise) => {
+            for_each_service_in_expression(condition, bind_join, f)?;
+            for_each_service_in_expression(then, bind_join, f)?;
+            for_each_service_in_expression(otherwise, bind_join, f)
+        },
+        Expression::In(expression, list) => {
+            for_each_service_in_expression(expression, bind_join, f)?;
+            for expression in list {
+                for_each_service_in_expression(expression, bind_join, f)?;
+            }
+            Ok(())
+        },
+        Expression::Coalesce(list) | Expression::FunctionCall(_, list) => {
+            for expression in list {
+                for_each_service_in_expression(expression, bind_join, f)?;
+            }
+            Ok(())
+        },
+        Expression::NamedNode(_) |
+        Expression::Literal(_) |
+        Expression::Variable(_) |
+        Expression::Bound(_) => Ok(()),
+    }
+}
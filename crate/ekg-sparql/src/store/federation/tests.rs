@@ -0,0 +1,335 @@
+#![cfg(all(test, not(target_family = "wasm"), feature = "oxigraph-support"))]
+
+use {
+    crate::{
+        Federation,
+        OxigraphStore,
+        Prefixes,
+        SPARQLClient,
+        SolutionSet,
+        Statement,
+        TripleStore,
+        client::mock_server::{MockResponse, MockServer},
+    },
+    ekg_identifier::Namespace,
+    ekg_metadata::{APPLICATION_SPARQL_RESULTS_JSON, TEXT_TURTLE},
+    std::sync::Arc,
+};
+
+const PEOPLE: &str = r#"
+@prefix ex: <https://example.com/> .
+
+ex:alice a ex:Person ; ex:country ex:nl .
+ex:bob a ex:Person ; ex:country ex:be .
+ex:carol a ex:Person ; ex:country ex:fr .
+"#;
+
+const REFERENCE_DATA: &str = r#"
+@prefix ex: <https://example.com/> .
+
+ex:nl ex:label "Netherlands"@en .
+ex:be ex:label "Belgium"@en .
+"#;
+
+/// The response of the remote endpoint, with the labels of the countries in
+/// Dutch
+const REMOTE_RESPONSE: &str = r#"{
+  "head": { "vars": [ "country", "dutch" ] },
+  "results": {
+    "bindings": [
+      {
+        "country": { "type": "uri", "value": "https://example.com/nl" },
+        "dutch": { "type": "literal", "value": "Nederland", "xml:lang": "nl" }
+      },
+      {
+        "country": { "type": "uri", "value": "https://example.com/fr" },
+        "dutch": { "type": "literal", "value": "Frankrijk", "xml:lang": "nl" }
+      }
+    ]
+  }
+}"#;
+
+const REFERENCE_ENDPOINT: &str = "https://example.com/reference/sparql";
+
+fn statement(sparql: &str) -> Result<Statement, ekg_error::Error> {
+    let namespace = Namespace::declare(
+        "ex:",
+        iri_string::types::IriReferenceString::try_from("https://example.com/")
+            .unwrap()
+            .into(),
+    )?;
+    Statement::new(
+        Prefixes::builder().declare(&namespace).build()?,
+        sparql.into(),
+    )
+}
+
+fn store(data: &str) -> Result<OxigraphStore, ekg_error::Error> {
+    let store = OxigraphStore::new()?;
+    store.import_data(None, &TEXT_TURTLE, data.as_bytes())?;
+    Ok(store)
+}
+
+/// A federation of the local store with the people, the in-process
+/// reference data store and the given remote endpoint
+async fn federation(server: &MockServer) -> Result<Federation, ekg_error::Error> {
+    let remote = SPARQLClient::new(
+        hyper_util::rt::TokioExecutor::new(),
+        server.endpoint.as_ref(),
+        None,
+    )
+    .await?;
+    Federation::new(Arc::new(store(PEOPLE)?))
+        .with_endpoint(
+            REFERENCE_ENDPOINT,
+            Arc::new(store(REFERENCE_DATA)?),
+        )?
+        .with_endpoint(server.endpoint.as_str(), Arc::new(remote))
+}
+
+/// The values, in Turtle syntax, of the given variable in all solutions
+fn column(solutions: &SolutionSet, variable: &str) -> Vec<String> {
+    solutions
+        .iter()
+        .map(|solution| {
+            solution
+                .get(variable)
+                .map(|value| value.display_turtle().to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[test_log::test(tokio::test)]
+async fn test_federation_service() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            REMOTE_RESPONSE,
+        )
+    })
+    .await;
+    let federation = federation(&server).await?;
+
+    let solutions = federation
+        .select(&statement(&format!(
+            r#"
+            SELECT ?person ?english ?dutch
+            WHERE {{
+                ?person a ex:Person ; ex:country ?country .
+                OPTIONAL {{ SERVICE <{REFERENCE_ENDPOINT}> {{ ?country ex:label ?english }} }}
+                SERVICE <{}> {{ ?country ex:label ?dutch }}
+            }}
+            ORDER BY ?person
+            "#,
+            server.endpoint
+        ))?)
+        .await?;
+
+    assert_eq!(column(&solutions, "person"), [
+        "<https://example.com/alice>",
+        "<https://example.com/carol>"
+    ]);
+    assert_eq!(column(&solutions, "english"), [
+        "\"Netherlands\"@en",
+        ""
+    ]);
+    assert_eq!(column(&solutions, "dutch"), [
+        "\"Nederland\"@nl",
+        "\"Frankrijk\"@nl"
+    ]);
+
+    // Only the pattern of the SERVICE clause is sent to the remote endpoint,
+    // along with the countries of the people
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let (parameter, query) = &requests[0].form()[0];
+    assert_eq!(parameter, "query");
+    assert!(query.contains("<https://example.com/label>"));
+    assert!(!query.contains("Person"));
+    assert!(query.contains("VALUES"));
+    for country in ["nl", "be", "fr"] {
+        assert!(query.contains(&format!("<https://example.com/{country}>")));
+    }
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_federation_bind_join() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            REMOTE_RESPONSE,
+        )
+    })
+    .await;
+    let federation = federation(&server).await?;
+
+    // Without local bindings to join with, the endpoint is not asked at all
+    let solutions = federation
+        .select(&statement(&format!(
+            r#"
+            SELECT * WHERE {{
+                ?person a ex:Robot ; ex:country ?country .
+                SERVICE <{}> {{ ?country ex:label ?dutch }}
+            }}
+            "#,
+            server.endpoint
+        ))?)
+        .await?;
+    assert!(solutions.is_empty());
+    assert!(server.requests().is_empty());
+
+    // Without shared variables, the SERVICE clause is sent without bindings
+    let solutions = federation
+        .select(&statement(&format!(
+            r#"
+            SELECT * WHERE {{
+                ?person a ex:Person .
+                SERVICE <{}> {{ ?country ex:label ?dutch }}
+            }}
+            "#,
+            server.endpoint
+        ))?)
+        .await?;
+    assert_eq!(solutions.len(), 6);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let (_, query) = &requests[0].form()[0];
+    assert!(!query.contains("VALUES"));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_federation_unknown_endpoint() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "")).await;
+    let federation = federation(&server).await?;
+
+    let result = federation
+        .select(&statement(
+            r#"
+            SELECT * WHERE {
+                ?person a ex:Person
+                SERVICE <https://example.com/unknown> { ?person ?p ?o }
+            }
+            "#,
+        )?)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnknownSPARQLEndpoint { endpoint, .. })
+            if endpoint == "https://example.com/unknown"
+    ));
+
+    // A SERVICE SILENT clause that fails has a single empty solution
+    let solutions = federation
+        .select(&statement(
+            r#"
+            SELECT * WHERE {
+                ?person a ex:Person
+                SERVICE SILENT <https://example.com/unknown> { ?person ?p ?o }
+            }
+            "#,
+        )?)
+        .await?;
+    assert_eq!(solutions.len(), 3);
+    assert!(column(&solutions, "o").iter().all(String::is_empty));
+
+    let result = federation
+        .select(&statement(
+            "SELECT * WHERE { ?person a ex:Person SERVICE ?endpoint { ?person ?p ?o } }",
+        )?)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnsupportedByStore { .. })
+    ));
+    assert!(server.requests().is_empty());
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_federation_update() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| MockResponse::ok("text/plain", "")).await;
+    let federation = federation(&server).await?;
+
+    federation
+        .update(&statement(&format!(
+            r#"
+            INSERT {{ ?person ex:countryName ?label }}
+            WHERE {{
+                ?person ex:country ?country .
+                SERVICE <{REFERENCE_ENDPOINT}> {{ ?country ex:label ?label }}
+            }}
+            "#
+        ))?)
+        .await?;
+
+    assert_eq!(federation.local().count_triples(None).await?, 8);
+    assert!(
+        federation
+            .ask(&statement(
+                r#"ASK { ex:bob ex:countryName "Belgium"@en }"#
+            )?)
+            .await?
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_federation_select_union() -> Result<(), ekg_error::Error> {
+    let server = MockServer::start(|_| {
+        MockResponse::ok(
+            APPLICATION_SPARQL_RESULTS_JSON.as_ref(),
+            r#"{
+              "head": { "vars": [ "label", "source" ] },
+              "results": { "bindings": [
+                {
+                  "label": { "type": "literal", "value": "Nederland", "xml:lang": "nl" },
+                  "source": { "type": "literal", "value": "remote" }
+                }
+              ] }
+            }"#,
+        )
+    })
+    .await;
+    let federation = federation(&server).await?;
+    assert_eq!(federation.endpoints().collect::<Vec<_>>(), [
+        server.endpoint.as_str(),
+        REFERENCE_ENDPOINT
+    ]);
+
+    let statement = statement("SELECT ?country ?label WHERE { ?country ex:label ?label }")?;
+    let solutions = federation
+        .select_union(
+            &[REFERENCE_ENDPOINT, server.endpoint.as_str()],
+            &statement,
+        )
+        .await?;
+    assert_eq!(solutions.variables(), [
+        "country", "label", "source"
+    ]);
+    assert_eq!(solutions.len(), 3);
+    let mut labels = column(&solutions, "label");
+    labels.sort();
+    assert_eq!(labels, [
+        "\"Belgium\"@en",
+        "\"Nederland\"@nl",
+        "\"Netherlands\"@en"
+    ]);
+    assert_eq!(
+        column(&solutions, "source")
+            .iter()
+            .filter(|source| !source.is_empty())
+            .count(),
+        1
+    );
+
+    assert!(matches!(
+        federation
+            .select_union(&["https://example.com/unknown"], &statement)
+            .await,
+        Err(ekg_error::Error::UnknownSPARQLEndpoint { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use {
+    super::service::{
+        ServiceCall,
+        empty_solution,
+        first_service_call,
+        no_solutions,
+        replace_first_service,
+        statement_patterns,
+        values,
+    },
+    crate::{
+        ParsedStatement,
+        Quad,
+        Solution,
+        SolutionSet,
+        statement::Statement,
+        store::{StoreFuture, TripleStore},
+    },
+    ekg_error::Error,
+    ekg_util::log::LOG_TARGET_SPARQL,
+    spargebra::algebra::GraphPattern,
+    std::{borrow::Cow, collections::BTreeMap, sync::Arc, task::Poll},
+};
+
+/// A [`TripleStore`] that spans a local store, such as the embedded RDFox
+/// database or an [`OxigraphStore`](crate::OxigraphStore), and a registry of
+/// named endpoints, such as remote SPARQL endpoints via a
+/// [`SPARQLClient`](crate::SPARQLClient).
+///
+/// Statements are evaluated by the local store after their `SERVICE`
+/// clauses have been evaluated by the registered endpoint with the IRI of
+/// the clause. The solutions of each clause are passed on to the local
+/// store as a `VALUES` block, so `SERVICE` clauses with a variable instead
+/// of an IRI are not supported. A `SERVICE SILENT` clause whose endpoint is
+/// unknown or fails has a single empty solution, as per the SPARQL 1.1
+/// Federated Query specification.
+///
+/// The clauses are evaluated one after another. A clause that is joined
+/// with the pattern before it, as in
+/// `?person ex:country ?country SERVICE <...> { ?country ex:label ?label }`,
+/// only asks the endpoint for the solutions that match the distinct local
+/// bindings of the shared variables (`?country`), which are sent along as a
+/// `VALUES` block: a bind join. The endpoint is not asked at all if there
+/// are no such bindings. That keeps the responses small but the request
+/// grows with the number of distinct bindings, and the pattern before the
+/// clause is evaluated twice by the local store. Any other clause, such as
+/// one that shares no variables with the pattern before it or one within a
+/// `GRAPH` clause, is sent without bindings and returns all its solutions,
+/// so add a `LIMIT` within the clause if that can be a lot.
+///
+/// [`Federation::select_union`] and [`Federation::construct_union`] fan a
+/// statement out to several endpoints instead. Register the local store as
+/// an endpoint as well to include it.
+#[derive(Clone)]
+pub struct Federation {
+    local:     Arc<dyn TripleStore>,
+    endpoints: BTreeMap<String, Arc<dyn TripleStore>>,
+}
+
+impl Federation {
+    pub fn new(local: Arc<dyn TripleStore>) -> Self { Self { local, endpoints: BTreeMap::new() } }
+
+    /// Register the given store as the endpoint with the given IRI, replacing
+    /// any store that was registered with that IRI before.
+    pub fn with_endpoint(mut self, iri: &str, store: Arc<dyn TripleStore>) -> Result<Self, Error> {
+        let iri = oxrdf::NamedNode::new(iri).map_err(|_| Error::InvalidIri(iri.to_string()))?;
+        self.endpoints.insert(iri.into_string(), store);
+        Ok(self)
+    }
+
+    /// The store that evaluates everything but the `SERVICE` clauses
+    pub fn local(&self) -> &dyn TripleStore { self.local.as_ref() }
+
+    /// The store registered as the endpoint with the given IRI
+    pub fn endpoint(&self, iri: &str) -> Option<&dyn TripleStore> {
+        self.endpoints.get(iri).map(Arc::as_ref)
+    }
+
+    /// The IRIs of the registered endpoints, in alphabetical order
+    pub fn endpoints(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.keys().map(String::as_str)
+    }
+
+    /// Evaluate the given SPARQL SELECT query at each of the given endpoints
+    /// concurrently and return the union of their solutions. The variables
+    /// are those of the first endpoint followed by the ones that only
+    /// occur in the solutions of the other endpoints.
+    pub async fn select_union(
+        &self,
+        endpoints: &[&str],
+        statement: &Statement,
+    ) -> Result<SolutionSet, Error> {
+        let stores = self.registered(endpoints, statement)?;
+        let solution_sets =
+            try_join_all(stores.iter().map(|store| store.select(statement)).collect()).await?;
+        Ok(union(solution_sets))
+    }
+
+    /// Evaluate the given SPARQL CONSTRUCT or DESCRIBE query at each of the
+    /// given endpoints concurrently and return all resulting triples.
+    pub async fn construct_union(
+        &self,
+        endpoints: &[&str],
+        statement: &Statement,
+    ) -> Result<Vec<Quad>, Error> {
+        let stores = self.registered(endpoints, statement)?;
+        let quads = try_join_all(
+            stores
+                .iter()
+                .map(|store| store.construct(statement))
+                .collect(),
+        )
+        .await?;
+        Ok(quads.into_iter().flatten().collect())
+    }
+
+    fn registered(
+        &self,
+        endpoints: &[&str],
+        statement: &Statement,
+    ) -> Result<Vec<&dyn TripleStore>, Error> {
+        endpoints
+            .iter()
+            .map(|iri| {
+                self.endpoint(iri).ok_or_else(|| {
+                    Error::UnknownSPARQLEndpoint {
+                        endpoint:  iri.to_string(),
+                        statement: statement.to_string(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// The given statement with its `SERVICE` clauses replaced by their
+    /// solutions, or `None` if it has no `SERVICE` clauses.
+    async fn resolve_services(&self, statement: &Statement) -> Result<Option<Statement>, Error> {
+        let parsed = ParsedStatement::parse(statement, None)?;
+        if !parsed.analysis().uses_service {
+            return Ok(None);
+        }
+        let ParsedStatement { mut query_statement, mut update_statement, .. } = parsed;
+        // One at a time, so that a clause can be joined with the solutions of
+        // the ones before it
+        let mut count = 0;
+        loop {
+            let Some(call) = first_service_call(
+                &mut statement_patterns(&mut query_statement, &mut update_statement),
+                statement,
+            )?
+            else {
+                break;
+            };
+            let solutions = self.evaluate_service(&call, statement).await?;
+            replace_first_service(
+                &mut statement_patterns(&mut query_statement, &mut update_statement),
+                solutions,
+            )?;
+            count += 1;
+        }
+        let text = match (query_statement, update_statement) {
+            (Some(query), _) => query.to_string(),
+            (None, Some(update)) => update.to_string(),
+            (None, None) => return Ok(None),
+        };
+        tracing::debug!(
+            target: LOG_TARGET_SPARQL,
+            "Evaluated {count} SERVICE clause(s):\n{text}"
+        );
+        Ok(Some(Statement {
+            prefixes:   statement.prefixes.clone(),
+            text:       format!("{}\n{text}", statement.prefixes),
+            params:     statement.params.clone(),
+            directives: statement.directives.clone(),
+        }))
+    }
+
+    async fn evaluate_service(
+        &self,
+        call: &ServiceCall,
+        statement: &Statement,
+    ) -> Result<GraphPattern, Error> {
+        let Some(store) = self.endpoint(call.endpoint.as_str()) else {
+            if call.silent {
+                return Ok(empty_solution());
+            }
+            return Err(Error::UnknownSPARQLEndpoint {
+                endpoint:  call.endpoint.clone(),
+                statement: statement.to_string(),
+            });
+        };
+        let query = match &call.bindings {
+            Some(bindings) => {
+                let bindings = self.local.select(bindings).await?;
+                if bindings.is_empty() {
+                    return Ok(no_solutions());
+                }
+                Cow::Owned(call.query_with(&bindings)?)
+            },
+            None => Cow::Borrowed(&call.query),
+        };
+        let result = store.select(&query).await;
+        match result.and_then(|solutions| values(call, &solutions)) {
+            Ok(values) => Ok(values),
+            Err(error) if call.silent => {
+                tracing::warn!(
+                    target: LOG_TARGET_SPARQL,
+                    "Ignoring error of SERVICE SILENT <{}>: {error}",
+                    call.endpoint
+                );
+                Ok(empty_solution())
+            },
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// Evaluates the `SERVICE` clauses at the registered endpoints and the rest
+/// of the statement in the local store.
+impl TripleStore for Federation {
+    fn select<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, SolutionSet> {
+        Box::pin(async move {
+            let resolved = self.resolve_services(statement).await?;
+            self.local
+                .select(resolved.as_ref().unwrap_or(statement))
+                .await
+        })
+    }
+
+    fn ask<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, bool> {
+        Box::pin(async move {
+            let resolved = self.resolve_services(statement).await?;
+            self.local.ask(resolved.as_ref().unwrap_or(statement)).await
+        })
+    }
+
+    fn construct<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, Vec<Quad>> {
+        Box::pin(async move {
+            let resolved = self.resolve_services(statement).await?;
+            self.local
+                .construct(resolved.as_ref().unwrap_or(statement))
+                .await
+        })
+    }
+
+    fn update<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            let resolved = self.resolve_services(statement).await?;
+            self.local
+                .update(resolved.as_ref().unwrap_or(statement))
+                .await
+        })
+    }
+}
+
+/// The union of the given solution sets, see [`Federation::select_union`].
+fn union(solution_sets: Vec<SolutionSet>) -> SolutionSet {
+    let mut variables = Vec::<String>::new();
+    for variable in solution_sets.iter().flat_map(SolutionSet::variables) {
+        if !variables.contains(variable) {
+            variables.push(variable.clone());
+        }
+    }
+    let mut solutions = Vec::new();
+    for solution_set in &solution_sets {
+        let indexes = variables
+            .iter()
+            .map(|variable| solution_set.variable_index(variable))
+            .collect::<Vec<_>>();
+        solutions.extend(solution_set.iter().map(|solution| {
+            Solution::new(
+                indexes
+                    .iter()
+                    .map(|index| index.and_then(|index| solution.value(index)).cloned())
+                    .collect(),
+            )
+            .with_multiplicity(solution.multiplicity())
+        }));
+    }
+    SolutionSet::new(variables, solutions)
+}
+
+/// Poll the given futures concurrently and return their results in the
+/// same order, or the first error.
+async fn try_join_all<T>(futures: Vec<StoreFuture<'_, T>>) -> Result<Vec<T>, Error> {
+    let mut futures = futures.into_iter().map(Some).collect::<Vec<_>>();
+    let mut results = futures.iter().map(|_| None).collect::<Vec<_>>();
+    std::future::poll_fn(|cx| {
+        let mut pending = false;
+        for (slot, result) in futures.iter_mut().zip(results.iter_mut()) {
+            let Some(future) = slot else { continue };
+            match future.as_mut().poll(cx) {
+                Poll::Ready(Ok(value)) => {
+                    *result = Some(value);
+                    *slot = None;
+                },
+                Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    })
+    .await?;
+    Ok(results.into_iter().flatten().collect())
+}
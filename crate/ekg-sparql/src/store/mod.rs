@@ -0,0 +1,166 @@
+//! A common interface to the triple stores that SPARQL statements can be
+//! evaluated against: the embedded RDFox database, a remote SPARQL endpoint
+//! via [`SPARQLClient`](crate::SPARQLClient) and the embedded Oxigraph
+//! store, as well as a [`Federation`] of them.
+//!
+//! Code that only needs to select, ask, construct, update, import, export
+//! or count can take a `&dyn TripleStore` and run unchanged against any of
+//! them, for instance
+//! [`ClassReport::number_of_individuals`](crate::ClassReport::number_of_individuals).
+use {
+    crate::{
+        Prefixes,
+        Quad,
+        SolutionSet,
+        client::quads::QuadDecoder,
+        statement::Statement,
+    },
+    ekg_error::Error,
+    ekg_metadata::Graph,
+    mime::Mime,
+    std::{future::Future, pin::Pin},
+};
+pub use federation::Federation;
+#[cfg(feature = "oxigraph-support")]
+pub use oxigraph::OxigraphStore;
+
+mod client;
+mod federation;
+#[cfg(feature = "oxigraph-support")]
+mod oxigraph;
+#[cfg(feature = "_rdfox")]
+mod rdfox;
+
+/// The future returned by the methods of [`TripleStore`].
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+/// A store of RDF quads that SPARQL statements can be evaluated against.
+///
+/// Only [`TripleStore::select`], [`TripleStore::ask`],
+/// [`TripleStore::construct`] and [`TripleStore::update`] have to be
+/// implemented, the other methods are built on top of them. The methods are
+/// asynchronous so that remote stores can be used, stores that evaluate
+/// statements in-process simply return a future that is ready.
+pub trait TripleStore: Send + Sync {
+    /// Evaluate the given SPARQL SELECT query and return its solutions.
+    fn select<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, SolutionSet>;
+
+    /// Evaluate the given SPARQL ASK query.
+    fn ask<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, bool>;
+
+    /// Evaluate the given SPARQL CONSTRUCT or DESCRIBE query and return the
+    /// resulting triples, as quads without a graph.
+    fn construct<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, Vec<Quad>>;
+
+    /// Execute the given SPARQL update statement.
+    fn update<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, ()>;
+
+    /// Add the given RDF, in the given format (Turtle, N-Triples, N-Quads or
+    /// TriG), to the given graph, or to the graphs of its quads if `graph`
+    /// is `None`. Returns the number of quads that were read.
+    fn import<'a>(
+        &'a self,
+        graph: Option<&'a Graph>,
+        content_type: &'a Mime,
+        data: &'a [u8],
+    ) -> StoreFuture<'a, usize> {
+        Box::pin(async move {
+            let (statement, count) = insert_data_statement(graph, content_type, data)?;
+            self.update(&statement).await?;
+            Ok(count)
+        })
+    }
+
+    /// All triples in the given graph, or in the default graph if `graph` is
+    /// `None`.
+    fn export<'a>(&'a self, graph: Option<&'a Graph>) -> StoreFuture<'a, Vec<Quad>> {
+        Box::pin(async move {
+            let pattern = graph_pattern(graph, "?s ?p ?o")?;
+            let statement = Statement::new(
+                Prefixes::builder().build()?,
+                format!("CONSTRUCT {{ ?s ?p ?o }} WHERE {{ {pattern} }}").into(),
+            )?;
+            let quads = self.construct(&statement).await?;
+            Ok(quads
+                .into_iter()
+                .map(|(subject, predicate, object, _)| (subject, predicate, object, graph.cloned()))
+                .collect())
+        })
+    }
+
+    /// The number of solutions of the given SPARQL SELECT query, counting
+    /// each solution as often as its multiplicity.
+    fn count<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, usize> {
+        Box::pin(async move { Ok(self.select(statement).await?.total_multiplicity()) })
+    }
+
+    /// The number of triples in the given graph, or in the default graph if
+    /// `graph` is `None`.
+    fn count_triples<'a>(&'a self, graph: Option<&'a Graph>) -> StoreFuture<'a, usize> {
+        Box::pin(async move {
+            let pattern = graph_pattern(graph, "?s ?p ?o")?;
+            let statement = Statement::new(
+                Prefixes::builder().build()?,
+                format!("SELECT (COUNT(*) AS ?count) WHERE {{ {pattern} }}").into(),
+            )?;
+            let solutions = self.select(&statement).await?;
+            let count = solutions
+                .iter()
+                .next()
+                .and_then(|solution| solution.value(0))
+                .and_then(|count| {
+                    count
+                        .as_unsigned_long()
+                        .or_else(|| {
+                            count
+                                .as_signed_long()
+                                .and_then(|count| count.try_into().ok())
+                        })
+                        .or_else(|| count.as_str().and_then(|count| count.parse().ok()))
+                })
+                .ok_or_else(|| {
+                    Error::InvalidSPARQLResults("COUNT(*) did not return a number".to_string())
+                })?;
+            Ok(count as usize)
+        })
+    }
+}
+
+/// The given triple pattern, within a `GRAPH` clause if a graph is given.
+fn graph_pattern(graph: Option<&Graph>, triples: &str) -> Result<String, Error> {
+    Ok(match graph {
+        Some(graph) => format!("GRAPH <{}> {{ {triples} }}", graph.as_iri()?),
+        None => triples.to_string(),
+    })
+}
+
+/// Parse the given RDF and turn it into an `INSERT DATA` statement, returns
+/// the statement and the number of quads.
+fn insert_data_statement(
+    graph: Option<&Graph>,
+    content_type: &Mime,
+    data: &[u8],
+) -> Result<(Statement, usize), Error> {
+    let mut decoder = QuadDecoder::for_content_type(Some(content_type.as_ref()))?;
+    decoder.push(data);
+    decoder.end();
+    let target = graph.map(|graph| graph.as_iri()).transpose()?;
+    let mut sparql = String::from("INSERT DATA {\n");
+    let mut count = 0_usize;
+    while let Some(quad) = decoder.next_quad()? {
+        let triple = oxrdf::Triple::from(quad.clone());
+        match (&target, &quad.graph_name) {
+            (Some(iri), _) => sparql.push_str(&format!("  GRAPH <{iri}> {{ {triple} }}\n")),
+            (None, oxrdf::GraphName::NamedNode(iri)) => {
+                sparql.push_str(&format!("  GRAPH {iri} {{ {triple} }}\n"))
+            },
+            (None, _) => sparql.push_str(&format!("  {triple} .\n")),
+        }
+        count += 1;
+    }
+    sparql.push('}');
+    Ok((
+        Statement::new(Prefixes::builder().build()?, sparql.into())?,
+        count,
+    ))
+}
@@ -0,0 +1,5 @@
+pub use this::OxigraphStore;
+
+#[cfg(test)]
+mod tests;
+mod this;
@@ -0,0 +1,342 @@
+#![cfg(all(test, not(target_family = "wasm"), feature = "oxigraph-support"))]
+
+use {
+    crate::{
+        ClassReport,
+        FactDomain,
+        OxigraphStore,
+        Parameters,
+        Prefixes,
+        SolutionSet,
+        Statement,
+        TripleStore,
+    },
+    ekg_identifier::Namespace,
+    ekg_metadata::{
+        Class,
+        Graph,
+        consts::{APPLICATION_N_TRIPLES, APPLICATION_SPARQL_RESULTS_JSON, TEXT_TURTLE},
+    },
+};
+
+const DATA: &str = r#"
+@prefix ex: <https://example.com/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:alice a ex:Person ; ex:name "Alice" ; ex:age 42 ; ex:knows ex:bob .
+ex:bob a ex:Person ; ex:name "Bob" ; ex:age 17 ; ex:knows ex:carol .
+ex:carol a ex:Person ; ex:age 30 .
+ex:Person rdfs:subClassOf ex:Agent .
+"#;
+
+fn namespace() -> Namespace {
+    Namespace::declare(
+        "ex:",
+        iri_string::types::IriReferenceString::try_from("https://example.com/")
+            .unwrap()
+            .into(),
+    )
+    .unwrap()
+}
+
+fn statement(sparql: &str) -> Result<Statement, ekg_error::Error> {
+    Statement::new(
+        Prefixes::builder().declare(&namespace()).build()?,
+        sparql.into(),
+    )
+}
+
+fn store() -> Result<OxigraphStore, ekg_error::Error> {
+    let store = OxigraphStore::new()?;
+    assert_eq!(
+        store.import_data(None, &TEXT_TURTLE, DATA.as_bytes())?,
+        11
+    );
+    Ok(store)
+}
+
+/// The values, in Turtle syntax, of the given variable in all solutions
+fn column(solutions: &SolutionSet, variable: &str) -> Vec<String> {
+    let index = solutions.variable_index(variable).unwrap();
+    solutions
+        .iter()
+        .map(|solution| {
+            solution
+                .value(index)
+                .map(|value| value.display_turtle().to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[test_log::test]
+fn test_oxigraph_store_select() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    let solutions = store.evaluate_select(
+        &statement(
+            r#"
+        SELECT ?person ?name
+        WHERE {
+            ?person a ex:Person ; ex:age ?age .
+            OPTIONAL { ?person ex:name ?name }
+            FILTER(?age >= 18)
+        }
+        ORDER BY DESC(?age)
+        "#,
+        )?,
+        Parameters::empty()?,
+    )?;
+    assert_eq!(solutions.variables(), ["person", "name"]);
+    assert_eq!(column(&solutions, "person"), [
+        "<https://example.com/alice>",
+        "<https://example.com/carol>"
+    ]);
+    assert_eq!(column(&solutions, "name"), ["\"Alice\"", ""]);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_aggregate() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    let solutions = store.evaluate_select(
+        &statement(
+            r#"
+        SELECT (COUNT(?person) AS ?count) (SUM(?age) AS ?total) (MAX(?age) AS ?oldest)
+        WHERE { ?person ex:age ?age }
+        "#,
+        )?,
+        Parameters::empty()?,
+    )?;
+    assert_eq!(solutions.len(), 1);
+    assert_eq!(column(&solutions, "count"), ["3"]);
+    assert_eq!(column(&solutions, "total"), ["89"]);
+    assert_eq!(column(&solutions, "oldest"), ["42"]);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_ask_and_construct() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    assert!(store.evaluate_ask(
+        &statement("ASK { ex:alice ex:knows ex:bob }")?,
+        Parameters::empty()?
+    )?);
+    assert!(!store.evaluate_ask(
+        &statement("ASK { ex:bob ex:knows ex:alice }")?,
+        Parameters::empty()?
+    )?);
+
+    let quads = store.evaluate_construct(
+        &statement("CONSTRUCT { ?b ex:knownBy ?a } WHERE { ?a ex:knows ?b }")?,
+        Parameters::empty()?,
+    )?;
+    assert_eq!(quads.len(), 2);
+    assert!(quads.iter().all(|(_, predicate, _, graph)| {
+        predicate.display_turtle().to_string() == "<https://example.com/knownBy>" && graph.is_none()
+    }));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_update() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    store.evaluate_update(
+        &statement(
+            r#"
+        INSERT DATA { GRAPH ex:extra { ex:dave a ex:Person } } ;
+        DELETE { ?person ex:age ?age } INSERT { ?person ex:adult true }
+        WHERE { ?person ex:age ?age FILTER(?age >= 18) }
+        "#,
+        )?,
+        Parameters::empty()?,
+    )?;
+    assert_eq!(store.len()?, 12);
+    assert!(store.evaluate_ask(
+        &statement("ASK { GRAPH ex:extra { ex:dave a ex:Person } }")?,
+        Parameters::empty()?
+    )?);
+    assert!(store.evaluate_ask(
+        &statement("ASK { ex:carol ex:adult true }")?,
+        Parameters::empty()?
+    )?);
+    assert!(!store.evaluate_ask(
+        &statement("ASK { ex:alice ex:age ?age }")?,
+        Parameters::empty()?
+    )?);
+
+    store.evaluate_update(&statement("CLEAR DEFAULT")?, Parameters::empty()?)?;
+    assert_eq!(store.len()?, 1);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_unsupported() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    let result = store.evaluate_select(
+        &statement("SELECT * WHERE { SERVICE <https://example.com/sparql> { ?s ?p ?o } }")?,
+        Parameters::empty()?,
+    );
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnsupportedByStore { .. })
+    ));
+    let result = store.evaluate_select(
+        &statement("ASK { ?s ?p ?o }")?,
+        Parameters::empty()?,
+    );
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnexpectedSPARQLStatementType { .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_import_data_from_file() -> Result<(), ekg_error::Error> {
+    let store = OxigraphStore::new()?;
+    let people = Graph::declare(namespace(), "people");
+    let extra = Graph::declare(namespace(), "extra");
+    let turtle = std::env::temp_dir().join(format!(
+        "ekg-sparql-oxigraph-{}.ttl",
+        std::process::id()
+    ));
+    let ntriples = turtle.with_extension("nt");
+    std::fs::write(&turtle, DATA)?;
+    std::fs::write(
+        &ntriples,
+        "<https://example.com/dave> <https://example.com/knows> <https://example.com/alice> .\n",
+    )?;
+    let result = store
+        .import_data_from_file(&turtle, &people)
+        .and_then(|_| store.import_data_from_file(&ntriples, &extra));
+    std::fs::remove_file(&turtle)?;
+    std::fs::remove_file(&ntriples)?;
+    result?;
+
+    assert_eq!(store.len()?, 12);
+    assert!(store.evaluate_ask(
+        &statement("ASK { GRAPH ex:people { ex:alice ex:knows ex:bob } }")?,
+        Parameters::empty()?
+    )?);
+    assert!(store.evaluate_ask(
+        &statement("ASK { GRAPH ex:extra { ex:dave ex:knows ex:alice } }")?,
+        Parameters::empty()?
+    )?);
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_consume() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    let query = statement("SELECT ?person ?age WHERE { ?person ex:age ?age } ORDER BY ?age")?;
+    let mut ages = Vec::new();
+    let count = store.consume(&query, Parameters::empty()?, 10, |row| {
+        assert_eq!(row.variables(), ["person", "age"]);
+        ages.push(row.get("age").unwrap().display_turtle().to_string());
+        Ok::<(), ekg_error::Error>(())
+    })?;
+    assert_eq!(count, 3);
+    assert_eq!(ages, ["17", "30", "42"]);
+
+    let result = store.consume(&query, Parameters::empty()?, 2, |_| Ok(()));
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::ExceededMaximumNumberOfRows { maxrow: 2, .. })
+    ));
+    let limited = statement("# ekg:max-rows: 1\nSELECT ?person WHERE { ?person ex:age ?age }")?;
+    let result = store.consume(&limited, Parameters::empty()?, 10, |_| Ok(()));
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::ExceededMaximumNumberOfRows { maxrow: 1, .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_fact_domain() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    let query = statement("ASK { ex:alice ex:knows ex:bob }")?;
+    let mut parameters = Parameters::empty()?;
+    parameters.fact_domain(&FactDomain::ASSERTED)?;
+    assert!(store.evaluate_ask(&query, parameters)?);
+
+    let mut parameters = Parameters::empty()?;
+    parameters.fact_domain(&FactDomain::INFERRED)?;
+    assert!(matches!(
+        store.evaluate_ask(&query, parameters),
+        Err(ekg_error::Error::UnsupportedByStore { .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test]
+fn test_oxigraph_store_evaluate_to_stream() -> Result<(), ekg_error::Error> {
+    let store = store()?;
+    let json = store.evaluate_to_stream(
+        Vec::new(),
+        &statement("SELECT ?name WHERE { ex:alice ex:name ?name }")?,
+        Parameters::empty()?,
+        &APPLICATION_SPARQL_RESULTS_JSON,
+    )?;
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains(r#""vars":["name"]"#), "{json}");
+    assert!(json.contains(r#""value":"Alice""#), "{json}");
+
+    let ntriples = store.evaluate_to_stream(
+        Vec::new(),
+        &statement("CONSTRUCT { ?b ex:knownBy ?a } WHERE { ?a ex:knows ?b }")?,
+        Parameters::empty()?,
+        &APPLICATION_N_TRIPLES,
+    )?;
+    let ntriples = String::from_utf8(ntriples).unwrap();
+    assert_eq!(ntriples.lines().count(), 2);
+    assert!(ntriples.contains(
+        "<https://example.com/bob> <https://example.com/knownBy> <https://example.com/alice> ."
+    ));
+
+    let result = store.evaluate_to_stream(
+        Vec::new(),
+        &statement("ASK { ?s ?p ?o }")?,
+        Parameters::empty()?,
+        &APPLICATION_N_TRIPLES,
+    );
+    assert!(matches!(
+        result,
+        Err(ekg_error::Error::UnsupportedByStore { .. })
+    ));
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn test_oxigraph_store_as_triple_store() -> Result<(), ekg_error::Error> {
+    let oxigraph = OxigraphStore::new()?;
+    let store: &dyn TripleStore = &oxigraph;
+    let graph = Graph::declare(namespace(), "people");
+    assert_eq!(
+        store
+            .import(Some(&graph), &TEXT_TURTLE, DATA.as_bytes())
+            .await?,
+        11
+    );
+    assert_eq!(store.count_triples(None).await?, 0);
+    assert_eq!(store.count_triples(Some(&graph)).await?, 11);
+
+    let quads = store.export(Some(&graph)).await?;
+    assert_eq!(quads.len(), 11);
+    assert!(quads.iter().all(|(.., g)| {
+        g.as_ref().map(|g| g.as_iri().unwrap()) == Some(graph.as_iri().unwrap())
+    }));
+
+    let people = Class::declare(namespace(), "Person");
+    assert_eq!(
+        ClassReport(&people).number_of_individuals(store).await?,
+        3
+    );
+    assert_eq!(
+        ClassReport(&people)
+            .number_of_individuals_in_graph(store, &graph)
+            .await?,
+        3
+    );
+    Ok(())
+}
@@ -0,0 +1,431 @@
+use {
+    crate::{
+        FactDomain,
+        Parameters,
+        ParsedStatement,
+        Quad,
+        Solution,
+        SolutionSet,
+        client::quads::{QuadDecoder, quad_from_oxrdf, term_from_oxrdf},
+        statement::Statement,
+        store::{StoreFuture, TripleStore},
+    },
+    ekg_error::Error,
+    ekg_metadata::{Graph, Literal, Term},
+    ekg_util::log::LOG_TARGET_DATABASE,
+    mime::Mime,
+    oxigraph::{
+        io::{RdfFormat, RdfParser, RdfSerializer},
+        model::{GraphName, NamedNode},
+        sparql::{
+            QueryEvaluationError,
+            QueryResults,
+            QuerySolution,
+            QuerySolutionIter,
+            SparqlEvaluator,
+            results::{QueryResultsFormat, QueryResultsSerializer},
+        },
+        store::Store,
+    },
+    std::{io::Write, path::Path, sync::Arc},
+};
+
+/// An RDF store that is embedded in the process and evaluates SPARQL
+/// statements with [Oxigraph](https://github.com/oxigraph/oxigraph), so that
+/// neither a database server nor the RDFox library is needed, for instance
+/// for tests.
+///
+/// The store keeps its data in memory, or on disk when opened with
+/// `OxigraphStore::open` (feature `oxigraph-rocksdb`).
+///
+/// Statements are evaluated with [`Parameters`] like they are by RDFox,
+/// completed with the parameters in the comments of the statement. Oxigraph
+/// does not reason, all its facts are explicit ones, so the `derived` fact
+/// domain is not supported and the other fact domains give the same
+/// results. Parameters that configure an RDFox server or data store are
+/// ignored.
+///
+/// `SERVICE` clauses are not evaluated, use a
+/// [`Federation`](crate::Federation) for that. Clones share the same data.
+#[derive(Clone)]
+pub struct OxigraphStore {
+    store: Store,
+}
+
+impl OxigraphStore {
+    /// A new, empty, store that keeps its data in memory
+    pub fn new() -> Result<Self, Error> { Ok(Self { store: Store::new()? }) }
+
+    /// Open the store in the given directory, or create it there if it does
+    /// not exist yet. Only one `OxigraphStore` at a time can open a
+    /// directory.
+    #[cfg(feature = "oxigraph-rocksdb")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(Self { store: Store::open(path)? })
+    }
+
+    /// The number of quads in the store
+    pub fn len(&self) -> Result<usize, Error> { Ok(self.store.len()?) }
+
+    pub fn is_empty(&self) -> Result<bool, Error> { Ok(self.store.is_empty()?) }
+
+    /// Import the given Turtle or N-Triples file into the given graph.
+    pub fn import_data_from_file<P>(&self, file: P, graph: &Graph) -> Result<(), Error>
+    where P: AsRef<Path> {
+        let file = file.as_ref();
+        // N-Triples is a subset of Turtle
+        let parser =
+            RdfParser::from_format(RdfFormat::Turtle).with_default_graph(named_node(graph)?);
+        self.store.load_from_reader(
+            parser,
+            std::io::BufReader::new(std::fs::File::open(file)?),
+        )?;
+        tracing::debug!(
+            target: LOG_TARGET_DATABASE,
+            "Imported file {} into {}",
+            file.display(),
+            graph.as_display_iri()
+        );
+        Ok(())
+    }
+
+    /// Add the given RDF, in the given format, to the given graph, or to
+    /// the graphs of its quads if `graph` is `None`. Returns the number of
+    /// quads that were read.
+    pub fn import_data(
+        &self,
+        graph: Option<&Graph>,
+        content_type: &Mime,
+        data: &[u8],
+    ) -> Result<usize, Error> {
+        let target = graph.map(named_node).transpose()?;
+        let mut decoder = QuadDecoder::for_content_type(Some(content_type.as_ref()))?;
+        decoder.push(data);
+        decoder.end();
+        let mut quads = Vec::new();
+        while let Some(mut quad) = decoder.next_quad()? {
+            if let Some(target) = &target {
+                quad.graph_name = GraphName::NamedNode(target.clone());
+            }
+            quads.push(quad);
+        }
+        let count = quads.len();
+        self.store.extend(quads)?;
+        Ok(count)
+    }
+
+    /// Evaluate the given SPARQL SELECT query and call `f` with each of its
+    /// solutions, the values of which are [`Literal`]s. Returns the number
+    /// of solutions.
+    ///
+    /// Like `Cursor::consume` of the RDFox module it fails when there are
+    /// more than `max_row` solutions, the `ekg:max-rows` directive of the
+    /// statement can only lower that maximum.
+    pub fn consume<T, E>(
+        &self,
+        statement: &Statement,
+        parameters: Parameters,
+        max_row: usize,
+        mut f: T,
+    ) -> Result<usize, E>
+    where
+        T: FnMut(&Solution) -> Result<(), E>,
+        E: From<Error>,
+    {
+        let max_row = statement
+            .directives
+            .max_rows
+            .map_or(max_row, |directive| directive.min(max_row));
+        let (variables, solutions) = self.solutions(statement, parameters)?;
+        let mut count = 0_usize;
+        for solution in solutions {
+            if count == max_row {
+                return Err(Error::ExceededMaximumNumberOfRows {
+                    maxrow: max_row,
+                    query:  statement.to_string(),
+                }
+                .into());
+            }
+            f(&self::solution(statement, &variables, solution)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Evaluate the given SPARQL SELECT query.
+    pub fn evaluate_select(
+        &self,
+        statement: &Statement,
+        parameters: Parameters,
+    ) -> Result<SolutionSet, Error> {
+        let (variables, solutions) = self.solutions(statement, parameters)?;
+        Ok(SolutionSet::new(
+            variables.to_vec(),
+            solutions
+                .map(|solution| self::solution(statement, &variables, solution))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
+    /// Evaluate the given SPARQL ASK query.
+    pub fn evaluate_ask(
+        &self,
+        statement: &Statement,
+        parameters: Parameters,
+    ) -> Result<bool, Error> {
+        let parsed = parse_expecting(
+            statement,
+            "ASK",
+            ParsedStatement::is_ask_statement,
+        )?;
+        check_parameters(statement, parameters)?;
+        match self.query(statement, &parsed)? {
+            QueryResults::Boolean(result) => Ok(result),
+            _ => Err(unexpected_results(statement)),
+        }
+    }
+
+    /// Evaluate the given SPARQL CONSTRUCT or DESCRIBE query.
+    pub fn evaluate_construct(
+        &self,
+        statement: &Statement,
+        parameters: Parameters,
+    ) -> Result<Vec<Quad>, Error> {
+        let parsed = parse_expecting(statement, "CONSTRUCT or DESCRIBE", |parsed| {
+            parsed.is_construct_statement() || parsed.is_describe_statement()
+        })?;
+        check_parameters(statement, parameters)?;
+        let QueryResults::Graph(triples) = self.query(statement, &parsed)? else {
+            return Err(unexpected_results(statement));
+        };
+        triples
+            .map(|triple| {
+                let triple = triple.map_err(|error| evaluation_error(statement, error))?;
+                quad_from_oxrdf(
+                    triple.in_graph(GraphName::DefaultGraph),
+                    &statement.prefixes,
+                )
+            })
+            .collect()
+    }
+
+    /// Execute the given SPARQL update statement, all its operations are
+    /// applied in one transaction.
+    pub fn evaluate_update(
+        &self,
+        statement: &Statement,
+        parameters: Parameters,
+    ) -> Result<(), Error> {
+        let parsed = parse_expecting(
+            statement,
+            "update",
+            ParsedStatement::is_update_statement,
+        )?;
+        check_parameters(statement, parameters)?;
+        SparqlEvaluator::new()
+            .for_update(parsed.update_algebra().clone())
+            .on_store(&self.store)
+            .execute()?;
+        Ok(())
+    }
+
+    /// Evaluate the given query and write its results to the given writer in
+    /// the given format, without collecting them first: a SPARQL results
+    /// format (such as `application/sparql-results+json`) for SELECT and ASK
+    /// queries, an RDF format (such as `application/n-triples`) for
+    /// CONSTRUCT and DESCRIBE queries. Returns the writer.
+    pub fn evaluate_to_stream<W: Write>(
+        &self,
+        writer: W,
+        statement: &Statement,
+        parameters: Parameters,
+        mime_type: &Mime,
+    ) -> Result<W, Error> {
+        let parsed = parse_expecting(statement, "query", |parsed| {
+            !parsed.is_update_statement()
+        })?;
+        check_parameters(statement, parameters)?;
+        let unsupported_format = || {
+            Error::UnsupportedByStore {
+                store:     STORE.to_string(),
+                feature:   format!("{mime_type} results for this query"),
+                statement: statement.to_string(),
+            }
+        };
+        let results_format = || {
+            QueryResultsFormat::from_media_type(mime_type.essence_str())
+                .map(QueryResultsSerializer::from_format)
+                .ok_or_else(unsupported_format)
+        };
+        Ok(match self.query(statement, &parsed)? {
+            QueryResults::Solutions(solutions) => {
+                let mut serializer = results_format()?
+                    .serialize_solutions_to_writer(writer, solutions.variables().to_vec())?;
+                for solution in solutions {
+                    let solution = solution.map_err(|error| evaluation_error(statement, error))?;
+                    serializer.serialize(&solution)?;
+                }
+                serializer.finish()?
+            },
+            QueryResults::Boolean(result) => {
+                results_format()?.serialize_boolean_to_writer(writer, result)?
+            },
+            QueryResults::Graph(triples) => {
+                let format = RdfFormat::from_media_type(mime_type.essence_str())
+                    .ok_or_else(unsupported_format)?;
+                let mut serializer = RdfSerializer::from_format(format).for_writer(writer);
+                for triple in triples {
+                    let triple = triple.map_err(|error| evaluation_error(statement, error))?;
+                    serializer.serialize_triple(&triple)?;
+                }
+                serializer.finish()?
+            },
+        })
+    }
+
+    /// The variables and the (lazily evaluated) solutions of the given
+    /// SELECT query, see [`solution`].
+    fn solutions(
+        &self,
+        statement: &Statement,
+        parameters: Parameters,
+    ) -> Result<(Arc<[String]>, QuerySolutionIter<'static>), Error> {
+        let parsed = parse_expecting(
+            statement,
+            "SELECT",
+            ParsedStatement::is_select_statement,
+        )?;
+        check_parameters(statement, parameters)?;
+        let QueryResults::Solutions(solutions) = self.query(statement, &parsed)? else {
+            return Err(unexpected_results(statement));
+        };
+        let variables = solutions
+            .variables()
+            .iter()
+            .map(|variable| variable.as_str().to_string())
+            .collect();
+        Ok((variables, solutions))
+    }
+
+    fn query(
+        &self,
+        statement: &Statement,
+        parsed: &ParsedStatement,
+    ) -> Result<QueryResults<'static>, Error> {
+        SparqlEvaluator::new()
+            .for_query(parsed.query_algebra().clone())
+            .on_store(&self.store)
+            .execute()
+            .map_err(|error| evaluation_error(statement, error))
+    }
+}
+
+/// Evaluates statements in-process with the
+/// [`Statement::default_parameters`], the returned futures are ready
+/// immediately.
+impl TripleStore for OxigraphStore {
+    fn select<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, SolutionSet> {
+        Box::pin(async move { self.evaluate_select(statement, statement.default_parameters()?) })
+    }
+
+    fn ask<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, bool> {
+        Box::pin(async move { self.evaluate_ask(statement, statement.default_parameters()?) })
+    }
+
+    fn construct<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, Vec<Quad>> {
+        Box::pin(async move { self.evaluate_construct(statement, statement.default_parameters()?) })
+    }
+
+    fn update<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, ()> {
+        Box::pin(async move { self.evaluate_update(statement, statement.default_parameters()?) })
+    }
+
+    fn import<'a>(
+        &'a self,
+        graph: Option<&'a Graph>,
+        content_type: &'a Mime,
+        data: &'a [u8],
+    ) -> StoreFuture<'a, usize> {
+        Box::pin(async move { self.import_data(graph, content_type, data) })
+    }
+}
+
+const STORE: &str = "OxigraphStore";
+
+/// Parse the given statement and check that it is of the expected type.
+fn parse_expecting(
+    statement: &Statement,
+    expected: &str,
+    is_expected: fn(&ParsedStatement) -> bool,
+) -> Result<ParsedStatement, Error> {
+    let parsed = ParsedStatement::parse(statement, None)?;
+    if !is_expected(&parsed) {
+        return Err(Error::UnexpectedSPARQLStatementType {
+            expected:  expected.to_string(),
+            statement: statement.to_string(),
+        });
+    }
+    Ok(parsed)
+}
+
+/// Complete the given parameters with the ones in the comments of the
+/// statement and check that Oxigraph can evaluate the statement with them.
+fn check_parameters(statement: &Statement, parameters: Parameters) -> Result<(), Error> {
+    let parameters = statement.complete_parameters(parameters)?;
+    let fact_domain = parameters.get_string("fact-domain", FactDomain::ALL.as_str())?;
+    if fact_domain == FactDomain::INFERRED.as_str() {
+        return Err(Error::UnsupportedByStore {
+            store:     STORE.to_string(),
+            feature:   format!("the {fact_domain} fact domain"),
+            statement: statement.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// `SERVICE` clauses are reported as unsupported rather than as a failed
+/// evaluation, like the other stores do.
+fn evaluation_error(statement: &Statement, error: QueryEvaluationError) -> Error {
+    match error {
+        QueryEvaluationError::UnsupportedService(service) => {
+            Error::UnsupportedByStore {
+                store:     STORE.to_string(),
+                feature:   format!("SERVICE {service}"),
+                statement: statement.to_string(),
+            }
+        },
+        error => error.into(),
+    }
+}
+
+fn unexpected_results(statement: &Statement) -> Error {
+    Error::InvalidSPARQLResults(format!(
+        "Oxigraph returned results of another query type for:\n{statement}"
+    ))
+}
+
+fn named_node(graph: &Graph) -> Result<NamedNode, Error> {
+    let iri = graph.as_iri()?;
+    NamedNode::new(iri.as_str()).map_err(|_| Error::InvalidIri(iri.to_string()))
+}
+
+/// The given Oxigraph solution as a [`Solution`] of [`Literal`]s.
+fn solution(
+    statement: &Statement,
+    variables: &Arc<[String]>,
+    solution: Result<QuerySolution, QueryEvaluationError>,
+) -> Result<Solution, Error> {
+    let solution = solution.map_err(|error| evaluation_error(statement, error))?;
+    let values = solution
+        .values()
+        .iter()
+        .map(|value| value.clone().map(literal).transpose())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Solution::new(values).with_variables(variables.clone()))
+}
+
+fn literal(term: oxrdf::Term) -> Result<Literal, Error> {
+    Ok(match term_from_oxrdf(term)? {
+        Term::Iri(literal) | Term::Literal(literal) | Term::BlankNode(literal) => literal,
+    })
+}
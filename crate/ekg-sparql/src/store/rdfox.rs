@@ -0,0 +1,91 @@
+use {
+    crate::{
+        Quad,
+        SolutionSet,
+        rdfox::{DataStoreConnection, Transaction},
+        statement::Statement,
+        store::{StoreFuture, TripleStore},
+    },
+    ekg_error::Error,
+    std::sync::Arc,
+};
+
+/// The maximum number of rows of a cursor, same as
+/// [`crate::rdfox::Cursor::count`].
+const MAX_ROWS: usize = 1_000_000_000;
+
+/// Evaluates statements in the embedded RDFox data store, each in its own
+/// transaction and with the [`Statement::default_parameters`].
+impl TripleStore for Arc<DataStoreConnection> {
+    fn select<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, SolutionSet> {
+        Box::pin(async move { solutions(self, statement) })
+    }
+
+    fn ask<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, bool> {
+        // RDFox returns one (empty) answer if the pattern matches
+        Box::pin(async move { Ok(!solutions(self, statement)?.is_empty()) })
+    }
+
+    fn construct<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, Vec<Quad>> {
+        Box::pin(async move {
+            // RDFox returns the constructed triples as answers with three columns
+            solutions(self, statement)?
+                .iter()
+                .map(|solution| {
+                    match solution.values() {
+                        [Some(subject), Some(predicate), Some(object)] => {
+                            Ok((
+                                subject.as_term(),
+                                predicate.as_term(),
+                                object.as_term(),
+                                None,
+                            ))
+                        },
+                        _ => {
+                            Err(Error::InvalidSPARQLResults(
+                                "a constructed triple does not have three terms".to_string(),
+                            ))
+                        },
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn update<'a>(&'a self, statement: &'a Statement) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            Transaction::begin_read_write_do(self, |_tx| {
+                self.evaluate_update(statement, statement.default_parameters()?)?;
+                Ok(())
+            })
+        })
+    }
+}
+
+/// Evaluate the given query in a read-only transaction and collect its
+/// answers, each with its multiplicity.
+fn solutions(
+    connection: &Arc<DataStoreConnection>,
+    statement: &Statement,
+) -> Result<SolutionSet, Error> {
+    let mut variables = Vec::new();
+    let mut solutions = Vec::new();
+    Transaction::begin_read_only(connection)?.execute_and_rollback(|tx| {
+        statement
+            .cursor(connection, statement.default_parameters()?)?
+            .consume_opened(
+                &tx,
+                MAX_ROWS,
+                |opened| {
+                    // Before consuming the rows, so that an empty result has variables too
+                    variables = opened.answer_variable_names()?;
+                    Ok::<(), Error>(())
+                },
+                |row| {
+                    solutions.push(row.solution()?);
+                    Ok(())
+                },
+            )
+    })?;
+    Ok(SolutionSet::new(variables, solutions))
+}
@@ -1,10 +1,12 @@
-#![cfg(test)]
+#![cfg(all(test, not(target_family = "wasm")))]
 
-use crate::{prefixes::Prefixes, statement::Statement, SPARQLClient};
+use crate::{SPARQLClient, prefixes::Prefixes, statement::Statement};
 
 #[test_log::test(tokio::test)]
+#[ignore = "needs network access to dbpedia.org"]
 async fn test_sparql_client() -> Result<(), ekg_error::Error> {
     let sparql_client = SPARQLClient::new(
+        hyper_util::rt::TokioExecutor::new(),
         iri_string::types::IriReferenceString::try_from("https://dbpedia.org/sparql")?.as_ref(),
         None,
     )
@@ -41,10 +41,11 @@ fn test_define_data_store() -> Result<Arc<DataStore>, ekg_error::Error> {
     #[cfg(not(feature = "rdfox-7-0a"))]
     let data_store_params = Parameters::empty()
         .unwrap()
-        .persist_datastore(PersistenceMode::Off)
+        .persist_datastore(&PersistenceMode::Off)
         .unwrap()
         .persist_roles(PersistenceMode::Off)
-        .unwrap();
+        .unwrap()
+        .clone();
     let result = DataStore::declare_with_parameters("example", data_store_params);
     assert!(result.is_ok());
     tracing::info!("test_define_data_store end");
@@ -62,10 +63,11 @@ fn test_create_server() -> Result<Arc<Server>, ekg_error::Error> {
     #[cfg(not(feature = "rdfox-7-0a"))]
     let server_params = Parameters::empty()
         .unwrap()
-        .persist_datastore(PersistenceMode::Off)
+        .persist_datastore(&PersistenceMode::Off)
         .unwrap()
         .persist_roles(PersistenceMode::Off)
-        .unwrap();
+        .unwrap()
+        .clone();
 
     Server::start_with_parameters(RoleCreds::default(), Some(server_params)).map_err(|e| {
         tracing::error!("Server creation failed: {}", e);
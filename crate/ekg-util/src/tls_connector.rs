@@ -5,7 +5,7 @@
 //! crate because we do not want the ekg-util crate to have a dependency on any
 //! AWS SDK crates.
 use {
-    hyper::client::connect::HttpConnector,
+    hyper_util::client::legacy::connect::HttpConnector,
     hyper_rustls::{HttpsConnector, HttpsConnectorBuilder},
 };
 
@@ -24,3 +24,20 @@ pub async fn create() -> Result<HttpsConnector<HttpConnector>, ekg_error::Error>
 
     Ok(rustls_connector)
 }
+
+/// Create a connector that uses TLS for `https` URIs but also accepts plain
+/// `http` URIs, for instance for a SPARQL endpoint running on `localhost`.
+pub async fn create_https_or_http() -> Result<HttpsConnector<HttpConnector>, ekg_error::Error> {
+    tracing::debug!("Attempting to create a TLS 1.3 connector that also allows plain HTTP:");
+
+    let tls_config = crate::tls_config::create().await?;
+
+    let rustls_connector = HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config)
+        .https_or_http()
+        .enable_http1()
+        .enable_http2()
+        .build();
+
+    Ok(rustls_connector)
+}